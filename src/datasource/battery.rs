@@ -0,0 +1,107 @@
+use std::{thread, time::Duration};
+
+use anyhow::{anyhow, Result};
+use log::{debug, info};
+
+use crate::{
+    datasource::file_path::*,
+    model::settings_queue::{submit_settings_change, SettingsChange},
+    utils::{
+        constants::strategy,
+        event_loop::PollHandler,
+        file_operate::{check_read, read_file},
+        numeric::parse_i64,
+    },
+};
+
+// 只有放电时才算使用电池，充电、充满和插电未充电都不启用省电
+const STATUS_DISCHARGING: &str = "Discharging";
+
+/// 电池省电处理器 - 放电且电量低于阈值时切换到省电模式，充电或电量回升后恢复
+pub struct BatteryHandler {
+    threshold: i64,
+    low_battery: bool,
+}
+
+impl BatteryHandler {
+    pub fn new(threshold: u32) -> Result<Self> {
+        info!("{BATTERY_THREAD} Start");
+
+        // 检查电池节点，缺少任一节点时禁用电池省电
+        let mut capacity_ok = false;
+        let mut status_ok = false;
+        let capacity_status = check_read(BATTERY_CAPACITY, &mut capacity_ok);
+        info!("{BATTERY_CAPACITY}: {capacity_status}");
+        let status_status = check_read(BATTERY_STATUS, &mut status_ok);
+        info!("{BATTERY_STATUS}: {status_status}");
+        if !capacity_ok || !status_ok {
+            return Err(anyhow!("Battery nodes not found, battery saver disabled"));
+        }
+
+        info!("Battery saver: powersave below {threshold}% while discharging");
+        Ok(Self {
+            threshold: threshold as i64,
+            low_battery: false,
+        })
+    }
+
+    // 读取电量和充电状态
+    fn read_battery() -> Result<(i64, bool)> {
+        let capacity = read_file(BATTERY_CAPACITY, 16)?;
+        let capacity = parse_i64(capacity.trim())
+            .ok_or_else(|| anyhow!("Failed to parse battery capacity: {}", capacity.trim()))?;
+        let status = read_file(BATTERY_STATUS, 32)?;
+        Ok((capacity, status.trim() == STATUS_DISCHARGING))
+    }
+
+    // 低电量状态变化时提交设置变更
+    fn update(&mut self) {
+        let (capacity, discharging) = match Self::read_battery() {
+            Ok(battery) => battery,
+            Err(e) => {
+                debug!("Failed to read battery state: {e}");
+                return;
+            }
+        };
+
+        let low_battery = discharging && capacity < self.threshold;
+        if low_battery != self.low_battery {
+            info!(
+                "Battery saver {} (capacity {capacity}%, {})",
+                if low_battery { "enabled" } else { "disabled" },
+                if discharging {
+                    "discharging"
+                } else {
+                    "charging"
+                }
+            );
+            self.low_battery = low_battery;
+            submit_settings_change(SettingsChange::LowBattery(low_battery));
+        }
+    }
+}
+
+impl PollHandler for BatteryHandler {
+    fn name(&self) -> &'static str {
+        BATTERY_THREAD
+    }
+
+    fn tick_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(strategy::BATTERY_POLL_MS))
+    }
+
+    fn on_tick(&mut self) -> Result<()> {
+        self.update();
+        Ok(())
+    }
+}
+
+/// 定期读取电池状态，低电量放电时切换到省电模式
+pub fn monitor_battery(threshold: u32) -> Result<()> {
+    let mut handler = BatteryHandler::new(threshold)?;
+
+    loop {
+        handler.update();
+        thread::sleep(Duration::from_millis(strategy::BATTERY_POLL_MS));
+    }
+}
@@ -1,59 +1,906 @@
-use crate::datasource::file_path::CONFIG_TOML_FILE;
-use crate::model::gpu::GPU;
+use crate::datasource::{
+    config_report::{struct_fields, ConfigReport},
+    file_path::{resolve_path, PathKey},
+    freq_table::validate_table_voltages,
+    load_monitor::{
+        set_load_calibration, set_load_fusion, set_load_source_order, set_precise_load_node,
+        set_read_cache_ttl, LoadFusion, PreciseLoadNode,
+    },
+};
+use crate::model::{
+    app_profile::{set_app_profiles, AppProfile},
+    effective_config::set_log_effective_config,
+    frequency_manager::{FreqMismatchAction, OppResetValue, V2SnapTiebreak},
+    frequency_strategy::{GovernorType, ModeParams, UpscaleMode},
+    governor_mode::GovernorMode,
+    governor_state::apply_config_enabled,
+    gpu::GPU,
+    heartbeat::set_heartbeat,
+    load_curve::LoadCurve,
+    package_pattern::PackagePattern,
+    snapshot::set_status_file,
+    telemetry::set_telemetry,
+    volt_offset::VoltOffset,
+};
+use crate::utils::{
+    constants::strategy,
+    event_loop::{set_event_loop_mode, EventLoopMode},
+    log_rotation::set_log_rotation,
+    logger::{set_log_format, set_max_log_rate, LogFormat},
+    numeric::{set_numeric_format, NumericFormat},
+    thread_sched::{set_thread_sched, ThreadSched},
+};
 use anyhow::Result;
-use log::info;
+use log::{info, warn};
 use serde::Deserialize;
-use std::fs;
+use std::{collections::HashMap, fs};
+use toml::{Table, Value};
 
 #[derive(Deserialize)]
 pub struct Config {
     global: Global,
-    powersave: ModeParams,
-    balance: ModeParams,
-    performance: ModeParams,
-    fast: ModeParams,
+    #[serde(default)]
+    powersave: Option<ModeParams>,
+    #[serde(default)]
+    balance: Option<ModeParams>,
+    #[serde(default)]
+    performance: Option<ModeParams>,
+    #[serde(default)]
+    fast: Option<ModeParams>,
+    #[serde(default)]
+    curve_point: Vec<CurvePoint>,
+    #[serde(default)]
+    volt_offset_freq: Vec<VoltOffsetFreq>,
+    #[serde(default)]
+    app_profile: Vec<AppProfileConfig>,
+    #[serde(default)]
+    load_calibration: HashMap<String, f64>,
+    #[serde(default)]
+    thread_sched: HashMap<String, ThreadSchedConfig>,
 }
 
 #[derive(Deserialize)]
 pub struct Global {
     mode: String,
     idle_threshold: i32,
+    #[serde(default)]
+    startup_opp_index: i64,
+    #[serde(default)]
+    startup_use_current_freq: bool,
+    #[serde(default)]
+    governor: Option<String>,
+    #[serde(default)]
+    background_max_freq: i64,
+    #[serde(default)]
+    min_write_interval_us: u64,
+    #[serde(default)]
+    numeric_format: Option<String>,
+    #[serde(default)]
+    touch_boost_freq: i64,
+    #[serde(default = "default_touch_boost_ms")]
+    touch_boost_ms: u64,
+    #[serde(default)]
+    upscale_mode: Option<String>,
+    #[serde(default)]
+    event_loop: Option<String>,
+    #[serde(default)]
+    frame_drop_boost_freq: i64,
+    #[serde(default = "default_frame_drop_boost_ms")]
+    frame_drop_boost_ms: u64,
+    #[serde(default = "default_max_margin")]
+    max_margin: i64,
+    #[serde(default)]
+    sustained_opp_set: Vec<i64>,
+    #[serde(default = "default_sustained_window_ms")]
+    sustained_window_ms: u64,
+    #[serde(default)]
+    downscale_delay_ms: u64,
+    #[serde(default)]
+    idle_grace_ms: u64,
+    #[serde(default)]
+    fixed_freq: i64,
+    #[serde(default)]
+    fixed_freq_hold_idle: bool,
+    #[serde(default)]
+    allowed_freqs: Vec<i64>,
+    #[serde(default)]
+    blocked_freqs: Vec<i64>,
+    #[serde(default)]
+    volt_offset: i64,
+    #[serde(default)]
+    min_volt: i64,
+    #[serde(default = "default_max_undervolt")]
+    max_undervolt: i64,
+    #[serde(default = "default_volt_tolerance")]
+    volt_tolerance: i64,
+    #[serde(default)]
+    reject_unknown_volt: bool,
+    #[serde(default)]
+    max_step_up: u32,
+    #[serde(default)]
+    max_step_down: u32,
+    #[serde(default)]
+    predictive_steps: u32,
+    #[serde(default = "default_predictive_samples")]
+    predictive_samples: usize,
+    #[serde(default)]
+    log_effective_config: bool,
+    #[serde(default)]
+    min_effective_load: i32,
+    #[serde(default)]
+    load_window_ms: u64,
+    #[serde(default)]
+    time_weighted_load: bool,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+    #[serde(default = "default_foreground_poll_ms")]
+    foreground_poll_ms: u64,
+    #[serde(default = "default_foreground_read_timeout_ms")]
+    foreground_read_timeout_ms: u64,
+    #[serde(default)]
+    decision_trace: bool,
+    #[serde(default)]
+    verify_high_opp_index: Option<i64>,
+    #[serde(default = "default_load_stats_window_ms")]
+    load_stats_window_ms: u64,
+    #[serde(default)]
+    precise_load_node: Option<String>,
+    #[serde(default)]
+    load_fusion: Option<String>,
+    #[serde(default)]
+    load_source_order: Vec<String>,
+    #[serde(default)]
+    disabled_load_sources: Vec<String>,
+    #[serde(default)]
+    read_cache_ttl_ms: u64,
+    #[serde(default = "default_frame_budget_ms")]
+    frame_budget_ms: f64,
+    #[serde(default)]
+    on_freq_mismatch: Option<String>,
+    #[serde(default)]
+    sustained_floor_rise_secs: u64,
+    #[serde(default)]
+    sustained_floor_decay_secs: u64,
+    #[serde(default)]
+    status_file: Option<String>,
+    #[serde(default)]
+    telemetry_file: Option<String>,
+    #[serde(default = "default_telemetry_stride")]
+    telemetry_stride: u32,
+    #[serde(default)]
+    heartbeat_file: Option<String>,
+    #[serde(default = "default_heartbeat_interval_ms")]
+    heartbeat_interval_ms: u64,
+    #[serde(default)]
+    thermal_zones: Vec<String>,
+    #[serde(default)]
+    thermal_cap_temp: i32,
+    #[serde(default)]
+    thermal_cap_freq: i64,
+    #[serde(default)]
+    thermal_warn_temp: i32,
+    #[serde(default)]
+    thermal_critical_temp: i32,
+    #[serde(default)]
+    screen_state_node: Option<String>,
+    #[serde(default)]
+    battery_saver_threshold: u32,
+    #[serde(default)]
+    battery_saver_max_freq: i64,
+    #[serde(default)]
+    config_apply_delay_ms: u64,
+    #[serde(default)]
+    log_format: Option<String>,
+    #[serde(default = "default_max_log_lines_per_sec")]
+    max_log_lines_per_sec: u32,
+    #[serde(default = "default_log_max_size_kb")]
+    log_max_size_kb: u64,
+    #[serde(default = "default_log_keep_files")]
+    log_keep_files: u32,
+    #[serde(default)]
+    unmatched_app_profile: Option<String>,
+    #[serde(default)]
+    v2_snap_tiebreak: Option<String>,
+    #[serde(default)]
+    opp_reset_value: Option<String>,
+    #[serde(default = "default_load_ema_alpha")]
+    load_ema_alpha: f64,
+    #[serde(default)]
+    adaptive_sampling_window: usize,
+    #[serde(default = "default_adaptive_variance_threshold")]
+    adaptive_variance_threshold: f64,
+    #[serde(default)]
+    ddr_load_scaling: bool,
+    #[serde(default = "default_ddr_high_load")]
+    ddr_high_load: i32,
+    #[serde(default = "default_ddr_low_load")]
+    ddr_low_load: i32,
+    #[serde(default)]
+    dcs_load_scaling: bool,
+    #[serde(default = "default_dcs_high_load")]
+    dcs_high_load: i32,
+    #[serde(default = "default_dcs_low_load")]
+    dcs_low_load: i32,
+}
+
+fn default_touch_boost_ms() -> u64 {
+    strategy::TOUCH_BOOST_DEFAULT_MS
+}
+
+fn default_frame_drop_boost_ms() -> u64 {
+    strategy::FRAME_DROP_BOOST_DEFAULT_MS
+}
+
+fn default_max_margin() -> i64 {
+    strategy::MAX_MARGIN
+}
+
+fn default_foreground_poll_ms() -> u64 {
+    strategy::FOREGROUND_POLL_DEFAULT_MS
+}
+
+fn default_foreground_read_timeout_ms() -> u64 {
+    strategy::FOREGROUND_READ_TIMEOUT_DEFAULT_MS
+}
+
+fn default_load_stats_window_ms() -> u64 {
+    strategy::LOAD_STATS_WINDOW_DEFAULT_MS
+}
+
+fn default_frame_budget_ms() -> f64 {
+    strategy::FRAME_BUDGET_DEFAULT_MS
+}
+
+fn default_load_ema_alpha() -> f64 {
+    strategy::LOAD_EMA_ALPHA_DEFAULT
+}
+
+fn default_predictive_samples() -> usize {
+    strategy::PREDICTIVE_SAMPLES_DEFAULT
+}
+
+fn default_adaptive_variance_threshold() -> f64 {
+    strategy::ADAPTIVE_VARIANCE_THRESHOLD_DEFAULT
+}
+
+fn default_max_log_lines_per_sec() -> u32 {
+    strategy::MAX_LOG_LINES_PER_SEC_DEFAULT
+}
+
+fn default_max_undervolt() -> i64 {
+    strategy::MAX_UNDERVOLT_DEFAULT
+}
+
+fn default_volt_tolerance() -> i64 {
+    strategy::VOLT_TOLERANCE_DEFAULT
+}
+
+fn default_log_max_size_kb() -> u64 {
+    strategy::LOG_MAX_SIZE_KB_DEFAULT
+}
+
+fn default_log_keep_files() -> u32 {
+    strategy::LOG_KEEP_FILES_DEFAULT
+}
+
+fn default_telemetry_stride() -> u32 {
+    strategy::TELEMETRY_STRIDE_DEFAULT
+}
+
+fn default_ddr_high_load() -> i32 {
+    strategy::DDR_HIGH_LOAD_DEFAULT
+}
+
+fn default_ddr_low_load() -> i32 {
+    strategy::DDR_LOW_LOAD_DEFAULT
+}
+
+fn default_dcs_high_load() -> i32 {
+    strategy::DCS_HIGH_LOAD_DEFAULT
+}
+
+fn default_dcs_low_load() -> i32 {
+    strategy::DCS_LOW_LOAD_DEFAULT
+}
+
+fn default_heartbeat_interval_ms() -> u64 {
+    strategy::HEARTBEAT_INTERVAL_DEFAULT_MS
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_sustained_window_ms() -> u64 {
+    strategy::SUSTAINED_WINDOW_DEFAULT_MS
 }
 
 #[derive(Deserialize)]
-pub struct ModeParams {
-    very_high_load_threshold: i32,
-    margin: i64,
-    down_threshold: i64,
-    aggressive_down: bool,
-    sampling_interval: u64,
+pub struct CurvePoint {
+    load: i32,
+    freq: i64,
 }
 
-pub fn load_config(gpu: &mut GPU) -> Result<()> {
-    let content = fs::read_to_string(CONFIG_TOML_FILE)?;
-    let config: Config = toml::from_str(&content)?;
+#[derive(Deserialize)]
+pub struct VoltOffsetFreq {
+    freq: i64,
+    offset: i64,
+}
 
-    gpu.idle_manager_mut()
-        .set_idle_threshold(config.global.idle_threshold);
+#[derive(Deserialize)]
+pub struct AppProfileConfig {
+    package: String,
+    #[serde(default)]
+    disable_dcs: bool,
+    #[serde(default)]
+    disable_ddr_scaling: bool,
+    #[serde(default)]
+    launch_boost: bool,
+    #[serde(default = "default_launch_boost_ms")]
+    launch_boost_ms: u64,
+    #[serde(default)]
+    min_freq: Option<i64>,
+    #[serde(default)]
+    max_freq: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct ThreadSchedConfig {
+    #[serde(default)]
+    cpu_mask: Option<u64>,
+    #[serde(default)]
+    nice: Option<i32>,
+}
+
+fn default_launch_boost_ms() -> u64 {
+    strategy::LAUNCH_BOOST_DEFAULT_MS
+}
+
+#[derive(Deserialize, Default)]
+struct EarlyConfig {
+    #[serde(default)]
+    global: EarlyGlobal,
+}
+
+#[derive(Deserialize, Default)]
+struct EarlyGlobal {
+    #[serde(default)]
+    pid_file: Option<String>,
+    #[serde(default)]
+    freq_table_source: Option<String>,
+    #[serde(default)]
+    log_format: Option<String>,
+}
 
-    let params = match config.global.mode.as_str() {
+/// 频率表来源
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FreqTableSource {
+    /// 频率表配置文件（默认）
+    File,
+    /// 内核devfreq接口提供的可用频率
+    Devfreq,
+}
+
+impl FreqTableSource {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "file" => Some(Self::File),
+            "devfreq" => Some(Self::Devfreq),
+            _ => None,
+        }
+    }
+}
+
+// 在加载完整配置之前读取全局配置中的早期选项
+fn read_early_global() -> EarlyGlobal {
+    fs::read_to_string(resolve_path(PathKey::ConfigToml))
+        .ok()
+        .and_then(|content| toml::from_str::<EarlyConfig>(&content).ok())
+        .map(|config| config.global)
+        .unwrap_or_default()
+}
+
+/// 在日志初始化之前读取PID文件路径，配置缺失或解析失败时使用默认路径
+pub fn read_pid_file_path() -> String {
+    read_early_global()
+        .pid_file
+        .unwrap_or_else(|| resolve_path(PathKey::PidFile).to_string())
+}
+
+/// 在日志初始化之前读取日志格式，未配置或无效时使用文本格式
+pub fn read_log_format() -> LogFormat {
+    read_early_global()
+        .log_format
+        .as_deref()
+        .and_then(LogFormat::parse)
+        .unwrap_or(LogFormat::Text)
+}
+
+/// 在读取频率表之前读取频率表来源，未配置或无效时使用频率表文件
+pub fn read_freq_table_source() -> FreqTableSource {
+    match read_early_global().freq_table_source.as_deref() {
+        None => FreqTableSource::File,
+        Some(value) => FreqTableSource::parse(value).unwrap_or_else(|| {
+            warn!("Invalid freq_table_source '{value}', using file");
+            FreqTableSource::File
+        }),
+    }
+}
+
+// 按模式名取模式参数，配置文件中有对应的模式段时优先使用，否则使用内置预设
+fn resolve_mode_params(config: &Config, name: &str) -> Option<ModeParams> {
+    let section = match name {
         "powersave" => &config.powersave,
-        "balance" => &config.balance,
+        "balance" | "balanced" => &config.balance,
         "performance" => &config.performance,
         "fast" => &config.fast,
-        _ => {
-            info!("Invalid mode '{}', using balance mode", config.global.mode);
-            &config.balance
+        _ => return None,
+    };
+    section
+        .clone()
+        .or_else(|| GovernorMode::parse(name).map(GovernorMode::params))
+}
+
+// 配置的模式参数，模式无效时使用均衡模式
+fn config_mode_params(config: &Config) -> ModeParams {
+    resolve_mode_params(config, &config.global.mode).unwrap_or_else(|| {
+        info!("Invalid mode '{}', using balance mode", config.global.mode);
+        resolve_mode_params(config, "balance").unwrap_or_else(|| GovernorMode::Balanced.params())
+    })
+}
+
+// 模式段中整数值的范围
+const MODE_RANGES: &[(&str, (i64, i64))] = &[
+    ("very_high_load_threshold", (0, 100)),
+    ("margin", (0, 100)),
+    ("down_threshold", (0, i64::MAX)),
+    ("sampling_interval", (1, 10_000)),
+];
+
+// [global]中整数值的范围，超出范围时使用默认值
+const GLOBAL_RANGES: &[(&str, (i64, i64))] = &[
+    ("startup_opp_index", (0, i64::MAX)),
+    ("max_margin", (0, 100)),
+    ("min_effective_load", (0, 100)),
+    ("ddr_high_load", (0, 100)),
+    ("ddr_low_load", (0, 100)),
+    ("dcs_high_load", (0, 100)),
+    ("dcs_low_load", (0, 100)),
+    ("battery_saver_threshold", (0, 100)),
+];
+
+// 检查数组中每个表的键
+fn check_array_keys(report: &mut ConfigReport, section: &str, value: &Value, known: &[&str]) {
+    for (index, table) in value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_table)
+        .enumerate()
+    {
+        report.check_keys(&format!("{section}[{index}]"), table, known);
+    }
+}
+
+/// 在应用配置之前校验解析出的TOML：记录无法识别的键，超出范围的值限制到范围内或删除以使用默认值
+pub fn validate_config(table: &mut Table) -> ConfigReport {
+    let mut report = ConfigReport::default();
+    let config_fields = struct_fields::<Config>();
+
+    for (section, value) in table.iter_mut() {
+        if !config_fields.contains(&section.as_str()) {
+            report.unknown_keys.push(section.clone());
+            continue;
         }
+
+        match section.as_str() {
+            "global" => {
+                let Some(global) = value.as_table_mut() else {
+                    continue;
+                };
+                let known: Vec<&str> = struct_fields::<Global>()
+                    .iter()
+                    .chain(struct_fields::<EarlyGlobal>())
+                    .copied()
+                    .collect();
+                report.check_keys("global", global, &known);
+                // idle_threshold没有默认值，超出范围时限制到范围内
+                report.check_range("global", global, "idle_threshold", (0, 100), true);
+                for &(key, range) in GLOBAL_RANGES {
+                    report.check_range("global", global, key, range, false);
+                }
+            }
+            "powersave" | "balance" | "performance" | "fast" => {
+                let Some(params) = value.as_table_mut() else {
+                    continue;
+                };
+                report.check_keys(section, params, struct_fields::<ModeParams>());
+                // 模式参数没有默认值，超出范围时限制到范围内
+                for &(key, range) in MODE_RANGES {
+                    report.check_range(section, params, key, range, true);
+                }
+            }
+            "curve_point" => {
+                check_array_keys(&mut report, section, value, struct_fields::<CurvePoint>())
+            }
+            "volt_offset_freq" => check_array_keys(
+                &mut report,
+                section,
+                value,
+                struct_fields::<VoltOffsetFreq>(),
+            ),
+            "app_profile" => check_array_keys(
+                &mut report,
+                section,
+                value,
+                struct_fields::<AppProfileConfig>(),
+            ),
+            "thread_sched" => {
+                for (thread, sched) in value.as_table().into_iter().flatten() {
+                    if let Some(sched) = sched.as_table() {
+                        report.check_keys(
+                            &format!("thread_sched.{thread}"),
+                            sched,
+                            struct_fields::<ThreadSchedConfig>(),
+                        );
+                    }
+                }
+            }
+            _ => report.applied.push(section.clone()),
+        }
+    }
+
+    report
+}
+
+/// 加载TOML配置，返回校验报告；无法识别和超出范围的配置只输出警告，不会导致加载失败
+pub fn load_config(gpu: &mut GPU) -> Result<ConfigReport> {
+    let content = fs::read_to_string(resolve_path(PathKey::ConfigToml))?;
+    let mut table: Table = toml::from_str(&content)?;
+    let report = validate_config(&mut table);
+    let config = Config::deserialize(Value::Table(table))?;
+    report.log();
+
+    gpu.idle_manager_mut()
+        .set_idle_threshold(config.global.idle_threshold);
+
+    gpu.frequency_mut().set_startup_opp(
+        config.global.startup_opp_index,
+        config.global.startup_use_current_freq,
+    );
+    gpu.frequency_mut()
+        .set_min_write_interval(config.global.min_write_interval_us);
+    gpu.frequency_mut()
+        .set_verify_high_opp_index(config.global.verify_high_opp_index);
+    if let Some(action) = config.global.on_freq_mismatch.as_deref() {
+        match FreqMismatchAction::parse(action) {
+            Some(on_freq_mismatch) => gpu.frequency_mut().set_on_freq_mismatch(on_freq_mismatch),
+            None => warn!("Invalid on_freq_mismatch '{action}', using log"),
+        }
+    }
+    gpu.frequency_mut().set_freq_filter(
+        config.global.allowed_freqs.clone(),
+        config.global.blocked_freqs.clone(),
+    );
+    gpu.frequency_mut().set_volt_validation(
+        config.global.volt_tolerance,
+        config.global.reject_unknown_volt,
+    );
+    validate_table_voltages(gpu);
+    let volt_offset = VoltOffset::new(
+        config.global.volt_offset,
+        config
+            .volt_offset_freq
+            .iter()
+            .map(|entry| (entry.freq, entry.offset))
+            .collect(),
+        config.global.min_volt,
+        config.global.max_undervolt,
+    );
+    if let Err(e) = gpu.frequency_mut().set_volt_offset(volt_offset) {
+        warn!("Rejected voltage offset: {e}, using frequency table voltages");
+        gpu.frequency_mut().volt_offset = VoltOffset::default();
+    }
+    gpu.ddr_manager_mut().set_load_scaling(
+        config.global.ddr_load_scaling,
+        config.global.ddr_high_load,
+        config.global.ddr_low_load,
+    );
+    gpu.set_dcs_load_scaling(
+        config.global.dcs_load_scaling,
+        config.global.dcs_high_load,
+        config.global.dcs_low_load,
+    );
+    if let Some(tiebreak) = config.global.v2_snap_tiebreak.as_deref() {
+        match V2SnapTiebreak::parse(tiebreak) {
+            Some(v2_snap_tiebreak) => gpu.frequency_mut().set_v2_snap_tiebreak(v2_snap_tiebreak),
+            None => warn!("Invalid v2_snap_tiebreak '{tiebreak}', using up"),
+        }
+    }
+    if let Some(value) = config.global.opp_reset_value.as_deref() {
+        match OppResetValue::parse(value) {
+            Some(opp_reset_value) => gpu.frequency_mut().set_opp_reset_value(opp_reset_value),
+            None => warn!("Invalid opp_reset_value '{value}', using auto"),
+        }
+    }
+
+    if let Some(format) = config.global.numeric_format.as_deref() {
+        match NumericFormat::parse(format) {
+            Some(numeric_format) => set_numeric_format(numeric_format),
+            None => warn!("Invalid numeric_format '{format}', using decimal"),
+        }
+    }
+
+    if let Some(event_loop) = config.global.event_loop.as_deref() {
+        match EventLoopMode::parse(event_loop) {
+            Some(mode) => set_event_loop_mode(mode),
+            None => warn!("Invalid event_loop '{event_loop}', using threaded mode"),
+        }
+    }
+
+    if let Some(node) = config.global.precise_load_node.as_deref() {
+        match PreciseLoadNode::parse(node) {
+            Some(precise_load_node) => set_precise_load_node(precise_load_node),
+            None => warn!("Invalid precise_load_node '{node}', using auto"),
+        }
+    }
+    if let Some(fusion) = config.global.load_fusion.as_deref() {
+        match LoadFusion::parse(fusion) {
+            Some(load_fusion) => set_load_fusion(load_fusion),
+            None => warn!("Invalid load_fusion '{fusion}', using first"),
+        }
+    }
+
+    set_load_calibration(&config.load_calibration);
+    set_thread_sched(
+        &config
+            .thread_sched
+            .iter()
+            .map(|(name, sched)| {
+                (
+                    name.clone(),
+                    ThreadSched {
+                        cpu_mask: sched.cpu_mask,
+                        nice: sched.nice,
+                    },
+                )
+            })
+            .collect(),
+    );
+    set_load_source_order(
+        &config.global.load_source_order,
+        &config.global.disabled_load_sources,
+    );
+    set_read_cache_ttl(config.global.read_cache_ttl_ms);
+    set_status_file(config.global.status_file.clone());
+    set_telemetry(
+        config.global.telemetry_file.clone(),
+        config.global.telemetry_stride,
+    );
+    set_heartbeat(
+        config.global.heartbeat_file.clone(),
+        config.global.heartbeat_interval_ms,
+    );
+
+    let params = config_mode_params(&config);
+
+    // 前台应用没有匹配的应用配置时使用的模式参数，default表示沿用当前模式
+    let unmatched_params = match config.global.unmatched_app_profile.as_deref() {
+        None | Some("default") => None,
+        Some(name) => resolve_mode_params(&config, name).or_else(|| {
+            warn!("Invalid unmatched_app_profile '{name}', using default");
+            None
+        }),
+    };
+    let unmatched_app_profile = match unmatched_params {
+        Some(_) => config
+            .global
+            .unmatched_app_profile
+            .clone()
+            .unwrap_or_default(),
+        None => "default".to_string(),
     };
 
     let strategy = gpu.frequency_strategy_mut();
-    strategy.very_high_load_threshold = params.very_high_load_threshold;
-    strategy.set_margin(params.margin);
-    strategy.set_down_threshold(params.down_threshold);
-    strategy.set_aggressive_down(params.aggressive_down);
-    strategy.set_sampling_interval(params.sampling_interval);
+    strategy.set_max_margin(config.global.max_margin);
+    strategy.set_mode_params(params);
+    strategy.set_unmatched_app_profile(unmatched_app_profile, unmatched_params);
+    strategy.set_background_max_freq(config.global.background_max_freq);
+    strategy.set_touch_boost(config.global.touch_boost_freq, config.global.touch_boost_ms);
+    strategy.set_frame_drop_boost(
+        config.global.frame_drop_boost_freq,
+        config.global.frame_drop_boost_ms,
+    );
+    strategy.set_sustained_opp_set(
+        config.global.sustained_opp_set.clone(),
+        config.global.sustained_window_ms,
+    );
+    strategy.set_downscale_delay(config.global.downscale_delay_ms);
+    strategy.set_idle_grace(config.global.idle_grace_ms);
+    strategy.set_fixed_freq(config.global.fixed_freq, config.global.fixed_freq_hold_idle);
+    strategy.set_max_steps(config.global.max_step_up, config.global.max_step_down);
+    strategy.set_predictive_ramp(
+        config.global.predictive_steps,
+        config.global.predictive_samples,
+    );
+    strategy.set_config_apply_delay(config.global.config_apply_delay_ms);
+    strategy.set_thermal_cap(
+        config.global.thermal_zones.clone(),
+        config.global.thermal_cap_temp,
+        config.global.thermal_cap_freq,
+    );
+    strategy.set_thermal_levels(
+        config.global.thermal_warn_temp,
+        config.global.thermal_critical_temp,
+    );
+    strategy.set_screen_state_node(config.global.screen_state_node.clone());
+    strategy.set_battery_saver(
+        config.global.battery_saver_threshold,
+        config.global.battery_saver_max_freq,
+    );
+    strategy.set_sustained_floor(
+        config.global.sustained_floor_rise_secs,
+        config.global.sustained_floor_decay_secs,
+    );
+    strategy.set_min_effective_load(config.global.min_effective_load);
+    strategy.set_load_window(
+        config.global.load_window_ms,
+        config.global.time_weighted_load,
+    );
+    strategy.set_load_ema_alpha(config.global.load_ema_alpha);
+    strategy.set_adaptive_variance(
+        config.global.adaptive_sampling_window,
+        config.global.adaptive_variance_threshold,
+    );
+    set_log_effective_config(config.global.log_effective_config);
+    let log_format = config.global.log_format.as_deref().unwrap_or("text");
+    match LogFormat::parse(log_format) {
+        Some(log_format) => set_log_format(log_format),
+        None => warn!("Invalid log_format '{log_format}', keeping current format"),
+    }
+    set_max_log_rate(config.global.max_log_lines_per_sec);
+    set_log_rotation(config.global.log_max_size_kb, config.global.log_keep_files);
+    apply_config_enabled(config.global.enabled);
+    strategy.set_foreground_polling(
+        config.global.foreground_poll_ms,
+        config.global.foreground_read_timeout_ms,
+    );
+    strategy.set_decision_trace(config.global.decision_trace);
+    strategy.set_load_stats_window(config.global.load_stats_window_ms);
+    strategy.set_frame_budget(config.global.frame_budget_ms);
+
+    let governor = match config.global.governor.as_deref() {
+        None | Some("threshold") => GovernorType::Threshold,
+        Some("curve") if !config.curve_point.is_empty() => GovernorType::Curve,
+        Some("curve") => {
+            warn!("Curve governor requires [[curve_point]] entries, using threshold governor");
+            GovernorType::Threshold
+        }
+        Some("frametime") => GovernorType::FrameTime,
+        Some(other) => {
+            warn!("Invalid governor '{other}', using threshold governor");
+            GovernorType::Threshold
+        }
+    };
+    let curve = LoadCurve::new(
+        config
+            .curve_point
+            .iter()
+            .map(|point| (point.load, point.freq))
+            .collect(),
+    );
+    let upscale_mode = match config.global.upscale_mode.as_deref() {
+        None | Some("max") => UpscaleMode::Max,
+        Some("demand") => UpscaleMode::Demand,
+        Some(other) => {
+            warn!("Invalid upscale_mode '{other}', using max upscale mode");
+            UpscaleMode::Max
+        }
+    };
+    strategy.set_upscale_mode(upscale_mode);
+    strategy.set_governor(governor);
+    strategy.set_load_curve(curve);
+    info!("Using {governor:?} governor");
+
+    set_app_profiles(
+        config
+            .app_profile
+            .into_iter()
+            .filter_map(|profile| {
+                let pattern = PackagePattern::parse(&profile.package)
+                    .inspect_err(|e| warn!("{e}, app profile ignored"))
+                    .ok()?;
+                Some(AppProfile {
+                    package: profile.package,
+                    pattern,
+                    disable_dcs: profile.disable_dcs,
+                    disable_ddr_scaling: profile.disable_ddr_scaling,
+                    launch_boost: profile.launch_boost,
+                    launch_boost_ms: profile.launch_boost_ms,
+                    min_freq: profile.min_freq.filter(|&freq| freq > 0),
+                    max_freq: profile.max_freq.filter(|&freq| freq > 0),
+                })
+            })
+            .collect(),
+    );
 
     info!("Loaded config for mode: {}", config.global.mode);
-    Ok(())
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::config_report::OutOfRange;
+
+    fn validate(content: &str) -> (Table, ConfigReport) {
+        let mut table: Table = toml::from_str(content).unwrap();
+        let report = validate_config(&mut table);
+        (table, report)
+    }
+
+    #[test]
+    fn unknown_keys_are_reported() {
+        let (_, report) = validate(
+            r#"
+            [global]
+            mode = "balance"
+            idle_treshold = 5
+
+            [balance]
+            margin = 20
+            marign = 10
+
+            [extra]
+            value = 1
+            "#,
+        );
+        assert_eq!(
+            report.unknown_keys,
+            vec!["balance.marign", "extra", "global.idle_treshold"]
+        );
+        assert!(report.applied.contains(&"global.mode".to_string()));
+        assert!(report.applied.contains(&"balance.margin".to_string()));
+        assert!(report.has_issues());
+    }
+
+    #[test]
+    fn out_of_range_values_are_adjusted() {
+        let (table, report) = validate(
+            r#"
+            [global]
+            max_margin = 150
+
+            [balance]
+            margin = 120
+            "#,
+        );
+        assert_eq!(
+            report.out_of_range,
+            vec![
+                OutOfRange {
+                    key: "balance.margin".to_string(),
+                    value: 120,
+                    min: 0,
+                    max: 100,
+                    replacement: Some(100),
+                },
+                OutOfRange {
+                    key: "global.max_margin".to_string(),
+                    value: 150,
+                    min: 0,
+                    max: 100,
+                    replacement: None,
+                },
+            ]
+        );
+        // [global]中超出范围的值被删除以使用默认值，模式参数被限制到范围内
+        assert!(!table["global"]
+            .as_table()
+            .unwrap()
+            .contains_key("max_margin"));
+        assert_eq!(table["balance"]["margin"].as_integer(), Some(100));
+        assert!(!report.applied.contains(&"balance.margin".to_string()));
+        assert!(report.unknown_keys.is_empty());
+    }
 }
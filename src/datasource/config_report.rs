@@ -0,0 +1,155 @@
+use std::fmt;
+
+use log::{debug, info, warn};
+use serde::{
+    de::{self, Visitor},
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+use toml::Table;
+
+/// 超出范围的配置值
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutOfRange {
+    /// 配置键，格式为"段.键"
+    pub key: String,
+    pub value: i64,
+    pub min: i64,
+    pub max: i64,
+    /// 限制到范围内后使用的值，None表示忽略配置值并使用默认值
+    pub replacement: Option<i64>,
+}
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} = {} is out of range {}..={}, ",
+            self.key, self.value, self.min, self.max
+        )?;
+        match self.replacement {
+            Some(value) => write!(f, "clamped to {value}"),
+            None => write!(f, "using default"),
+        }
+    }
+}
+
+/// 配置校验报告 - 记录无法识别的键、超出范围的值和已应用的键
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConfigReport {
+    /// 无法识别的键，格式为"段.键"，不会生效
+    pub unknown_keys: Vec<String>,
+    /// 超出范围的值
+    pub out_of_range: Vec<OutOfRange>,
+    /// 已应用的键
+    pub applied: Vec<String>,
+}
+
+impl ConfigReport {
+    /// 是否有被忽略或修改的配置
+    pub fn has_issues(&self) -> bool {
+        !self.unknown_keys.is_empty() || !self.out_of_range.is_empty()
+    }
+
+    /// 检查表中的键，无法识别的键记入报告，其余记为已应用
+    pub fn check_keys(&mut self, section: &str, table: &Table, known: &[&str]) {
+        for key in table.keys() {
+            let name = format!("{section}.{key}");
+            if known.contains(&key.as_str()) {
+                self.applied.push(name);
+            } else {
+                self.unknown_keys.push(name);
+            }
+        }
+    }
+
+    /// 检查整数值是否在范围内，超出范围时限制到范围内或删除该键（使用默认值）
+    pub fn check_range(
+        &mut self,
+        section: &str,
+        table: &mut Table,
+        key: &str,
+        (min, max): (i64, i64),
+        clamp: bool,
+    ) {
+        let Some(value) = table.get(key).and_then(|value| value.as_integer()) else {
+            return;
+        };
+        if (min..=max).contains(&value) {
+            return;
+        }
+
+        let replacement = clamp.then(|| value.clamp(min, max));
+        match replacement {
+            Some(clamped) => {
+                table.insert(key.to_string(), clamped.into());
+            }
+            None => {
+                table.remove(key);
+            }
+        }
+        let name = format!("{section}.{key}");
+        self.applied.retain(|applied| *applied != name);
+        self.out_of_range.push(OutOfRange {
+            key: name,
+            value,
+            min,
+            max,
+            replacement,
+        });
+    }
+
+    /// 输出报告，被忽略或修改的配置以warn级别输出
+    pub fn log(&self) {
+        for key in &self.unknown_keys {
+            warn!("Unknown config key {key}, ignored");
+        }
+        for issue in &self.out_of_range {
+            warn!("Config {issue}");
+        }
+        if self.has_issues() {
+            warn!(
+                "{} config keys ignored or adjusted, {} applied",
+                self.unknown_keys.len() + self.out_of_range.len(),
+                self.applied.len()
+            );
+        } else {
+            info!("{} config keys applied", self.applied.len());
+        }
+        debug!("Applied config keys: {}", self.applied.join(", "));
+    }
+}
+
+/// 获取结构体在配置文件中的字段名
+///
+/// 派生的Deserialize实现会把字段列表传给deserialize_struct，在这里记录下来后直接返回错误
+pub fn struct_fields<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    struct FieldsCapture<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> Deserializer<'de> for FieldsCapture<'_> {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            Err(de::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(de::Error::custom("fields captured"))
+        }
+
+        forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+            byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map
+            enum identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldsCapture(&mut fields));
+    fields
+}
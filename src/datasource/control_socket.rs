@@ -0,0 +1,332 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::Path,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use log::{debug, info, warn};
+
+use crate::{
+    datasource::{
+        file_path::*,
+        load_monitor::{
+            active_load_source, detected_load_sources, is_precise_load_available,
+            select_load_source, selected_load_source,
+        },
+        thermal::get_thermal_readings,
+    },
+    model::{
+        effective_config::get_effective_config,
+        frame_time::report_frame_time,
+        freq_limits::{set_max_cap, set_temporary_floor, LimitSource},
+        governor_mode::GovernorMode,
+        governor_state::start_cooldown,
+        gpu::GPU,
+        opp_table::get_opp_table,
+        settings_queue::{submit_settings_change, SettingsChange},
+        snapshot::get_snapshot,
+    },
+    utils::constants::strategy,
+};
+
+// 单个连接的读写超时，避免客户端卡住控制线程
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 控制命令处理器 - 每行一个命令，每个命令回复一行结果
+pub struct ControlSocketHandler {
+    listener: UnixListener,
+    frame_drop_boost_freq: i64,
+    frame_drop_boost_ms: u64,
+}
+
+impl ControlSocketHandler {
+    pub fn new(gpu: &GPU) -> Result<Self> {
+        info!("{CONTROL_SOCKET_THREAD} Start");
+
+        // 清理上次运行残留的套接字文件
+        let socket_path = resolve_path(PathKey::ControlSocket);
+        if Path::new(socket_path).exists() {
+            fs::remove_file(socket_path)
+                .with_context(|| format!("Failed to remove stale control socket: {socket_path}"))?;
+        }
+
+        let listener = UnixListener::bind(socket_path)
+            .with_context(|| format!("Failed to bind control socket: {socket_path}"))?;
+        info!("Control socket listening on {socket_path}");
+
+        Ok(Self {
+            listener,
+            frame_drop_boost_freq: gpu.frequency_strategy.frame_drop_boost_freq,
+            frame_drop_boost_ms: gpu.frequency_strategy.frame_drop_boost_ms,
+        })
+    }
+
+    // 接受一个连接并处理其中的所有命令
+    fn accept_one(&mut self) -> Result<()> {
+        let (stream, _) = self
+            .listener
+            .accept()
+            .with_context(|| "Failed to accept control connection")?;
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+        stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+        self.serve(stream)
+    }
+
+    fn serve(&self, stream: UnixStream) -> Result<()> {
+        let mut writer = stream.try_clone()?;
+        let reader = BufReader::new(stream);
+
+        for line in reader.lines() {
+            let line = line?;
+            let command = line.trim();
+            if command.is_empty() {
+                continue;
+            }
+
+            debug!("Control command: {command}");
+            let reply = self.handle_command(command);
+            writeln!(writer, "{reply}")?;
+        }
+
+        Ok(())
+    }
+
+    // 执行一条命令，返回回复内容（多行回复以"OK <行数>"开头）
+    fn handle_command(&self, command: &str) -> String {
+        let mut parts = command.split_whitespace();
+        match parts.next() {
+            Some("frame_drop") => self.handle_frame_drop(),
+            Some("floorboost") => Self::handle_floor_boost(parts.next(), parts.next()),
+            Some("dump-config") => Self::handle_dump_config(),
+            Some("opp-table") => Self::handle_opp_table(),
+            Some("cooldown") => Self::handle_cooldown(parts.next()),
+            Some("frametime") => Self::handle_frame_time(parts.next()),
+            Some("state") => Self::handle_state(),
+            Some("get-freq") => format!("OK {}", get_snapshot().cur_freq),
+            Some("get-load") => format!("OK {}", get_snapshot().load),
+            Some("set-mode") => Self::handle_set_mode(parts.next()),
+            Some("set-max-freq") => Self::handle_set_max_freq(parts.next()),
+            Some("thermal") => Self::handle_thermal(),
+            Some("load-sources") => Self::handle_load_sources(),
+            Some("set") => Self::handle_set(parts.next(), parts.next()),
+            Some("enable") => {
+                submit_settings_change(SettingsChange::Enabled(true));
+                "OK enabled".to_string()
+            }
+            Some("disable") => {
+                submit_settings_change(SettingsChange::Enabled(false));
+                "OK disabled".to_string()
+            }
+            Some(other) => format!("ERR unknown command: {other}"),
+            None => "ERR empty command".to_string(),
+        }
+    }
+
+    // 掉帧时临时提高频率下限，重复信号只刷新持续时间，不会叠加
+    fn handle_frame_drop(&self) -> String {
+        if self.frame_drop_boost_freq <= 0 || self.frame_drop_boost_ms == 0 {
+            return "ERR frame drop boost is disabled".to_string();
+        }
+
+        set_temporary_floor(
+            LimitSource::FrameDrop,
+            self.frame_drop_boost_freq,
+            Duration::from_millis(self.frame_drop_boost_ms),
+        );
+        format!(
+            "OK boost {}KHz for {}ms",
+            self.frame_drop_boost_freq, self.frame_drop_boost_ms
+        )
+    }
+
+    // 当前可用的频率列表，每次从最新发布的频率表读取，频率表重新加载后不会使用旧表
+    fn usable_freqs() -> Vec<i64> {
+        get_opp_table()
+            .map(|table| {
+                table
+                    .entries
+                    .iter()
+                    .filter(|entry| entry.index.is_some())
+                    .map(|entry| entry.freq)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // 界面切换等短时重负载前临时提高频率下限，到期自动失效，重复请求刷新持续时间
+    fn handle_floor_boost(index: Option<&str>, ms: Option<&str>) -> String {
+        let index = index.and_then(|index| index.parse::<usize>().ok());
+        let ms = ms
+            .and_then(|ms| ms.parse::<u64>().ok())
+            .filter(|&ms| ms > 0);
+        let (Some(index), Some(ms)) = (index, ms) else {
+            return "ERR usage: floorboost <index> <ms>".to_string();
+        };
+        let freqs = Self::usable_freqs();
+        if freqs.is_empty() {
+            return "ERR frequency table not available yet".to_string();
+        }
+        let Some(&freq) = freqs.get(index) else {
+            return format!("ERR index {index} out of range (0-{})", freqs.len() - 1);
+        };
+        let ms = ms.min(strategy::FLOOR_BOOST_MAX_MS);
+        set_temporary_floor(LimitSource::FloorBoost, freq, Duration::from_millis(ms));
+        format!("OK floor {freq}KHz for {ms}ms")
+    }
+
+    // 外部温控请求在指定秒数内固定最低频率
+    fn handle_cooldown(arg: Option<&str>) -> String {
+        match arg.and_then(|secs| secs.parse::<u64>().ok()) {
+            Some(secs) if secs > 0 => {
+                start_cooldown(Duration::from_secs(secs));
+                format!("OK cooldown {secs}s")
+            }
+            _ => "ERR usage: cooldown <secs>".to_string(),
+        }
+    }
+
+    // 上报GPU帧时间，供帧时间调速器使用
+    fn handle_frame_time(arg: Option<&str>) -> String {
+        match arg.and_then(|ms| ms.parse::<f64>().ok()) {
+            Some(frame_ms) if frame_ms.is_finite() && frame_ms > 0.0 => {
+                report_frame_time(frame_ms);
+                format!("OK frametime {frame_ms}ms")
+            }
+            _ => "ERR usage: frametime <ms>".to_string(),
+        }
+    }
+
+    // 以单行JSON返回最新的调速器状态，外部应用一次读取频率、负载、余量、调速器和应用配置
+    fn handle_state() -> String {
+        format!("OK {}", get_snapshot().to_json())
+    }
+
+    // 切换到内置调速模式预设，由调频循环应用
+    fn handle_set_mode(arg: Option<&str>) -> String {
+        match arg.and_then(GovernorMode::parse) {
+            Some(mode) => {
+                submit_settings_change(SettingsChange::SetMode(mode));
+                format!("OK mode {}", format!("{mode:?}").to_lowercase())
+            }
+            None => "ERR usage: set-mode <performance|balanced|powersave>".to_string(),
+        }
+    }
+
+    // 设置频率上限，不在频率表中的频率按表向下吸附，0表示清除
+    fn handle_set_max_freq(arg: Option<&str>) -> String {
+        match arg.and_then(|freq| freq.parse::<i64>().ok()) {
+            Some(0) => {
+                set_max_cap(LimitSource::Control, None);
+                "OK max_freq cleared".to_string()
+            }
+            Some(freq) if freq > 0 => {
+                let freqs = Self::usable_freqs();
+                let Some(snapped) = freqs
+                    .iter()
+                    .copied()
+                    .filter(|&table_freq| table_freq <= freq)
+                    .max()
+                    .or_else(|| freqs.first().copied())
+                else {
+                    return "ERR frequency table not available yet".to_string();
+                };
+                set_max_cap(LimitSource::Control, Some(snapped));
+                format!("OK max_freq {snapped}KHz")
+            }
+            _ => "ERR usage: set-max-freq <KHz|0>".to_string(),
+        }
+    }
+
+    // 列出各温区最近一次读取的温度（毫摄氏度）
+    fn handle_thermal() -> String {
+        let readings = get_thermal_readings();
+        if readings.is_empty() {
+            return "ERR thermal cap is disabled".to_string();
+        }
+        let zones: Vec<String> = readings
+            .iter()
+            .map(|(zone, temp)| match temp {
+                Some(temp) => format!("{zone}={temp}"),
+                None => format!("{zone}=unreadable"),
+            })
+            .collect();
+        format!("OK {}", zones.join(" "))
+    }
+
+    // 列出检测到的负载源以及当前使用和指定的负载源
+    fn handle_load_sources() -> String {
+        format!(
+            "OK active={} selected={} detected={}",
+            active_load_source().unwrap_or("none"),
+            selected_load_source().unwrap_or("auto"),
+            detected_load_sources().join(",")
+        )
+    }
+
+    // 运行时修改设置
+    fn handle_set(key: Option<&str>, value: Option<&str>) -> String {
+        match (key, value) {
+            (Some("load_source"), Some(name)) => match select_load_source(name) {
+                Ok(load) => format!("OK load_source {name} load {load}"),
+                Err(e) => format!("ERR {e}"),
+            },
+            (Some("load_source"), None) => "ERR usage: set load_source <name|auto>".to_string(),
+            (Some("precise"), value) => Self::handle_set_precise(value),
+            (Some(other), _) => format!("ERR unknown setting: {other}"),
+            (None, _) => "ERR usage: set <key> <value>".to_string(),
+        }
+    }
+
+    // 切换精确负载源，由调频循环应用并重置负载计数器
+    fn handle_set_precise(value: Option<&str>) -> String {
+        let precise = match value {
+            Some("on") => true,
+            Some("off") => false,
+            _ => return "ERR usage: set precise <on|off>".to_string(),
+        };
+        if precise && !is_precise_load_available() {
+            return "ERR precise load source not available".to_string();
+        }
+        submit_settings_change(SettingsChange::Precise(precise));
+        format!("OK precise {}", if precise { "on" } else { "off" })
+    }
+
+    // 导出最终生效的配置
+    fn handle_dump_config() -> String {
+        let config = get_effective_config();
+        if config.is_empty() {
+            return "ERR effective config not available yet".to_string();
+        }
+        let lines: Vec<&str> = config.lines().collect();
+        format!("OK {}\n{}", lines.len(), lines.join("\n"))
+    }
+
+    // 导出频率表，当前频率索引取最新的状态快照
+    fn handle_opp_table() -> String {
+        let Some(mut table) = get_opp_table() else {
+            return "ERR frequency table not available yet".to_string();
+        };
+        table.cur_freq_idx = get_snapshot().cur_freq_idx;
+        match table.to_toml() {
+            Ok(toml) => {
+                let lines: Vec<&str> = toml.lines().collect();
+                format!("OK {}\n{}", lines.len(), lines.join("\n"))
+            }
+            Err(e) => format!("ERR {e}"),
+        }
+    }
+}
+
+/// 监听控制套接字，处理外部工具发送的运行时命令
+pub fn monitor_control_socket(gpu: GPU) -> Result<()> {
+    let mut handler = ControlSocketHandler::new(&gpu)?;
+
+    loop {
+        if let Err(e) = handler.accept_one() {
+            warn!("Control connection error: {e}");
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use log::debug;
+use once_cell::sync::Lazy;
+
+use crate::{datasource::file_path::*, utils::numeric::parse_i64};
+
+// GPU对应的devfreq设备名关键字
+const GPU_DEVFREQ_KEYWORDS: [&str; 2] = ["mali", "gpu"];
+
+// 启动时查找一次GPU的devfreq设备目录
+static GPU_DEVFREQ_DIR: Lazy<Option<String>> = Lazy::new(find_gpu_devfreq_dir);
+
+fn find_gpu_devfreq_dir() -> Option<String> {
+    let entries = fs::read_dir(DEVFREQ_CLASS_DIR).ok()?;
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| {
+            let name = name.to_lowercase();
+            GPU_DEVFREQ_KEYWORDS
+                .iter()
+                .any(|keyword| name.contains(keyword))
+        })
+        .collect();
+    names.sort();
+
+    let dir = names
+        .first()
+        .map(|name| format!("{DEVFREQ_CLASS_DIR}/{name}"));
+    debug!("GPU devfreq device: {dir:?}");
+    dir
+}
+
+/// 获取GPU devfreq设备下的节点路径，设备或节点不存在时返回None
+pub fn gpu_devfreq_node(node: &str) -> Option<String> {
+    let path = format!("{}/{node}", GPU_DEVFREQ_DIR.as_ref()?);
+    Path::new(&path).exists().then_some(path)
+}
+
+// 读取单个频率节点（Hz）
+fn read_devfreq_hz(node: &str) -> Option<i64> {
+    let path = gpu_devfreq_node(node)?;
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| parse_i64(content.trim()))
+}
+
+/// 从devfreq读取可用频率列表（KHz，升序），按min_freq/max_freq节点过滤
+pub fn read_devfreq_freq_table() -> Result<Vec<i64>> {
+    let path = gpu_devfreq_node(DEVFREQ_AVAILABLE_FREQS_NODE)
+        .ok_or_else(|| anyhow!("GPU devfreq available_frequencies not found"))?;
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {path}"))?;
+
+    let min_hz = read_devfreq_hz(DEVFREQ_MIN_FREQ_NODE).unwrap_or(0);
+    let max_hz = read_devfreq_hz(DEVFREQ_MAX_FREQ_NODE).unwrap_or(i64::MAX);
+    debug!("devfreq range: {min_hz}Hz - {max_hz}Hz");
+
+    // devfreq使用Hz，调速器内部使用KHz
+    let mut freqs: Vec<i64> = content
+        .split_whitespace()
+        .filter_map(parse_i64)
+        .filter(|&hz| hz >= min_hz && hz <= max_hz)
+        .map(|hz| hz / 1000)
+        .collect();
+    freqs.sort_unstable();
+    freqs.dedup();
+
+    if freqs.is_empty() {
+        return Err(anyhow!("No usable frequencies in {path}"));
+    }
+    Ok(freqs)
+}
@@ -1,9 +1,21 @@
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+
 // Thread names
 pub const MAIN_THREAD: &str = "LoadMonitor";
 pub const GAME_THREAD: &str = "GameModeWatcher";
 pub const CONF_THREAD: &str = "ConfigWatcher";
 pub const FOREGROUND_APP_THREAD: &str = "ForegroundAppWatcher";
 pub const LOG_LEVEL_MONITOR_THREAD: &str = "LogLevelMonitor";
+pub const TOUCH_BOOST_THREAD: &str = "TouchBoostWatcher";
+pub const EVENT_LOOP_THREAD: &str = "MonitorEventLoop";
+pub const CONTROL_SOCKET_THREAD: &str = "ControlSocket";
+pub const THERMAL_THREAD: &str = "ThermalWatcher";
+pub const BATTERY_THREAD: &str = "BatteryWatcher";
+pub const SCREEN_THREAD: &str = "ScreenStateWatcher";
+pub const LOG_ROTATION_THREAD: &str = "LogRotation";
+pub const SUPERVISOR_THREAD: &str = "ThreadSupervisor";
 
 // File paths
 pub const GPU_GOVERNOR_GAME_MODE_PATH: &str = "/data/adb/gpu_governor/game/game_mode";
@@ -19,16 +31,42 @@ pub const PROC_MALI_LOAD: &str = "/proc/mali/utilization";
 pub const PROC_MTK_LOAD: &str = "/proc/mtk_mali/utilization";
 pub const DEBUG_DVFS_LOAD: &str = "/sys/kernel/debug/mali0/dvfs_utilization";
 pub const DEBUG_DVFS_LOAD_OLD: &str = "/proc/mali/dvfs_utilization";
+// 累计活动时间/总时间计数器，格式为"active total"
+pub const GPU_TIME_COUNTERS: &str = "/sys/kernel/gpu/gpu_time_counters";
 pub const GPUFREQV2_TABLE: &str = "/proc/gpufreqv2/stack_working_opp_table";
 pub const GPUFREQ_OPP: &str = "/proc/gpufreq/gpufreq_opp_freq";
+pub const GPUFREQ_OPP_DUMP: &str = "/proc/gpufreq/gpufreq_opp_dump";
 pub const GPUFREQV2_OPP: &str = "/proc/gpufreqv2/fix_target_opp_index";
 pub const GPUFREQ_VOLT: &str = "/proc/gpufreq/gpufreq_fixed_freq_volt";
 pub const GPUFREQV2_VOLT: &str = "/proc/gpufreqv2/fix_custom_freq_volt";
+pub const DEVFREQ_CLASS_DIR: &str = "/sys/class/devfreq";
+pub const DEVFREQ_LOAD_NODE: &str = "load";
+pub const DEVFREQ_AVAILABLE_FREQS_NODE: &str = "available_frequencies";
+pub const DEVFREQ_MIN_FREQ_NODE: &str = "min_freq";
+pub const DEVFREQ_MAX_FREQ_NODE: &str = "max_freq";
 // 频率表配置文件路径
 pub const FREQ_TABLE_CONFIG_FILE: &str = "/data/adb/gpu_governor/config/gpu_freq_table.toml";
 pub const LOG_PATH: &str = "/data/adb/gpu_governor/log/gpu_gov.log";
 pub const LOG_LEVEL_PATH: &str = "/data/adb/gpu_governor/log/log_level";
 pub const GAMES_CONF_PATH: &str = "/data/adb/gpu_governor/game/games.conf";
+pub const PID_FILE_PATH: &str = "/data/adb/gpu_governor/gpu_gov.pid";
+pub const CONTROL_SOCKET_PATH: &str = "/data/adb/gpu_governor/gpu_gov.sock";
+
+// 触摸输入设备路径
+pub const INPUT_DEVICE_DIR: &str = "/dev/input";
+pub const INPUT_CLASS_DIR: &str = "/sys/class/input";
+pub const THERMAL_CLASS_DIR: &str = "/sys/class/thermal";
+
+// 电池状态路径
+pub const BATTERY_CAPACITY: &str = "/sys/class/power_supply/battery/capacity";
+pub const BATTERY_STATUS: &str = "/sys/class/power_supply/battery/status";
+
+// 屏幕状态节点 - 各设备暴露的背光节点不同，按顺序尝试
+pub const SCREEN_BRIGHTNESS_NODES: [&str; 3] = [
+    "/sys/class/leds/lcd-backlight/brightness",
+    "/sys/class/backlight/panel0-backlight/brightness",
+    "/sys/class/backlight/panel/brightness",
+];
 
 // Mali GPU DVFS控制相关路径
 pub const MALI_DVFS_ENABLE: &str = "/proc/mali/dvfs_enable";
@@ -57,3 +95,88 @@ pub const DDR_FOURTH_FREQ: i64 = 3; // 第四档内存频率和电压
 pub const DDR_FIFTH_FREQ: i64 = 4; // 第五档内存频率和电压
                                    // 策略配置文件路径
 pub const CONFIG_TOML_FILE: &str = "/data/adb/gpu_governor/config/config.toml";
+
+/// 可通过环境变量覆盖的路径
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PathKey {
+    ConfigToml,
+    FreqTable,
+    Log,
+    LogLevel,
+    Games,
+    GameMode,
+    PidFile,
+    ControlSocket,
+}
+
+impl PathKey {
+    const ALL: [PathKey; 8] = [
+        PathKey::ConfigToml,
+        PathKey::FreqTable,
+        PathKey::Log,
+        PathKey::LogLevel,
+        PathKey::Games,
+        PathKey::GameMode,
+        PathKey::PidFile,
+        PathKey::ControlSocket,
+    ];
+
+    /// 覆盖该路径的环境变量名
+    pub fn env_var(self) -> &'static str {
+        match self {
+            PathKey::ConfigToml => "MALIGOV_CONFIG",
+            PathKey::FreqTable => "MALIGOV_FREQ_TABLE",
+            PathKey::Log => "MALIGOV_LOG",
+            PathKey::LogLevel => "MALIGOV_LOG_LEVEL",
+            PathKey::Games => "MALIGOV_GAMES",
+            PathKey::GameMode => "MALIGOV_GAME_MODE",
+            PathKey::PidFile => "MALIGOV_PID_FILE",
+            PathKey::ControlSocket => "MALIGOV_SOCKET",
+        }
+    }
+
+    /// 编译期默认路径
+    pub fn default_path(self) -> &'static str {
+        match self {
+            PathKey::ConfigToml => CONFIG_TOML_FILE,
+            PathKey::FreqTable => FREQ_TABLE_CONFIG_FILE,
+            PathKey::Log => LOG_PATH,
+            PathKey::LogLevel => LOG_LEVEL_PATH,
+            PathKey::Games => GAMES_CONF_PATH,
+            PathKey::GameMode => GPU_GOVERNOR_GAME_MODE_PATH,
+            PathKey::PidFile => PID_FILE_PATH,
+            PathKey::ControlSocket => CONTROL_SOCKET_PATH,
+        }
+    }
+}
+
+// 启动时解析一次的路径表，之后保持不变
+static RESOLVED_PATHS: Lazy<HashMap<PathKey, String>> = Lazy::new(|| {
+    PathKey::ALL
+        .iter()
+        .map(|&key| {
+            let path = std::env::var(key.env_var())
+                .ok()
+                .filter(|value| !value.is_empty())
+                .unwrap_or_else(|| key.default_path().to_string());
+            (key, path)
+        })
+        .collect()
+});
+
+/// 解析路径：优先使用环境变量，未设置时使用编译期默认路径
+pub fn resolve_path(key: PathKey) -> &'static str {
+    RESOLVED_PATHS
+        .get(&key)
+        .map(String::as_str)
+        .unwrap_or_else(|| key.default_path())
+}
+
+/// 获取被环境变量覆盖的路径列表 (环境变量名, 路径)
+pub fn overridden_paths() -> Vec<(&'static str, &'static str)> {
+    PathKey::ALL
+        .iter()
+        .filter(|&&key| resolve_path(key) != key.default_path())
+        .map(|&key| (key.env_var(), resolve_path(key)))
+        .collect()
+}
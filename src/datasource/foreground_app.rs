@@ -1,7 +1,7 @@
 use std::{
-    collections::HashSet,
     fs::File,
     io::{BufRead, BufReader},
+    sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError},
     thread,
     time::{Duration, Instant},
 };
@@ -16,12 +16,22 @@ use std::sync::Mutex;
 
 use crate::{
     datasource::file_path::*,
+    model::{
+        freq_limits::{set_max_cap, LimitSource},
+        gpu::GPU,
+        package_pattern::{PackageList, PackagePattern},
+        settings_queue::{submit_settings_change, SettingsChange},
+    },
     utils::{
+        event_loop::PollHandler,
         file_operate::{check_read_simple, write_file},
         inotify::InotifyWatcher,
     },
 };
 
+// 前台应用轮询间隔
+const POLL_INTERVAL_MS: u64 = 100;
+
 // 缓存前台应用信息，避免频繁调用系统命令
 struct ForegroundAppCache {
     package_name: String,
@@ -81,28 +91,22 @@ fn get_foreground_app_activity() -> Result<String> {
     // 新增：为error日志添加12小时限流器
     static ERROR_THROTTLER: Lazy<Mutex<WarningThrottler>> =
         Lazy::new(|| Mutex::new(WarningThrottler::new(43200)));
-    let dumper = loop {
-        match Dumpsys::new("activity") {
-            Some(s) => break s,
-            None => std::thread::sleep(std::time::Duration::from_secs(1)),
-        };
-    };
-    let output = loop {
-        match dumper.dump(&["lru"]) {
-            Ok(d) => break d,
-            Err(e) => {
-                // 线程安全的全局限流器
-                {
-                    let mut throttler = ERROR_THROTTLER.lock().unwrap();
-                    if throttler.should_warn() {
-                        log::error!("Unable to get foreground application: {e}");
-                    } else {
-                        log::debug!("Unable to get foreground application (throttled): {e}");
-                    }
+    // 获取失败时直接返回错误，由调用方跳过本轮，不在此处无限重试
+    let dumper = Dumpsys::new("activity").ok_or_else(|| anyhow!("activity service not ready"))?;
+    let output = match dumper.dump(&["lru"]) {
+        Ok(d) => d,
+        Err(e) => {
+            // 线程安全的全局限流器
+            {
+                let mut throttler = ERROR_THROTTLER.lock().unwrap();
+                if throttler.should_warn() {
+                    log::error!("Unable to get foreground application: {e}");
+                } else {
+                    log::debug!("Unable to get foreground application (throttled): {e}");
                 }
-                std::thread::sleep(Duration::from_secs(1));
             }
-        };
+            return Err(anyhow!("dumpsys activity lru failed: {e}"));
+        }
     };
 
     // 使用正则表达式提取前台应用包名
@@ -151,9 +155,9 @@ fn get_foreground_app() -> Result<String> {
     }
 }
 
-// 读取游戏列表
-fn read_games_list(path: &str) -> Result<HashSet<String>> {
-    let mut games = HashSet::new();
+// 读取游戏列表，每行一个包名、通配符或正则（见PackagePattern），无效的规则跳过
+fn read_games_list(path: &str) -> Result<PackageList> {
+    let mut games = PackageList::default();
 
     if !check_read_simple(path) {
         return Ok(games);
@@ -173,67 +177,127 @@ fn read_games_list(path: &str) -> Result<HashSet<String>> {
             continue;
         }
 
-        games.insert(trimmed.to_string());
+        match PackagePattern::parse(trimmed) {
+            Ok(pattern) => games.push(pattern),
+            Err(e) => warn!("{e}, skipped in games list"),
+        }
     }
 
     Ok(games)
 }
 
-// 监控前台应用
-pub fn monitor_foreground_app() -> Result<()> {
-    // 设置线程名称
-    info!("{FOREGROUND_APP_THREAD} Start");
-
-    // 初始化缓存
-    let mut app_cache = ForegroundAppCache::new();
-    let cache_ttl = Duration::from_millis(1000); // 缓存有效期1秒
-                                                 // 初始化警告限流器，设置60秒的限流时间
-    let mut warning_throttler = WarningThrottler::new(43200); // 12小时限流
-
-    // 读取游戏列表
-    let mut games = read_games_list(GAMES_CONF_PATH)?;
-    info!("Loaded {} games from {}", games.len(), GAMES_CONF_PATH);
-
-    // 设置文件监控
-    let mut inotify = InotifyWatcher::new()?;
-
-    // 如果游戏列表文件存在，监控它的变化
-    if check_read_simple(GAMES_CONF_PATH) {
-        inotify.add(GAMES_CONF_PATH, WatchMask::CLOSE_WRITE | WatchMask::MODIFY)?;
-        info!("Watching games list file: {GAMES_CONF_PATH}");
-    } else {
-        info!("Games list file does not exist: {GAMES_CONF_PATH}");
+// 在后台线程读取前台应用，避免读取卡住时阻塞监控线程
+fn spawn_foreground_read() -> Receiver<Result<String>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(get_foreground_app());
+    });
+    rx
+}
+
+/// 前台应用监控处理器
+pub struct ForegroundAppHandler {
+    app_cache: ForegroundAppCache,
+    cache_ttl: Duration,
+    read_timeout: Duration,
+    // 超时后仍在进行的读取，完成前不会发起新的读取
+    pending_read: Option<Receiver<Result<String>>>,
+    warning_throttler: WarningThrottler,
+    games: PackageList,
+    background_max_freq: i64,
+    inotify: InotifyWatcher,
+    // 启动延迟结束的时间点，之前的轮询直接跳过
+    ready_at: Instant,
+}
+
+impl ForegroundAppHandler {
+    pub fn new(gpu: &GPU, start_delay: Duration) -> Result<Self> {
+        // 设置线程名称
+        info!("{FOREGROUND_APP_THREAD} Start");
+
+        // 初始化缓存
+        let app_cache = ForegroundAppCache::new();
+        let cache_ttl = Duration::from_millis(gpu.frequency_strategy.foreground_poll_ms);
+        let read_timeout = Duration::from_millis(gpu.frequency_strategy.foreground_read_timeout_ms);
+        info!(
+            "Foreground app poll: {}ms, read timeout: {}ms",
+            cache_ttl.as_millis(),
+            read_timeout.as_millis()
+        );
+        // 初始化警告限流器
+        let warning_throttler = WarningThrottler::new(43200); // 12小时限流
+
+        // 读取游戏列表
+        let games_path = resolve_path(PathKey::Games);
+        let games = read_games_list(games_path)?;
+        info!("Loaded {} games from {}", games.len(), games_path);
+
+        // 后台频率上限：非游戏应用位于前台时生效
+        let background_max_freq = gpu.frequency_strategy.background_max_freq;
+        if background_max_freq > 0 {
+            info!("Background max freq: {background_max_freq}KHz");
+        }
+
+        // 设置文件监控
+        let mut inotify = InotifyWatcher::new()?;
+
+        // 如果游戏列表文件存在，监控它的变化
+        if check_read_simple(games_path) {
+            inotify.add(games_path, WatchMask::CLOSE_WRITE | WatchMask::MODIFY)?;
+            info!("Watching games list file: {games_path}");
+        } else {
+            info!("Games list file does not exist: {games_path}");
+        }
+
+        Ok(Self {
+            app_cache,
+            cache_ttl,
+            read_timeout,
+            pending_read: None,
+            warning_throttler,
+            games,
+            background_max_freq,
+            inotify,
+            ready_at: Instant::now() + start_delay,
+        })
     }
 
-    // 主循环
-    loop {
+    // 检查游戏列表变化并更新前台应用状态
+    fn poll(&mut self) -> Result<()> {
+        if Instant::now() < self.ready_at {
+            return Ok(());
+        }
+
         // 检查inotify事件，只在游戏列表文件变化时才重新读取
-        if let Ok(events) = inotify.check_events() {
+        if let Ok(events) = self.inotify.check_events() {
             if !events.is_empty() {
                 debug!("Detected changes in games list file");
-                games = read_games_list(GAMES_CONF_PATH)?;
+                self.games = read_games_list(resolve_path(PathKey::Games))?;
                 info!(
                     "The game configuration file has changed. Loaded {} games.",
-                    games.len()
+                    self.games.len()
                 );
             }
         }
 
         // 获取前台应用
-        if app_cache.is_expired(cache_ttl) {
-            match get_foreground_app() {
+        if self.pending_read.is_some() || self.app_cache.is_expired(self.cache_ttl) {
+            let Some(result) = self.read_foreground_app() else {
+                return Ok(());
+            };
+            match result {
                 Ok(package_name) => {
                     // 只有当包名变化时才处理
-                    if package_name != app_cache.package_name {
+                    if package_name != self.app_cache.package_name {
                         // 将前台应用变化的日志改为debug级别
                         debug!("Foreground app changed: {package_name}");
 
                         // 检查是否是游戏
-                        let is_game = games.contains(&package_name);
+                        let is_game = self.games.contains(&package_name);
 
                         // 检查前一个应用是否是游戏
-                        let prev_is_game = !app_cache.package_name.is_empty()
-                            && games.contains(&app_cache.package_name);
+                        let prev_is_game = !self.app_cache.package_name.is_empty()
+                            && self.games.contains(&self.app_cache.package_name);
 
                         // 只有在游戏模式状态变化时才记录info级别日志
                         if is_game {
@@ -249,9 +313,20 @@ pub fn monitor_foreground_app() -> Result<()> {
                             );
                         }
 
+                        // 应用前台应用的配置，切换到其他应用时自动恢复
+                        submit_settings_change(SettingsChange::AppProfile(package_name.clone()));
+
+                        // 游戏离开前台后限制频率，游戏回到前台时解除限制
+                        if self.background_max_freq > 0 {
+                            set_max_cap(
+                                LimitSource::Background,
+                                (!is_game).then_some(self.background_max_freq),
+                            );
+                        }
+
                         // 写入游戏模式文件
                         if let Err(e) = write_file(
-                            GPU_GOVERNOR_GAME_MODE_PATH,
+                            resolve_path(PathKey::GameMode),
                             if is_game { "1" } else { "0" },
                             3,
                         ) {
@@ -265,11 +340,14 @@ pub fn monitor_foreground_app() -> Result<()> {
                     }
 
                     // 更新缓存
-                    app_cache.update(package_name);
+                    self.app_cache.update(package_name);
                 }
                 Err(e) => {
+                    // 失败后同样等待一个读取间隔再重试
+                    self.app_cache.last_update = Instant::now();
+
                     // 使用警告限流器检查是否应该显示警告
-                    if warning_throttler.should_warn() {
+                    if self.warning_throttler.should_warn() {
                         warn!("Failed to get foreground app: {e}");
                     } else {
                         // 如果不应该显示警告，则降级为debug日志
@@ -279,7 +357,92 @@ pub fn monitor_foreground_app() -> Result<()> {
             }
         }
 
+        Ok(())
+    }
+}
+
+impl ForegroundAppHandler {
+    // 读取前台应用，超时返回None并跳过本轮，读取结果留到之后的轮询中取回
+    fn read_foreground_app(&mut self) -> Option<Result<String>> {
+        if let Some(rx) = &self.pending_read {
+            return match rx.try_recv() {
+                Ok(result) => {
+                    self.pending_read = None;
+                    Some(result)
+                }
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => {
+                    self.pending_read = None;
+                    None
+                }
+            };
+        }
+
+        let rx = spawn_foreground_read();
+        match rx.recv_timeout(self.read_timeout) {
+            Ok(result) => Some(result),
+            Err(RecvTimeoutError::Timeout) => {
+                debug!(
+                    "Foreground app read timed out after {}ms, skipping this cycle",
+                    self.read_timeout.as_millis()
+                );
+                self.pending_read = Some(rx);
+                None
+            }
+            Err(RecvTimeoutError::Disconnected) => None,
+        }
+    }
+}
+
+impl PollHandler for ForegroundAppHandler {
+    fn name(&self) -> &'static str {
+        FOREGROUND_APP_THREAD
+    }
+
+    fn tick_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(POLL_INTERVAL_MS))
+    }
+
+    fn on_tick(&mut self) -> Result<()> {
+        self.poll()
+    }
+}
+
+// 监控前台应用
+pub fn monitor_foreground_app(gpu: GPU) -> Result<()> {
+    let mut handler = ForegroundAppHandler::new(&gpu, Duration::ZERO)?;
+
+    // 主循环
+    loop {
+        handler.poll()?;
+
         // 休眠一段时间
-        thread::sleep(Duration::from_millis(100));
+        thread::sleep(Duration::from_millis(POLL_INTERVAL_MS));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn games_list_skips_invalid_entries() {
+        let path =
+            std::env::temp_dir().join(format!("gpugovernor-{}-games.txt", std::process::id()));
+        std::fs::write(
+            &path,
+            "# games\ncom.tencent.tmgp.sgame\n\nre:com.(broken\ncom.miHoYo.*\n",
+        )
+        .unwrap();
+        let games = read_games_list(&path.to_string_lossy()).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(games.len(), 2);
+        assert!(games.contains("com.tencent.tmgp.sgame"));
+        assert!(games.contains("com.miHoYo.GenshinImpact"));
+        assert!(!games.contains("com.broken"));
+
+        // 游戏列表文件不存在时为空
+        assert_eq!(read_games_list("/nonexistent/games.txt").unwrap().len(), 0);
     }
 }
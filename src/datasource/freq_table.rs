@@ -1,5 +1,6 @@
 use std::{
-    fs::File,
+    collections::HashMap,
+    fs::{File, OpenOptions},
     io::{BufRead, BufReader},
     path::Path,
 };
@@ -7,80 +8,145 @@ use std::{
 use anyhow::{Context, Result};
 use log::{debug, info, warn};
 
-use crate::{datasource::file_path::*, model::gpu::GPU, utils::file_operate::check_read_simple};
+use crate::{
+    datasource::file_path::*,
+    model::gpu::GPU,
+    utils::{file_operate::check_read_simple, numeric::parse_i64},
+};
+
+/// 驱动节点探测结果
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NodeProbe {
+    /// 节点存在
+    pub exists: bool,
+    /// 节点可以打开写入
+    pub writable: bool,
+}
+
+impl NodeProbe {
+    // 节点是否可以用于调频
+    fn usable(self) -> bool {
+        self.exists && self.writable
+    }
+
+    fn describe(self) -> &'static str {
+        match (self.exists, self.writable) {
+            (true, true) => "Found, writable",
+            (true, false) => "Found, not writable",
+            _ => "Not Found",
+        }
+    }
+}
+
+/// 驱动类型检测结果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DriverDetection {
+    pub v1_volt: NodeProbe,
+    pub v1_opp: NodeProbe,
+    pub v2_volt: NodeProbe,
+    pub v2_opp: NodeProbe,
+    /// 是否使用v2驱动
+    pub gpuv2: bool,
+    /// 两种驱动的节点探测结果相同（都可用或都不可用），使用了默认的v1驱动
+    pub ambiguous: bool,
+}
+
+impl DriverDetection {
+    /// 当前选择的驱动缺少或无法写入的节点
+    pub fn missing_nodes(&self) -> Vec<&'static str> {
+        let (volt, opp, volt_path, opp_path) = if self.gpuv2 {
+            (self.v2_volt, self.v2_opp, GPUFREQV2_VOLT, GPUFREQV2_OPP)
+        } else {
+            (self.v1_volt, self.v1_opp, GPUFREQ_VOLT, GPUFREQ_OPP)
+        };
+        [(volt, volt_path), (opp, opp_path)]
+            .into_iter()
+            .filter(|(probe, _)| !probe.usable())
+            .map(|(_, path)| path)
+            .collect()
+    }
+}
+
+/// 根据电压和OPP节点的探测结果选择驱动类型
+///
+/// 按 (OPP可写, 电压可写, OPP存在, 电压存在) 依次比较两种驱动，节点更完整的一方胜出；
+/// 两种驱动都可用或都不可用时无法区分，默认使用v1驱动
+pub fn detect_driver_version(probe: impl Fn(&str) -> NodeProbe) -> DriverDetection {
+    let v1_volt = probe(GPUFREQ_VOLT);
+    let v1_opp = probe(GPUFREQ_OPP);
+    let v2_volt = probe(GPUFREQV2_VOLT);
+    let v2_opp = probe(GPUFREQV2_OPP);
+
+    let rank =
+        |volt: NodeProbe, opp: NodeProbe| (opp.usable(), volt.usable(), opp.exists, volt.exists);
+    let v1_rank = rank(v1_volt, v1_opp);
+    let v2_rank = rank(v2_volt, v2_opp);
+
+    DriverDetection {
+        v1_volt,
+        v1_opp,
+        v2_volt,
+        v2_opp,
+        gpuv2: v2_rank > v1_rank,
+        ambiguous: v1_rank == v2_rank,
+    }
+}
+
+// 探测节点是否存在以及能否打开写入，打开时不写入任何内容
+fn probe_node(path: &str) -> NodeProbe {
+    let exists = check_read_simple(path);
+    NodeProbe {
+        exists,
+        writable: exists && OpenOptions::new().write(true).open(path).is_ok(),
+    }
+}
 
 // 检测GPU驱动类型，但不读取系统支持的频率表
 fn detect_gpu_driver_type(gpu: &mut GPU) -> Result<()> {
-    // 检查v1驱动的电压和频率控制文件
-    let v1_volt_exists = Path::new(GPUFREQ_VOLT).exists() && check_read_simple(GPUFREQ_VOLT);
-    let v1_opp_exists = Path::new(GPUFREQ_OPP).exists() && check_read_simple(GPUFREQ_OPP);
-
-    // 检查v2驱动的电压和频率控制文件
-    let v2_volt_exists = Path::new(GPUFREQV2_VOLT).exists() && check_read_simple(GPUFREQV2_VOLT);
-    let v2_opp_exists = Path::new(GPUFREQV2_OPP).exists() && check_read_simple(GPUFREQV2_OPP);
+    let detection = detect_driver_version(probe_node);
 
     // 记录检测到的文件
     info!("GPU Driver Files Detection:");
-    let v1_volt_status = if v1_volt_exists { "Found" } else { "Not Found" };
-    info!("  V1 Voltage File: {v1_volt_status}");
-    let v1_opp_status = if v1_opp_exists { "Found" } else { "Not Found" };
-    info!("  V1 Frequency File: {v1_opp_status}");
-    let v2_volt_status = if v2_volt_exists { "Found" } else { "Not Found" };
-    info!("  V2 Voltage File: {v2_volt_status}");
-    let v2_opp_status = if v2_opp_exists { "Found" } else { "Not Found" };
-    info!("  V2 Frequency File: {v2_opp_status}");
-
-    // 检查v1驱动
-    if v1_volt_exists || v1_opp_exists {
-        gpu.set_gpuv2(false);
-        gpu.set_dcs_enable(false);
-        info!("Detected gpufreq Driver (v1)");
+    info!("  V1 Voltage File: {}", detection.v1_volt.describe());
+    info!("  V1 Frequency File: {}", detection.v1_opp.describe());
+    info!("  V2 Voltage File: {}", detection.v2_volt.describe());
+    info!("  V2 Frequency File: {}", detection.v2_opp.describe());
 
-        // 警告如果某些文件不存在
-        if !v1_volt_exists {
-            warn!("V1 voltage control file not found: {GPUFREQ_VOLT}");
-        }
-        if !v1_opp_exists {
-            warn!("V1 frequency control file not found: {GPUFREQ_OPP}");
-        }
+    gpu.set_gpuv2(detection.gpuv2);
+    gpu.set_dcs_enable(detection.gpuv2);
 
+    if detection.ambiguous {
+        if detection.v1_opp.usable() {
+            warn!("Both gpufreq (v1) and gpufreqv2 (v2) nodes are usable, defaulting to v1");
+        } else {
+            warn!("No valid GPU frequency driver detected, defaulting to gpufreq (v1)");
+            warn!("The program may not be able to control GPU frequency!");
+        }
         return Ok(());
     }
 
-    // 检查v2驱动
-    if v2_volt_exists || v2_opp_exists {
-        gpu.set_gpuv2(true);
-        gpu.set_dcs_enable(true);
+    if detection.gpuv2 {
         info!("Detected gpufreqv2 Driver (v2)");
-
-        // 警告如果某些文件不存在
-        if !v2_volt_exists {
-            warn!("V2 voltage control file not found: {GPUFREQV2_VOLT}");
-        }
-        if !v2_opp_exists {
-            warn!("V2 frequency control file not found: {GPUFREQV2_OPP}");
-        }
-
-        return Ok(());
+    } else {
+        info!("Detected gpufreq Driver (v1)");
+    }
+    // 警告如果某些文件不存在或不可写
+    for path in detection.missing_nodes() {
+        warn!("Control file not found or not writable: {path}");
     }
-
-    // 如果没有检测到任何驱动，默认使用v1
-    warn!("No valid GPU frequency driver detected, defaulting to gpufreq (v1)");
-    warn!("The program may not be able to control GPU frequency!");
-    gpu.set_gpuv2(false);
-    gpu.set_dcs_enable(false);
 
     Ok(())
 }
 
-// 读取v2 driver设备的频率表
-fn read_v2_driver_freq_table() -> Result<Vec<i64>> {
+// 读取v2 driver设备的频率表，同时返回驱动默认电压
+fn read_v2_driver_freq_table() -> Result<(Vec<i64>, HashMap<i64, i64>)> {
     let mut freq_list = Vec::new();
+    let mut def_volt = HashMap::new();
 
     // 检查频率表文件是否存在
     if !Path::new(GPUFREQV2_TABLE).exists() || !check_read_simple(GPUFREQV2_TABLE) {
         warn!("V2 driver frequency table file not found: {GPUFREQV2_TABLE}");
-        return Ok(freq_list);
+        return Ok((freq_list, def_volt));
     }
 
     // 打开并读取频率表文件
@@ -97,9 +163,12 @@ fn read_v2_driver_freq_table() -> Result<Vec<i64>> {
         // 查找频率值
         if let Some(freq_pos) = line.find("freq: ") {
             let freq_str = line[freq_pos + 6..].split(',').next().unwrap_or("0");
-            if let Ok(freq) = freq_str.trim().parse::<i64>() {
+            if let Some(freq) = parse_i64(freq_str) {
                 freq_list.push(freq);
                 debug!("Found V2 driver frequency: {freq}");
+                if let Some(volt) = opp_field(&line, &["volt: "]) {
+                    def_volt.insert(freq, volt);
+                }
             }
         }
     }
@@ -109,7 +178,57 @@ fn read_v2_driver_freq_table() -> Result<Vec<i64>> {
 
     info!("Read {} frequencies from V2 driver table", freq_list.len());
 
-    Ok(freq_list)
+    Ok((freq_list, def_volt))
+}
+
+// 读取OPP表一行中某个字段的值，字段值以逗号结束
+fn opp_field(line: &str, keys: &[&str]) -> Option<i64> {
+    keys.iter().find_map(|key| {
+        let pos = line.find(key)?;
+        parse_i64(line[pos + key.len()..].split(',').next()?)
+    })
+}
+
+// 读取v1 driver的默认OPP表电压，文件不存在时返回空表
+fn read_v1_default_volt_table() -> HashMap<i64, i64> {
+    let mut def_volt = HashMap::new();
+    let Ok(file) = File::open(GPUFREQ_OPP_DUMP) else {
+        debug!("V1 driver OPP table not readable: {GPUFREQ_OPP_DUMP}");
+        return def_volt;
+    };
+
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        if let (Some(freq), Some(volt)) = (
+            opp_field(&line, &["freq = "]),
+            opp_field(&line, &["volt = ", "vgpu = "]),
+        ) {
+            def_volt.insert(freq, volt);
+        }
+    }
+    info!(
+        "Read {} default voltages from V1 driver table",
+        def_volt.len()
+    );
+    def_volt
+}
+
+/// 按驱动默认电压校验频率表电压并为缺少电压的频率插值，修改时输出汇总
+pub fn validate_table_voltages(gpu: &mut GPU) {
+    let adjustments = gpu.frequency_mut().validate_voltages();
+    if !adjustments.is_empty() {
+        warn!(
+            "{} frequency table voltages adjusted against the driver OPP table",
+            adjustments.len()
+        );
+    }
+
+    let interpolated = gpu.frequency_mut().interpolate_missing_volts();
+    if !interpolated.is_empty() {
+        info!(
+            "{} missing frequency table voltages interpolated",
+            interpolated.len()
+        );
+    }
 }
 
 // 检测内存频率控制文件
@@ -150,19 +269,34 @@ fn detect_ddr_freq_paths() -> Result<()> {
     Ok(())
 }
 
+/// 校验频率表中的频率是否都被v2驱动支持，返回不支持的频率数量
+pub fn validate_freq_table(gpu: &GPU) -> usize {
+    let unsupported: Vec<i64> = gpu
+        .get_config_list()
+        .into_iter()
+        .filter(|&freq| !gpu.is_freq_supported_by_v2_driver(freq))
+        .collect();
+
+    if !unsupported.is_empty() {
+        warn!("Frequencies not supported by V2 driver: {unsupported:?}");
+    }
+    unsupported.len()
+}
+
 pub fn gpufreq_table_init(gpu: &mut GPU) -> Result<()> {
     // 检测GPU驱动类型
     detect_gpu_driver_type(gpu)?;
 
     // 检测内存频率控制文件
     detect_ddr_freq_paths()?; // 读取系统支持的频率表
-    let v2_supported_freqs = if gpu.is_gpuv2() {
+    let (v2_supported_freqs, def_volt) = if gpu.is_gpuv2() {
         info!("Reading V2 driver frequency table");
         read_v2_driver_freq_table()?
     } else {
-        // V1 driver使用配置文件中的频率，不需要读取系统频率表
-        Vec::new()
+        // V1 driver使用配置文件中的频率，不需要读取系统频率表，只读取默认电压
+        (Vec::new(), read_v1_default_volt_table())
     };
+    gpu.frequency_mut().set_def_volt(def_volt);
 
     // 保存v2 driver支持的频率列表到GPU对象
     if gpu.is_gpuv2() && !v2_supported_freqs.is_empty() {
@@ -224,5 +358,6 @@ pub fn gpufreq_table_init(gpu: &mut GPU) -> Result<()> {
         warn!("No frequencies in config list yet");
     }
 
+    validate_table_voltages(gpu);
     Ok(())
 }
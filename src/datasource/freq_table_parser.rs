@@ -1,19 +1,25 @@
 use std::{
     collections::HashMap,
+    fmt,
     fs::{self},
+    ops::Range,
 };
 
 use anyhow::Result;
 use log::{error, info, warn};
 use serde::Deserialize;
+use toml::Spanned;
 
-use crate::model::gpu::{TabType, GPU};
+use crate::{
+    datasource::{devfreq::read_devfreq_freq_table, freq_table::validate_table_voltages},
+    model::gpu::{TabType, GPU},
+};
 
 #[derive(Deserialize)]
 struct FreqTableEntry {
-    freq: i64,
-    volt: i64,
-    ddr_opp: i64,
+    freq: Spanned<i64>,
+    volt: Spanned<i64>,
+    ddr_opp: Spanned<i64>,
 }
 
 #[derive(Deserialize)]
@@ -22,24 +28,99 @@ struct FreqTableConfig {
     freq_table: Vec<FreqTableEntry>,
 }
 
+/// 频率表解析错误 - 包含出错的行号和具体内容，方便用户定位问题
+#[derive(Debug)]
+pub enum FreqTableError {
+    /// TOML语法或字段类型错误
+    Parse {
+        line: usize,
+        token: String,
+        message: String,
+    },
+    /// 字段值无效
+    InvalidValue {
+        line: usize,
+        field: &'static str,
+        token: String,
+    },
+}
+
+impl fmt::Display for FreqTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse {
+                line,
+                token,
+                message,
+            } => write!(f, "line {line}: failed to parse '{token}': {message}"),
+            Self::InvalidValue { line, field, token } => {
+                write!(f, "line {line}: invalid {field} '{token}'")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FreqTableError {}
+
+impl FreqTableError {
+    fn from_toml(content: &str, e: &toml::de::Error) -> Self {
+        let span = e.span().unwrap_or(0..0);
+        Self::Parse {
+            line: line_of(content, span.start),
+            token: token_at(content, span),
+            message: e.message().trim().to_string(),
+        }
+    }
+}
+
+/// 计算字节偏移所在的行号（从1开始）
+fn line_of(content: &str, offset: usize) -> usize {
+    content
+        .get(..offset.min(content.len()))
+        .map_or(0, |prefix| prefix.matches('\n').count())
+        + 1
+}
+
+/// 获取出错位置的内容，空范围时取所在行的剩余部分
+fn token_at(content: &str, span: Range<usize>) -> String {
+    if span.start < span.end {
+        if let Some(token) = content.get(span.clone()) {
+            return token.trim().to_string();
+        }
+    }
+
+    content
+        .get(span.start.min(content.len())..)
+        .and_then(|rest| rest.lines().next())
+        .unwrap_or("")
+        .trim()
+        .to_string()
+}
+
 fn volt_is_valid(v: i64) -> bool {
     v != 0 && v % 625 == 0
 }
 
 pub fn freq_table_read(config_file: &str, gpu: &mut GPU) -> Result<()> {
     let file = fs::read_to_string(config_file)?;
-    let toml: FreqTableConfig = toml::from_str(&file)?;
+    let toml: FreqTableConfig =
+        toml::from_str(&file).map_err(|e| FreqTableError::from_toml(&file, &e))?;
     let mut new_config_list = Vec::new();
     let mut new_fvtab = HashMap::new();
     let mut new_fdtab = HashMap::new();
 
     for entry in toml.freq_table {
-        let freq = entry.freq;
-        let volt = entry.volt;
-        let dram = entry.ddr_opp;
+        let freq = *entry.freq.get_ref();
+        let volt = *entry.volt.get_ref();
+        let dram = *entry.ddr_opp.get_ref();
 
         if !volt_is_valid(volt) {
-            error!("Entry freq={freq}, volt={volt}, ddr_opp={dram} is invalid: volt {volt} is not valid");
+            let e = FreqTableError::InvalidValue {
+                line: line_of(&file, entry.volt.span().start),
+                field: "volt",
+                token: token_at(&file, entry.volt.span()),
+            };
+            error!("Entry freq={freq}, volt={volt}, ddr_opp={dram} is skipped: {e}");
             continue;
         }
 
@@ -73,6 +154,7 @@ pub fn freq_table_read(config_file: &str, gpu: &mut GPU) -> Result<()> {
     gpu.set_config_list(new_config_list);
     gpu.replace_tab(TabType::FreqVolt, new_fvtab);
     gpu.replace_tab(TabType::FreqDram, new_fdtab);
+    validate_table_voltages(gpu);
 
     info!("Load frequency table config succeed");
 
@@ -83,3 +165,86 @@ pub fn freq_table_read(config_file: &str, gpu: &mut GPU) -> Result<()> {
     }
     Ok(())
 }
+
+/// 从devfreq可用频率生成频率表，电压和DDR档位不固定（交由驱动决定）
+pub fn freq_table_read_devfreq(gpu: &mut GPU) -> Result<()> {
+    let freqs = read_devfreq_freq_table()?;
+
+    let new_fvtab: HashMap<i64, i64> = freqs.iter().map(|&freq| (freq, 0)).collect();
+    let new_fdtab: HashMap<i64, i64> = freqs.iter().map(|&freq| (freq, 0)).collect();
+
+    info!(
+        "Loaded {} frequency entries from devfreq: {freqs:?}",
+        freqs.len()
+    );
+
+    gpu.set_config_list(freqs);
+    gpu.replace_tab(TabType::FreqVolt, new_fvtab);
+    gpu.replace_tab(TabType::FreqDram, new_fdtab);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(content: &str) -> FreqTableError {
+        match toml::from_str::<FreqTableConfig>(content) {
+            Ok(_) => panic!("table should not parse"),
+            Err(e) => FreqTableError::from_toml(content, &e),
+        }
+    }
+
+    fn read_table(name: &str, content: &str, gpu: &mut GPU) -> Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("gpugovernor-{}-{name}.toml", std::process::id()));
+        fs::write(&path, content).unwrap();
+        let result = freq_table_read(path.to_str().unwrap(), gpu);
+        let _ = fs::remove_file(&path);
+        result
+    }
+
+    #[test]
+    fn parse_error_names_bad_token() {
+        let content = "[[freq_table]]\nfreq = 850000\nvolt = 8O0000\nddr_opp = 999\n";
+        let e = parse_error(content);
+        assert!(matches!(e, FreqTableError::Parse { line: 3, .. }), "{e}");
+        assert!(e.to_string().contains("8O0000"), "{e}");
+    }
+
+    #[test]
+    fn parse_error_for_wrong_type() {
+        let content = "[[freq_table]]\nfreq = \"fast\"\nvolt = 80000\nddr_opp = 999\n";
+        let e = parse_error(content);
+        assert!(matches!(e, FreqTableError::Parse { line: 2, .. }), "{e}");
+        assert!(e.to_string().contains("\"fast\""), "{e}");
+    }
+
+    #[test]
+    fn invalid_volt_entries_are_skipped() {
+        let mut gpu = GPU::new();
+        let content = "[[freq_table]]\nfreq = 850000\nvolt = 80000\nddr_opp = 999\n\n\
+                       [[freq_table]]\nfreq = 900000\nvolt = 80001\nddr_opp = 999\n";
+        read_table("skip", content, &mut gpu).unwrap();
+        assert_eq!(gpu.get_config_list(), vec![850000]);
+
+        let content = "[[freq_table]]\nfreq = 850000\nvolt = 0\nddr_opp = 999\n";
+        assert!(read_table("empty", content, &mut gpu).is_err());
+    }
+
+    #[test]
+    fn invalid_value_reports_line() {
+        let content = "[[freq_table]]\nfreq = 850000\nvolt = 80001\nddr_opp = 999\n";
+        let entry = toml::from_str::<FreqTableConfig>(content)
+            .unwrap()
+            .freq_table
+            .remove(0);
+        let e = FreqTableError::InvalidValue {
+            line: line_of(content, entry.volt.span().start),
+            field: "volt",
+            token: token_at(content, entry.volt.span()),
+        };
+        assert_eq!(e.to_string(), "line 3: invalid volt '80001'");
+    }
+}
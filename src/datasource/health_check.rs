@@ -0,0 +1,160 @@
+use log::{info, warn};
+
+use crate::{
+    datasource::{
+        freq_table::validate_freq_table,
+        load_monitor::{
+            detected_load_sources, get_precise_load_node, is_precise_load_available,
+            PreciseLoadNode,
+        },
+    },
+    model::gpu::{TabType, GPU},
+};
+
+// 单项检查结果，问题以WARN输出，正常以INFO输出
+struct HealthReport {
+    warnings: usize,
+}
+
+impl HealthReport {
+    fn ok(&self, check: &str, detail: &str) {
+        info!("[health] {check}: OK ({detail})");
+    }
+
+    fn warn(&mut self, check: &str, detail: &str) {
+        warn!("[health] {check}: {detail}");
+        self.warnings += 1;
+    }
+}
+
+// 频率表中的频率是否都被v2驱动支持
+fn check_freq_table(gpu: &GPU, report: &mut HealthReport) {
+    if !gpu.is_gpuv2() {
+        report.ok("freq table", "v1 driver uses the configured table");
+        return;
+    }
+    let unsupported = validate_freq_table(gpu);
+    let total = gpu.get_config_list().len();
+    if unsupported > 0 {
+        report.warn(
+            "freq table",
+            &format!("{unsupported}/{total} frequencies are not supported by the V2 driver and will be snapped"),
+        );
+    } else {
+        report.ok("freq table", &format!("{total} frequencies supported"));
+    }
+}
+
+// 频率表的范围是否在驱动支持的范围内
+fn check_freq_bounds(gpu: &GPU, report: &mut HealthReport) {
+    let (min_freq, max_freq) = (gpu.get_min_freq(), gpu.get_max_freq());
+    let supported = gpu.get_v2_supported_freqs();
+    if let (Some(&hw_min), Some(&hw_max)) = (supported.iter().min(), supported.iter().max()) {
+        if min_freq < hw_min || max_freq > hw_max {
+            report.warn(
+                "freq bounds",
+                &format!(
+                    "table {min_freq}-{max_freq}KHz exceeds driver range {hw_min}-{hw_max}KHz"
+                ),
+            );
+        } else {
+            report.ok(
+                "freq bounds",
+                &format!("table {min_freq}-{max_freq}KHz within driver range {hw_min}-{hw_max}KHz"),
+            );
+        }
+    }
+
+    // 配置的频率上下限超出频率表范围时会被截断到表中的频率
+    let strategy = &gpu.frequency_strategy;
+    let limits = [
+        ("background_max_freq", strategy.background_max_freq),
+        ("touch_boost_freq", strategy.touch_boost_freq),
+        ("frame_drop_boost_freq", strategy.frame_drop_boost_freq),
+        ("thermal_cap_freq", strategy.thermal_cap_freq),
+        ("battery_saver_max_freq", strategy.battery_saver_max_freq),
+    ];
+    for (name, freq) in limits {
+        if freq > 0 && (freq < min_freq || freq > max_freq) {
+            report.warn(
+                "freq bounds",
+                &format!("{name}={freq}KHz is outside the table range {min_freq}-{max_freq}KHz"),
+            );
+        }
+    }
+}
+
+// 频率表中配置的DDR_OPP是否都在DDR频率表中
+fn check_ddr_table(gpu: &GPU, report: &mut HealthReport) {
+    let ddr_table = match gpu.ddr_manager().get_ddr_freq_table() {
+        Ok(table) => table,
+        Err(e) => {
+            report.warn("ddr table", &format!("failed to read DDR table: {e}"));
+            return;
+        }
+    };
+
+    // 999和负数表示不固定内存频率
+    let mut unknown: Vec<i64> = gpu
+        .get_config_list()
+        .into_iter()
+        .map(|freq| gpu.read_tab(TabType::FreqDram, freq))
+        .filter(|&ddr_opp| ddr_opp != 999 && ddr_opp >= 0)
+        .filter(|ddr_opp| !ddr_table.iter().any(|(opp, _)| opp == ddr_opp))
+        .collect();
+    unknown.sort_unstable();
+    unknown.dedup();
+
+    if unknown.is_empty() {
+        report.ok(
+            "ddr table",
+            &format!("{} DDR OPP values known", ddr_table.len()),
+        );
+    } else {
+        report.warn(
+            "ddr table",
+            &format!("configured DDR OPP values not in DDR table: {unknown:?}"),
+        );
+    }
+}
+
+// 配置的精确负载节点是否存在，是否检测到可用的负载源
+fn check_load_sources(report: &mut HealthReport) {
+    let node = get_precise_load_node();
+    if node != PreciseLoadNode::Auto && !is_precise_load_available() {
+        report.warn(
+            "load source",
+            &format!("precise_load_node={node:?} is configured but the node is not available"),
+        );
+    }
+
+    let detected = detected_load_sources();
+    if detected.is_empty() {
+        report.warn(
+            "load source",
+            "no load source detected, load will read as -1",
+        );
+    } else {
+        report.ok("load source", &format!("detected {}", detected.join(",")));
+    }
+}
+
+/// 启动时对照驱动实际支持的能力检查配置，汇总输出到日志
+pub fn run_config_health_check(gpu: &GPU) {
+    info!("Config health check:");
+    let mut report = HealthReport { warnings: 0 };
+
+    check_freq_table(gpu, &mut report);
+    check_freq_bounds(gpu, &mut report);
+    check_ddr_table(gpu, &mut report);
+    check_load_sources(&mut report);
+
+    if report.warnings > 0 {
+        warn!(
+            "Config health check found {} issue(s), see [health] lines above",
+            report.warnings
+        );
+    } else {
+        info!("Config health check passed");
+    }
+}
@@ -1,267 +1,849 @@
 use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
 };
 
 use anyhow::{anyhow, Context, Result};
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
+use once_cell::sync::Lazy;
 
 use crate::{
-    datasource::file_path::*,
+    datasource::{
+        devfreq::gpu_devfreq_node,
+        file_path::*,
+        node_reader::{NodeReader, SysfsReader},
+    },
     utils::{
-        file_operate::{check_read, read_file},
-        file_status::{get_status, write_status},
+        file_operate::check_read,
+        numeric::{parse_i32, parse_i64},
     },
 };
 
-fn module_ged_load() -> Result<i32> {
-    if !get_status(MODULE_LOAD) {
-        return Ok(-1);
+// 精确模式：是否优先使用debug_dvfs计数器负载源
+static PRECISE_LOAD: AtomicBool = AtomicBool::new(false);
+
+/// 精确负载节点选择
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PreciseLoadNode {
+    /// 自动选择可用的节点，优先新节点（默认）
+    Auto,
+    /// 强制使用新节点
+    New,
+    /// 强制使用旧节点
+    Old,
+}
+
+impl PreciseLoadNode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "auto" => Some(Self::Auto),
+            "new" => Some(Self::New),
+            "old" => Some(Self::Old),
+            _ => None,
+        }
     }
+}
 
-    let buf = read_file(MODULE_LOAD, 32)?;
-    let load = buf
-        .trim()
-        .parse::<i32>()
-        .with_context(|| format!("Failed to parse GPU load from {MODULE_LOAD}"))?;
+// 精确负载节点选择，由配置设置
+static PRECISE_LOAD_NODE: AtomicU8 = AtomicU8::new(PreciseLoadNode::Auto as u8);
 
-    Ok(load)
+/// 设置精确负载节点选择
+pub fn set_precise_load_node(node: PreciseLoadNode) {
+    PRECISE_LOAD_NODE.store(node as u8, Ordering::SeqCst);
+    debug!("Set precise load node: {node:?}");
 }
 
-fn module_ged_idle() -> Result<i32> {
-    if !get_status(MODULE_IDLE) {
-        return module_ged_load();
+/// 获取精确负载节点选择
+pub fn get_precise_load_node() -> PreciseLoadNode {
+    match PRECISE_LOAD_NODE.load(Ordering::SeqCst) {
+        x if x == PreciseLoadNode::New as u8 => PreciseLoadNode::New,
+        x if x == PreciseLoadNode::Old as u8 => PreciseLoadNode::Old,
+        _ => PreciseLoadNode::Auto,
     }
+}
 
-    let buf = read_file(MODULE_IDLE, 32)?;
-    let idle = buf
-        .trim()
-        .parse::<i32>()
-        .with_context(|| format!("Failed to parse GPU idle from {MODULE_IDLE}"))?;
-
-    let load = 100 - idle;
-    debug!("module {load}");
-    Ok(100 - idle)
+/// 多负载源融合方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoadFusion {
+    /// 按优先级使用第一个有负载的负载源（默认）
+    First,
+    /// 取所有可用负载源中的最大值
+    Max,
+    /// 取所有可用负载源的平均值
+    Mean,
+    /// 取所有可用负载源的中位数
+    Median,
 }
 
-fn kernel_ged_load() -> Result<i32> {
-    if !get_status(KERNEL_LOAD) {
-        return module_ged_idle();
+impl LoadFusion {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "first" => Some(Self::First),
+            "max" => Some(Self::Max),
+            "mean" => Some(Self::Mean),
+            "median" => Some(Self::Median),
+            _ => None,
+        }
     }
 
-    let buf = read_file(KERNEL_LOAD, 32)?;
-    let parts: Vec<&str> = buf.split_whitespace().collect();
+    /// 合并多个负载源的读数，没有读数时返回None
+    pub fn combine(self, loads: &[i32]) -> Option<i32> {
+        if loads.is_empty() {
+            return None;
+        }
+        match self {
+            Self::First => loads.first().copied(),
+            Self::Max => loads.iter().copied().max(),
+            Self::Mean => {
+                Some((loads.iter().sum::<i32>() as f64 / loads.len() as f64).round() as i32)
+            }
+            Self::Median => {
+                let mut sorted = loads.to_vec();
+                sorted.sort_unstable();
+                let mid = sorted.len() / 2;
+                if sorted.len().is_multiple_of(2) {
+                    Some((sorted[mid - 1] + sorted[mid] + 1) / 2)
+                } else {
+                    Some(sorted[mid])
+                }
+            }
+        }
+    }
+}
 
-    if parts.len() >= 3 {
-        if let Ok(idle) = parts[2].parse::<i32>() {
-            let load = 100 - idle;
-            debug!("gedload {load}");
-            return Ok(if 100 - idle == 0 {
-                module_ged_load()?
+// 多负载源融合方式，由配置设置
+static LOAD_FUSION: AtomicU8 = AtomicU8::new(LoadFusion::First as u8);
+
+/// 设置多负载源融合方式
+pub fn set_load_fusion(fusion: LoadFusion) {
+    LOAD_FUSION.store(fusion as u8, Ordering::SeqCst);
+    debug!("Set load fusion: {fusion:?}");
+}
+
+/// 获取多负载源融合方式
+pub fn get_load_fusion() -> LoadFusion {
+    match LOAD_FUSION.load(Ordering::SeqCst) {
+        x if x == LoadFusion::Max as u8 => LoadFusion::Max,
+        x if x == LoadFusion::Mean as u8 => LoadFusion::Mean,
+        x if x == LoadFusion::Median as u8 => LoadFusion::Median,
+        _ => LoadFusion::First,
+    }
+}
+
+// 按配置选择精确负载节点，强制选择的节点不可用时返回None
+fn precise_load_path(reader: &dyn NodeReader) -> Option<&'static str> {
+    match get_precise_load_node() {
+        PreciseLoadNode::New => reader
+            .is_available(DEBUG_DVFS_LOAD)
+            .then_some(DEBUG_DVFS_LOAD),
+        PreciseLoadNode::Old => reader
+            .is_available(DEBUG_DVFS_LOAD_OLD)
+            .then_some(DEBUG_DVFS_LOAD_OLD),
+        PreciseLoadNode::Auto => {
+            if reader.is_available(DEBUG_DVFS_LOAD) {
+                Some(DEBUG_DVFS_LOAD)
+            } else if reader.is_available(DEBUG_DVFS_LOAD_OLD) {
+                Some(DEBUG_DVFS_LOAD_OLD)
             } else {
-                100 - idle
-            });
+                None
+            }
         }
     }
+}
 
-    module_ged_idle()
+/// debug_dvfs计数器的一次采样值
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DvfsSnapshot {
+    pub busy: i64,
+    pub idle: i64,
+    pub protm: i64,
 }
 
-fn kernel_debug_ged_load() -> Result<i32> {
-    if !get_status(KERNEL_D_LOAD) {
-        return kernel_ged_load();
+impl DvfsSnapshot {
+    /// 计算相对上一次采样的负载，两次采样间总增量不为正（包括计数器被重置）时返回None
+    pub fn load_since(&self, previous: &Self) -> Option<i32> {
+        // 这里不使用counter_delta：debug_dvfs的三个计数器由驱动一起重置，直接相减得到的
+        // 负增量会让这次采样没有负载（或负载为0），按32位回绕处理反而会算出错误的负载
+        let diff_busy = self.busy - previous.busy;
+        let diff_idle = self.idle - previous.idle;
+        let diff_protm = self.protm - previous.protm;
+
+        let total = diff_busy + diff_idle + diff_protm;
+        if total <= 0 {
+            return None;
+        }
+        let load = (((diff_busy + diff_protm) * 100 / total) as i32).max(0);
+        debug!("debugutil: {load} {diff_busy} {diff_idle} {diff_protm}");
+        Some(load)
     }
+}
 
-    let buf = read_file(KERNEL_D_LOAD, 32)?;
-    let parts: Vec<&str> = buf.split_whitespace().collect();
+// debug_dvfs计数器的上一次采样值，读取和更新在同一把锁内完成
+static PREV_DVFS: Mutex<DvfsSnapshot> = Mutex::new(DvfsSnapshot {
+    busy: 0,
+    idle: 0,
+    protm: 0,
+});
+
+// 活动时间/总时间计数器的上一次采样值 (active, total)
+static PREV_TIME_COUNTERS: Mutex<(i64, i64)> = Mutex::new((0, 0));
+
+// 计算活动时间/总时间计数器的增量，当前值小于上次值时按32位回绕处理，超出32位范围则视为计数器被重置
+fn counter_delta(current: i64, previous: i64) -> i64 {
+    if current >= previous {
+        current - previous
+    } else if previous <= u32::MAX as i64 {
+        current + (u32::MAX as i64 + 1 - previous)
+    } else {
+        current
+    }
+}
 
-    if parts.len() >= 3 {
-        if let Ok(idle) = parts[2].parse::<i32>() {
-            let load = 100 - idle;
-            debug!("dbggedload {load}");
-            return Ok(if 100 - idle == 0 {
-                kernel_ged_load()?
-            } else {
-                100 - idle
-            });
+/// 设置是否使用精确负载源
+pub fn set_precise_load(enabled: bool) {
+    PRECISE_LOAD.store(enabled, Ordering::SeqCst);
+}
+
+/// 是否正在使用精确负载源
+pub fn is_precise_load_enabled() -> bool {
+    PRECISE_LOAD.load(Ordering::SeqCst)
+}
+
+/// 精确负载源节点是否可用
+pub fn is_precise_load_available() -> bool {
+    precise_load_path(&SysfsReader).is_some()
+}
+
+/// 重置debug_dvfs计数器，避免切换负载源后第一次采样使用过期的差值
+pub fn reset_precise_load_state() {
+    *PREV_DVFS.lock().unwrap() = DvfsSnapshot::default();
+    *PREV_TIME_COUNTERS.lock().unwrap() = (0, 0);
+}
+
+// 负载源 - 每个负载源只负责读取和解析自己的节点，回退顺序由负载源表决定
+struct LoadSource {
+    name: &'static str,
+    // 负载源节点是否可用
+    available: fn(&dyn NodeReader) -> bool,
+    // 读取一次负载，节点内容无法解析时返回None
+    read: fn(&dyn NodeReader) -> Result<Option<i32>>,
+}
+
+// 负载源表，按自动选择时的优先级排列，debug_dvfs只在精确模式下参与自动选择
+static LOAD_SOURCES: &[LoadSource] = &[
+    LoadSource {
+        name: "debug_dvfs",
+        available: |reader| precise_load_path(reader).is_some(),
+        read: debug_dvfs_read,
+    },
+    LoadSource {
+        name: "gpufreq",
+        available: |reader| reader.is_available(GPU_FREQ_LOAD_PATH),
+        read: gpufreq_read,
+    },
+    LoadSource {
+        name: "mtk_mali",
+        available: |reader| reader.is_available(PROC_MTK_LOAD),
+        read: mtk_read,
+    },
+    LoadSource {
+        name: "mali",
+        available: |reader| reader.is_available(PROC_MALI_LOAD),
+        read: mali_read,
+    },
+    LoadSource {
+        name: "ged_kernel_d",
+        available: |reader| reader.is_available(KERNEL_DEBUG_LOAD),
+        read: |reader| ged_utilization_read(reader, KERNEL_DEBUG_LOAD),
+    },
+    LoadSource {
+        name: "ged_kernel_debug",
+        available: |reader| reader.is_available(KERNEL_D_LOAD),
+        read: |reader| ged_utilization_read(reader, KERNEL_D_LOAD),
+    },
+    LoadSource {
+        name: "ged_kernel",
+        available: |reader| reader.is_available(KERNEL_LOAD),
+        read: |reader| ged_utilization_read(reader, KERNEL_LOAD),
+    },
+    LoadSource {
+        name: "ged_module_idle",
+        available: |reader| reader.is_available(MODULE_IDLE),
+        read: module_ged_idle_read,
+    },
+    LoadSource {
+        name: "ged_module",
+        available: |reader| reader.is_available(MODULE_LOAD),
+        read: module_ged_load_read,
+    },
+    LoadSource {
+        name: "time_counter",
+        available: |reader| reader.is_available(GPU_TIME_COUNTERS),
+        read: time_counter_read,
+    },
+    LoadSource {
+        name: "devfreq",
+        available: |reader| devfreq_load_path(reader).is_some(),
+        read: devfreq_read,
+    },
+];
+
+// debug_dvfs在负载源表中的位置
+const DEBUG_DVFS_SOURCE: usize = 0;
+// 未指定负载源或尚未读取到负载时的索引
+const NO_SOURCE: usize = usize::MAX;
+
+// 通过控制命令指定的负载源，NO_SOURCE表示自动选择
+static SELECTED_SOURCE: AtomicUsize = AtomicUsize::new(NO_SOURCE);
+// 最近一次提供负载的负载源
+static ACTIVE_SOURCE: AtomicUsize = AtomicUsize::new(NO_SOURCE);
+// 各负载源的校准系数，与负载源表一一对应
+static LOAD_CALIBRATION: Lazy<Mutex<Vec<f64>>> =
+    Lazy::new(|| Mutex::new(vec![1.0; LOAD_SOURCES.len()]));
+// 自动选择和融合时尝试负载源的顺序（负载源表中的索引），不包含被禁用的负载源
+static LOAD_SOURCE_ORDER: Lazy<Mutex<Vec<usize>>> =
+    Lazy::new(|| Mutex::new((0..LOAD_SOURCES.len()).collect()));
+// 已警告过读数超出范围的负载源，每个负载源只警告一次
+static INVALID_LOAD_WARNED: Lazy<Mutex<HashSet<usize>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// 设置负载源校准系数，未配置的负载源使用1.0
+pub fn set_load_calibration(factors: &HashMap<String, f64>) {
+    let mut calibration = vec![1.0; LOAD_SOURCES.len()];
+    for (name, &factor) in factors {
+        let Some(index) = LOAD_SOURCES.iter().position(|source| source.name == name) else {
+            warn!("Unknown load source '{name}' in load_calibration, ignored");
+            continue;
+        };
+        if !factor.is_finite() || factor <= 0.0 {
+            warn!("Invalid load_calibration factor {factor} for {name}, using 1.0");
+            continue;
+        }
+        calibration[index] = factor;
+        debug!("Set load calibration: {name} x{factor}");
+    }
+    *LOAD_CALIBRATION.lock().unwrap() = calibration;
+}
+
+/// 设置负载源优先级：配置中列出的负载源按列出的顺序排在前面，其余按默认顺序排在后面，禁用的负载源不参与自动选择
+pub fn set_load_source_order(order: &[String], disabled: &[String]) {
+    let find = |name: &str| LOAD_SOURCES.iter().position(|source| source.name == name);
+
+    let mut disabled_indices = HashSet::new();
+    for name in disabled {
+        match find(name) {
+            Some(index) => {
+                disabled_indices.insert(index);
+            }
+            None => warn!("Unknown load source '{name}' in disabled_load_sources, ignored"),
+        }
+    }
+
+    let mut indices = Vec::with_capacity(LOAD_SOURCES.len());
+    for name in order {
+        match find(name) {
+            Some(index) if !indices.contains(&index) => indices.push(index),
+            Some(_) => warn!("Duplicate load source '{name}' in load_source_order, ignored"),
+            None => warn!("Unknown load source '{name}' in load_source_order, ignored"),
         }
     }
+    for index in 0..LOAD_SOURCES.len() {
+        if !indices.contains(&index) {
+            indices.push(index);
+        }
+    }
+    indices.retain(|index| !disabled_indices.contains(index));
+
+    debug!(
+        "Set load source order: {:?}",
+        indices
+            .iter()
+            .map(|&index| LOAD_SOURCES[index].name)
+            .collect::<Vec<_>>()
+    );
+    *LOAD_SOURCE_ORDER.lock().unwrap() = indices;
+}
 
-    kernel_ged_load()
+/// 自动选择时尝试负载源的顺序，不包含被禁用的负载源
+pub fn get_load_source_order() -> Vec<&'static str> {
+    LOAD_SOURCE_ORDER
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|&index| LOAD_SOURCES[index].name)
+        .collect()
 }
 
-fn kernel_d_ged_load() -> Result<i32> {
-    if !get_status(KERNEL_DEBUG_LOAD) {
-        return kernel_debug_ged_load();
+/// 被禁用的负载源
+pub fn get_disabled_load_sources() -> Vec<&'static str> {
+    let order = LOAD_SOURCE_ORDER.lock().unwrap();
+    LOAD_SOURCES
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !order.contains(index))
+        .map(|(_, source)| source.name)
+        .collect()
+}
+
+/// 非默认的负载源校准系数
+pub fn get_load_calibration() -> Vec<(&'static str, f64)> {
+    let calibration = LOAD_CALIBRATION.lock().unwrap();
+    LOAD_SOURCES
+        .iter()
+        .zip(calibration.iter())
+        .filter(|(_, &factor)| factor != 1.0)
+        .map(|(source, &factor)| (source.name, factor))
+        .collect()
+}
+
+// 从负载源读取一次负载并应用校准系数，超出0-100的读数视为无效，交给下一个负载源
+fn read_source(reader: &dyn NodeReader, index: usize) -> Result<Option<i32>> {
+    let Some(load) = (LOAD_SOURCES[index].read)(reader)? else {
+        return Ok(None);
+    };
+    if !(0..=100).contains(&load) {
+        let name = LOAD_SOURCES[index].name;
+        if INVALID_LOAD_WARNED.lock().unwrap().insert(index) {
+            warn!("Load source {name} reported out-of-range load {load}, ignoring");
+        } else {
+            debug!("Ignoring out-of-range load {load} from {name}");
+        }
+        return Ok(None);
+    }
+    let factor = LOAD_CALIBRATION.lock().unwrap()[index];
+    if factor == 1.0 {
+        return Ok(Some(load));
     }
+    Ok(Some((load as f64 * factor).round().clamp(0.0, 100.0) as i32))
+}
 
-    let buf = read_file(KERNEL_DEBUG_LOAD, 32)?;
+// 活动时间/总时间计数器负载，负载为两次采样间活动时间增量占总时间增量的百分比
+fn time_counter_read(reader: &dyn NodeReader) -> Result<Option<i32>> {
+    let buf = reader.read(GPU_TIME_COUNTERS, 64)?;
     let parts: Vec<&str> = buf.split_whitespace().collect();
 
-    if parts.len() >= 3 {
-        if let Ok(idle) = parts[2].parse::<i32>() {
-            let load = 100 - idle;
-            debug!("dgedload {load}");
-            return Ok(if 100 - idle == 0 {
-                kernel_debug_ged_load()?
-            } else {
-                100 - idle
-            });
+    if parts.len() >= 2 {
+        if let (Some(active), Some(total)) = (parse_i64(parts[0]), parse_i64(parts[1])) {
+            let (prev_active, prev_total) =
+                std::mem::replace(&mut *PREV_TIME_COUNTERS.lock().unwrap(), (active, total));
+            let diff_active = counter_delta(active, prev_active);
+            let diff_total = counter_delta(total, prev_total);
+
+            if diff_total > 0 {
+                let load = (diff_active * 100 / diff_total).clamp(0, 100) as i32;
+                debug!("timecounter: {load} {diff_active} {diff_total}");
+                return Ok(Some(load));
+            }
         }
     }
 
-    kernel_debug_ged_load()
+    Ok(None)
+}
+
+fn devfreq_load_path(reader: &dyn NodeReader) -> Option<String> {
+    gpu_devfreq_node(DEVFREQ_LOAD_NODE).filter(|path| reader.is_available(path))
+}
+
+// 标准devfreq负载节点，格式为"负载@频率Hz"或单独的负载值
+fn devfreq_read(reader: &dyn NodeReader) -> Result<Option<i32>> {
+    let Some(path) = devfreq_load_path(reader) else {
+        return Ok(None);
+    };
+
+    let buf = reader.read(&path, 64)?;
+    let load_part = buf.split('@').next().unwrap_or_default();
+    let load = parse_i32(load_part.trim())
+        .with_context(|| format!("Failed to parse GPU load from {path}"))?;
+
+    debug!("devfreq {load}");
+    Ok(Some(load))
+}
+
+fn module_ged_load_read(reader: &dyn NodeReader) -> Result<Option<i32>> {
+    let buf = reader.read(MODULE_LOAD, 32)?;
+    let load =
+        parse_i32(&buf).with_context(|| format!("Failed to parse GPU load from {MODULE_LOAD}"))?;
+
+    Ok(Some(load))
+}
+
+fn module_ged_idle_read(reader: &dyn NodeReader) -> Result<Option<i32>> {
+    let buf = reader.read(MODULE_IDLE, 32)?;
+    let idle =
+        parse_i32(&buf).with_context(|| format!("Failed to parse GPU idle from {MODULE_IDLE}"))?;
+
+    let load = 100 - idle;
+    debug!("module {load}");
+    Ok(Some(load))
 }
 
-fn mali_load() -> Result<i32> {
-    if !get_status(PROC_MALI_LOAD) {
-        return kernel_d_ged_load();
+// GED gpu_utilization节点，第三个值为空闲百分比
+fn ged_utilization_read(reader: &dyn NodeReader, path: &str) -> Result<Option<i32>> {
+    let buf = reader.read(path, 32)?;
+    let parts: Vec<&str> = buf.split_whitespace().collect();
+
+    if parts.len() >= 3 {
+        if let Some(idle) = parse_i32(parts[2]) {
+            let load = 100 - idle;
+            debug!("gedload {path}: {load}");
+            return Ok(Some(load));
+        }
     }
 
-    let buf = read_file(PROC_MALI_LOAD, 256)?;
+    Ok(None)
+}
+
+fn mali_read(reader: &dyn NodeReader) -> Result<Option<i32>> {
+    let buf = reader.read(PROC_MALI_LOAD, 256)?;
 
     // Parse "gpu/cljs0/cljs1=XX" format
     if let Some(pos) = buf.find('=') {
-        if let Ok(load) = buf[pos + 1..].trim().parse::<i32>() {
+        if let Some(load) = parse_i32(&buf[pos + 1..]) {
             debug!("mali {load}");
-            return Ok(if load == 0 {
-                kernel_d_ged_load()?
-            } else {
-                load
-            });
+            return Ok(Some(load));
         }
     }
 
-    kernel_d_ged_load()
+    Ok(None)
 }
 
-fn mtk_load() -> Result<i32> {
-    if !get_status(PROC_MTK_LOAD) {
-        return mali_load();
-    }
-
-    let buf = read_file(PROC_MTK_LOAD, 256)?;
+fn mtk_read(reader: &dyn NodeReader) -> Result<Option<i32>> {
+    let buf = reader.read(PROC_MTK_LOAD, 256)?;
 
     // Parse "ACTIVE=XX" format
     if let Some(pos) = buf.find("ACTIVE=") {
-        if let Ok(load) = buf[pos + 7..].trim().parse::<i32>() {
+        if let Some(load) = parse_i32(&buf[pos + 7..]) {
             debug!("mtk_mali {load}");
-            return Ok(if load == 0 { mali_load()? } else { load });
+            return Ok(Some(load));
         }
     }
 
-    mali_load()
+    Ok(None)
 }
 
-fn gpufreq_load() -> Result<i32> {
-    if !get_status(GPU_FREQ_LOAD_PATH) {
-        return mtk_load();
-    }
-
-    let file = match File::open(GPU_FREQ_LOAD_PATH) {
-        Ok(file) => file,
+fn gpufreq_read(reader: &dyn NodeReader) -> Result<Option<i32>> {
+    let buf = match reader.read(GPU_FREQ_LOAD_PATH, 4096) {
+        Ok(buf) => buf,
         Err(_) => {
-            write_status(GPU_FREQ_LOAD_PATH, false);
-            return Ok(0);
+            reader.mark_unavailable(GPU_FREQ_LOAD_PATH);
+            return Ok(None);
         }
     };
 
-    let reader = BufReader::new(file);
-
-    for line in reader.lines() {
-        let line = line?;
-
+    for line in buf.lines() {
         // Parse "gpu_loading = XX" format
         if let Some(pos) = line.find("gpu_loading = ") {
-            if let Ok(load) = line[pos + 14..].trim().parse::<i32>() {
+            if let Some(load) = parse_i32(&line[pos + 14..]) {
                 debug!("gpufreq {load}");
-                return Ok(if load == 0 { mtk_load()? } else { load });
+                return Ok(Some(load));
             }
         }
     }
 
-    mtk_load()
+    Ok(None)
 }
 
-fn debug_dvfs_load_func() -> Result<i32> {
-    // Check if debug_dvfs_load or debug_dvfs_load_old exists
-    let path = if get_status(DEBUG_DVFS_LOAD) {
-        DEBUG_DVFS_LOAD
-    } else if get_status(DEBUG_DVFS_LOAD_OLD) {
-        DEBUG_DVFS_LOAD_OLD
-    } else {
-        return gpufreq_load();
+fn debug_dvfs_read(reader: &dyn NodeReader) -> Result<Option<i32>> {
+    // Check if the configured debug_dvfs node exists
+    let Some(path) = precise_load_path(reader) else {
+        return Ok(None);
     };
 
-    let buf = read_file(path, 256)?;
+    let buf = reader.read(path, 256)?;
     let lines: Vec<&str> = buf.lines().collect();
 
     if lines.len() < 2 {
-        return gpufreq_load();
+        return Ok(None);
     }
 
-    // Static variables to keep track of previous values
-    static mut PREV_BUSY: i64 = 0;
-    static mut PREV_IDLE: i64 = 0;
-    static mut PREV_PROTM: i64 = 0;
-
     // Parse the second line which contains the values
     let parts: Vec<&str> = lines[1].split_whitespace().collect();
 
     if parts.len() >= 3 {
-        if let (Ok(busy), Ok(idle), Ok(protm)) = (
-            parts[0].parse::<i64>(),
-            parts[1].parse::<i64>(),
-            parts[2].parse::<i64>(),
+        if let (Some(busy), Some(idle), Some(protm)) = (
+            parse_i64(parts[0]),
+            parse_i64(parts[1]),
+            parse_i64(parts[2]),
         ) {
-            // Get previous values safely
-            let (prev_busy, prev_idle, prev_protm) = unsafe { (PREV_BUSY, PREV_IDLE, PREV_PROTM) };
-
-            // Calculate differences
-            let diff_busy = busy - prev_busy;
-            let diff_idle = idle - prev_idle;
-            let diff_protm = protm - prev_protm;
-
-            // Update previous values
-            unsafe {
-                PREV_BUSY = busy;
-                PREV_IDLE = idle;
-                PREV_PROTM = protm;
+            // 替换上一次采样值并计算负载百分比
+            let current = DvfsSnapshot { busy, idle, protm };
+            let previous = std::mem::replace(&mut *PREV_DVFS.lock().unwrap(), current);
+            if let Some(load) = current.load_since(&previous) {
+                return Ok(Some(load));
             }
+        }
+    }
 
-            // Calculate load percentage
-            let total = diff_busy + diff_idle + diff_protm;
-            if total > 0 {
-                let load = ((diff_busy + diff_protm) * 100 / total) as i32;
-                let load = if load < 0 { 0 } else { load };
+    Ok(None)
+}
 
-                debug!("debugutil: {load} {diff_busy} {diff_idle} {diff_protm}");
-                return Ok(if load == 0 { mtk_load()? } else { load });
+// 按配置的优先级依次尝试可用的负载源，负载为0或读取失败时继续尝试下一个负载源以避开卡住的节点
+fn auto_load(reader: &dyn NodeReader) -> Result<i32> {
+    let mut zero_source = None;
+
+    let order = LOAD_SOURCE_ORDER.lock().unwrap().clone();
+    for index in order {
+        let source = &LOAD_SOURCES[index];
+        if index == DEBUG_DVFS_SOURCE && !is_precise_load_enabled() {
+            continue;
+        }
+        if !(source.available)(reader) {
+            continue;
+        }
+
+        match read_source(reader, index) {
+            Ok(Some(0)) => {
+                zero_source.get_or_insert(index);
             }
+            Ok(Some(load)) => {
+                ACTIVE_SOURCE.store(index, Ordering::SeqCst);
+                return Ok(load);
+            }
+            Ok(None) => {}
+            Err(e) => debug!("Skipping load source {}: {e}", source.name),
         }
     }
 
-    gpufreq_load()
+    // 所有负载源都为0时GPU确实空闲，没有可用负载源时返回-1
+    match zero_source {
+        Some(index) => {
+            ACTIVE_SOURCE.store(index, Ordering::SeqCst);
+            Ok(0)
+        }
+        None => {
+            ACTIVE_SOURCE.store(NO_SOURCE, Ordering::SeqCst);
+            Ok(-1)
+        }
+    }
 }
 
+// 一次读取所有可用的负载源并按配置的方式合并，读取失败的负载源跳过
+fn fused_load(reader: &dyn NodeReader, fusion: LoadFusion) -> Result<i32> {
+    let mut contributors = Vec::new();
+    let order = LOAD_SOURCE_ORDER.lock().unwrap().clone();
+    for index in order {
+        let source = &LOAD_SOURCES[index];
+        if index == DEBUG_DVFS_SOURCE && !is_precise_load_enabled() {
+            continue;
+        }
+        if !(source.available)(reader) {
+            continue;
+        }
+        match read_source(reader, index) {
+            Ok(Some(load)) => contributors.push((index, load)),
+            Ok(None) => {}
+            Err(e) => debug!("Skipping load source {}: {e}", source.name),
+        }
+    }
+
+    let loads: Vec<i32> = contributors.iter().map(|&(_, load)| load).collect();
+    let Some(load) = fusion.combine(&loads) else {
+        ACTIVE_SOURCE.store(NO_SOURCE, Ordering::SeqCst);
+        return Ok(-1);
+    };
+    debug!(
+        "Fused load {load} ({fusion:?}) from {}",
+        contributors
+            .iter()
+            .map(|&(index, load)| format!("{}={load}", LOAD_SOURCES[index].name))
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+    ACTIVE_SOURCE.store(contributors[0].0, Ordering::SeqCst);
+    Ok(load)
+}
+
+// 热点读取缓存的有效期（毫秒），0表示不缓存
+static READ_CACHE_TTL_MS: AtomicU64 = AtomicU64::new(0);
+// 最近一次读取的负载 (读取时间, 负载)
+static LOAD_CACHE: Mutex<Option<(Instant, i32)>> = Mutex::new(None);
+// 最近一次读取的当前频率 (读取时间, 是否v1驱动, 频率)
+static FREQ_CACHE: Mutex<Option<(Instant, bool, i64)>> = Mutex::new(None);
+
+/// 设置负载和当前频率读取缓存的有效期（毫秒），0表示不缓存
+pub fn set_read_cache_ttl(ttl_ms: u64) {
+    READ_CACHE_TTL_MS.store(ttl_ms, Ordering::SeqCst);
+    invalidate_read_cache();
+    debug!("Set read cache TTL: {ttl_ms}ms");
+}
+
+/// 获取读取缓存的有效期（毫秒）
+pub fn get_read_cache_ttl() -> u64 {
+    READ_CACHE_TTL_MS.load(Ordering::SeqCst)
+}
+
+/// 清除读取缓存，写入新频率后调用，避免调频循环读到写入前的值
+pub fn invalidate_read_cache() {
+    *LOAD_CACHE.lock().unwrap() = None;
+    *FREQ_CACHE.lock().unwrap() = None;
+}
+
+// 缓存是否仍在有效期内
+fn cache_fresh(read_at: Instant) -> bool {
+    read_at.elapsed() < Duration::from_millis(READ_CACHE_TTL_MS.load(Ordering::Relaxed))
+}
+
+/// 读取GPU负载，启用读取缓存时有效期内复用上次的结果
+///
+/// 所有负载源都不可用或读取失败时返回-1，表示负载未知
 pub fn get_gpu_load() -> Result<i32> {
-    debug_dvfs_load_func()
+    if get_read_cache_ttl() == 0 {
+        return read_gpu_load(&SysfsReader);
+    }
+
+    if let Some((read_at, load)) = *LOAD_CACHE.lock().unwrap() {
+        if cache_fresh(read_at) {
+            return Ok(load);
+        }
+    }
+    let load = read_gpu_load(&SysfsReader)?;
+    *LOAD_CACHE.lock().unwrap() = Some((Instant::now(), load));
+    Ok(load)
+}
+
+/// 从指定的节点读取器读取GPU负载，不使用读取缓存，负载未知时返回-1
+pub fn read_gpu_load(reader: &dyn NodeReader) -> Result<i32> {
+    let selected = SELECTED_SOURCE.load(Ordering::SeqCst);
+    if let Some(source) = LOAD_SOURCES.get(selected) {
+        if (source.available)(reader) {
+            match read_source(reader, selected) {
+                Ok(Some(load)) => {
+                    ACTIVE_SOURCE.store(selected, Ordering::SeqCst);
+                    return Ok(load);
+                }
+                Ok(None) => {}
+                Err(e) => debug!("Failed to read load source {}: {e}", source.name),
+            }
+        }
+        debug!(
+            "Load source {} unavailable, using auto selection",
+            source.name
+        );
+    }
+
+    match get_load_fusion() {
+        LoadFusion::First => auto_load(reader),
+        fusion => fused_load(reader, fusion),
+    }
 }
 
+/// 重新检测负载节点，更新节点可用状态
+///
+/// 运行中所有负载源都失效时定期调用，节点恢复后负载读取会重新使用它们
+pub fn reprobe_load_sources() {
+    let mut found = false;
+    for path in [
+        MODULE_LOAD,
+        MODULE_IDLE,
+        KERNEL_LOAD,
+        KERNEL_DEBUG_LOAD,
+        KERNEL_D_LOAD,
+        GPU_FREQ_LOAD_PATH,
+        PROC_MTK_LOAD,
+        PROC_MALI_LOAD,
+        DEBUG_DVFS_LOAD,
+        DEBUG_DVFS_LOAD_OLD,
+        GPU_TIME_COUNTERS,
+    ] {
+        check_read(path, &mut found);
+    }
+    if let Some(path) = gpu_devfreq_node(DEVFREQ_LOAD_NODE) {
+        check_read(&path, &mut found);
+    }
+    invalidate_read_cache();
+
+    debug!(
+        "Reprobed load nodes (any available: {found}), load sources: [{}]",
+        detected_load_sources().join(", ")
+    );
+}
+
+/// 检测到的可用负载源名称，按自动选择时的优先级排列，不包含被禁用的负载源
+pub fn detected_load_sources() -> Vec<&'static str> {
+    LOAD_SOURCE_ORDER
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|&index| &LOAD_SOURCES[index])
+        .filter(|source| (source.available)(&SysfsReader))
+        .map(|source| source.name)
+        .collect()
+}
+
+/// 最近一次提供负载的负载源名称
+pub fn active_load_source() -> Option<&'static str> {
+    LOAD_SOURCES
+        .get(ACTIVE_SOURCE.load(Ordering::SeqCst))
+        .map(|source| source.name)
+}
+
+/// 通过控制命令指定的负载源名称，自动选择时返回None
+pub fn selected_load_source() -> Option<&'static str> {
+    LOAD_SOURCES
+        .get(SELECTED_SOURCE.load(Ordering::SeqCst))
+        .map(|source| source.name)
+}
+
+/// 指定负载源并立即读取一次负载，名称为"auto"时恢复自动选择
+pub fn select_load_source(name: &str) -> Result<i32> {
+    if name == "auto" {
+        SELECTED_SOURCE.store(NO_SOURCE, Ordering::SeqCst);
+        *LOAD_CACHE.lock().unwrap() = None;
+        info!("Load source selection reset to auto");
+        return auto_load(&SysfsReader);
+    }
+
+    let index = LOAD_SOURCES
+        .iter()
+        .position(|source| source.name == name)
+        .ok_or_else(|| anyhow!("unknown load source: {name}"))?;
+    let source = &LOAD_SOURCES[index];
+    if !(source.available)(&SysfsReader) {
+        return Err(anyhow!("load source not available: {name}"));
+    }
+
+    let load = read_source(&SysfsReader, index)?
+        .ok_or_else(|| anyhow!("failed to parse load from {name}"))?;
+    SELECTED_SOURCE.store(index, Ordering::SeqCst);
+    *LOAD_CACHE.lock().unwrap() = None;
+    ACTIVE_SOURCE.store(index, Ordering::SeqCst);
+    info!("Load source set to {name}, load {load}");
+    Ok(load)
+}
+
+/// 读取GPU当前频率，启用读取缓存时有效期内复用上次的结果
 pub fn get_gpu_current_freq(is_v1_driver: bool) -> Result<i64> {
+    if get_read_cache_ttl() == 0 {
+        return read_gpu_current_freq(&SysfsReader, is_v1_driver);
+    }
+
+    if let Some((read_at, cached_v1, freq)) = *FREQ_CACHE.lock().unwrap() {
+        if cached_v1 == is_v1_driver && cache_fresh(read_at) {
+            return Ok(freq);
+        }
+    }
+    let freq = read_gpu_current_freq(&SysfsReader, is_v1_driver)?;
+    *FREQ_CACHE.lock().unwrap() = Some((Instant::now(), is_v1_driver, freq));
+    Ok(freq)
+}
+
+fn read_gpu_current_freq(reader: &dyn NodeReader, is_v1_driver: bool) -> Result<i64> {
     // 对于v1驱动设备，只使用gpufreq_var_dump方法读取频率
     if is_v1_driver {
-        return read_v1_gpu_freq_from_var_dump();
+        return read_v1_gpu_freq_from_var_dump(reader);
     }
 
     // 对于v2驱动设备，使用原有的多路径读取策略
     // 首先尝试从GPU_CURRENT_FREQ_PATH读取频率
-    if get_status(GPU_CURRENT_FREQ_PATH) {
-        let buf = match read_file(GPU_CURRENT_FREQ_PATH, 64) {
+    if reader.is_available(GPU_CURRENT_FREQ_PATH) {
+        let buf = match reader.read(GPU_CURRENT_FREQ_PATH, 64) {
             Ok(content) => content,
             Err(e) => {
                 debug!("Failed to read GPU_CURRENT_FREQ_PATH: {e}");
-                write_status(GPU_CURRENT_FREQ_PATH, false);
+                reader.mark_unavailable(GPU_CURRENT_FREQ_PATH);
                 // 不立即返回，继续尝试其他路径
                 String::new()
             }
@@ -272,7 +854,7 @@ pub fn get_gpu_current_freq(is_v1_driver: bool) -> Result<i64> {
 
             // 读取第二个整数作为当前频率
             if parts.len() >= 2 {
-                if let Ok(freq) = parts[1].parse::<i64>() {
+                if let Some(freq) = parse_i64(parts[1]) {
                     debug!("Current GPU frequency from {GPU_CURRENT_FREQ_PATH}: {freq}");
                     return Ok(freq);
                 } else {
@@ -287,12 +869,12 @@ pub fn get_gpu_current_freq(is_v1_driver: bool) -> Result<i64> {
     }
 
     // 如果无法从GPU_CURRENT_FREQ_PATH读取，尝试从GPU_DEBUG_CURRENT_FREQ_PATH读取
-    if get_status(GPU_DEBUG_CURRENT_FREQ_PATH) {
-        let buf = match read_file(GPU_DEBUG_CURRENT_FREQ_PATH, 64) {
+    if reader.is_available(GPU_DEBUG_CURRENT_FREQ_PATH) {
+        let buf = match reader.read(GPU_DEBUG_CURRENT_FREQ_PATH, 64) {
             Ok(content) => content,
             Err(e) => {
                 debug!("Failed to read GPU_DEBUG_CURRENT_FREQ_PATH: {e}");
-                write_status(GPU_DEBUG_CURRENT_FREQ_PATH, false);
+                reader.mark_unavailable(GPU_DEBUG_CURRENT_FREQ_PATH);
                 // 不立即返回，继续尝试其他路径
                 String::new()
             }
@@ -303,7 +885,7 @@ pub fn get_gpu_current_freq(is_v1_driver: bool) -> Result<i64> {
 
             // 读取第二个整数作为当前频率
             if parts.len() >= 2 {
-                if let Ok(freq) = parts[1].parse::<i64>() {
+                if let Some(freq) = parse_i64(parts[1]) {
                     debug!("Current GPU frequency from {GPU_DEBUG_CURRENT_FREQ_PATH}: {freq}");
                     return Ok(freq);
                 } else {
@@ -318,13 +900,13 @@ pub fn get_gpu_current_freq(is_v1_driver: bool) -> Result<i64> {
     }
 
     // 如果无法从前两个路径读取，尝试从GPU_FREQ_LOAD_PATH读取（作为v2驱动的备用方案）
-    read_v1_gpu_freq_from_var_dump()
+    read_v1_gpu_freq_from_var_dump(reader)
 }
 
 /// 专门用于v1驱动设备的GPU频率读取函数
 /// 只从/proc/gpufreq/gpufreq_var_dump文件读取频率
-fn read_v1_gpu_freq_from_var_dump() -> Result<i64> {
-    if !get_status(GPU_FREQ_LOAD_PATH) {
+fn read_v1_gpu_freq_from_var_dump(reader: &dyn NodeReader) -> Result<i64> {
+    if !reader.is_available(GPU_FREQ_LOAD_PATH) {
         return Err(anyhow!(
             "V1 driver frequency path not available: {GPU_FREQ_LOAD_PATH}"
         ));
@@ -332,28 +914,18 @@ fn read_v1_gpu_freq_from_var_dump() -> Result<i64> {
 
     debug!("Reading V1 driver GPU frequency from {GPU_FREQ_LOAD_PATH}");
 
-    let file = match File::open(GPU_FREQ_LOAD_PATH) {
-        Ok(file) => file,
+    let buf = match reader.read(GPU_FREQ_LOAD_PATH, 4096) {
+        Ok(buf) => buf,
         Err(e) => {
             debug!("Failed to open GPU_FREQ_LOAD_PATH: {e}");
-            write_status(GPU_FREQ_LOAD_PATH, false);
+            reader.mark_unavailable(GPU_FREQ_LOAD_PATH);
             return Err(anyhow!(
                 "Cannot read V1 driver GPU frequency: file open failed"
             ));
         }
     };
 
-    let reader = BufReader::new(file);
-
-    for line in reader.lines() {
-        let line = match line {
-            Ok(l) => l,
-            Err(e) => {
-                debug!("Error reading line from GPU_FREQ_LOAD_PATH: {e}");
-                continue;
-            }
-        };
-
+    for line in buf.lines() {
         // 跳过长度小于等于3的行
         if line.len() <= 3 {
             continue;
@@ -366,7 +938,7 @@ fn read_v1_gpu_freq_from_var_dump() -> Result<i64> {
                 let freq_part = &line[freq_pos + 5..];
                 if let Some(comma_pos) = freq_part.find(',') {
                     let freq_str = freq_part[..comma_pos].trim();
-                    if let Ok(freq) = freq_str.parse::<i64>() {
+                    if let Some(freq) = parse_i64(freq_str) {
                         debug!(
                             "V1 driver GPU frequency from {GPU_FREQ_LOAD_PATH} (format 1): {freq}"
                         );
@@ -379,7 +951,7 @@ fn read_v1_gpu_freq_from_var_dump() -> Result<i64> {
         else if line.starts_with("Freq:") {
             if let Some(comma_pos) = line.find(',') {
                 let freq_str = line[5..comma_pos].trim();
-                if let Ok(freq) = freq_str.parse::<i64>() {
+                if let Some(freq) = parse_i64(freq_str) {
                     debug!("V1 driver GPU frequency from {GPU_FREQ_LOAD_PATH} (format 2): {freq}");
                     return Ok(freq);
                 }
@@ -387,7 +959,7 @@ fn read_v1_gpu_freq_from_var_dump() -> Result<i64> {
         }
         // 兼容原有的"cur_freq = XX"格式（备用）
         else if let Some(pos) = line.find("cur_freq = ") {
-            if let Ok(freq) = line[pos + 11..].trim().parse::<i64>() {
+            if let Some(freq) = parse_i64(&line[pos + 11..]) {
                 debug!("V1 driver GPU frequency from {GPU_FREQ_LOAD_PATH} (legacy format): {freq}");
                 return Ok(freq);
             }
@@ -450,6 +1022,20 @@ pub fn utilization_init() -> Result<()> {
     let debug_dvfs_load_old_status = check_read(DEBUG_DVFS_LOAD_OLD, &mut is_good);
     info!("{DEBUG_DVFS_LOAD_OLD}: {debug_dvfs_load_old_status}");
 
+    // 方法7：从活动时间/总时间计数器读取
+    let time_counters_status = check_read(GPU_TIME_COUNTERS, &mut is_good);
+    info!("{GPU_TIME_COUNTERS}: {time_counters_status}");
+
+    // 方法8：从标准devfreq接口读取（优先级最低）
+    info!("Testing devfreq...");
+    match gpu_devfreq_node(DEVFREQ_LOAD_NODE) {
+        Some(path) => {
+            let devfreq_load_status = check_read(&path, &mut is_good);
+            info!("{path}: {devfreq_load_status}");
+        }
+        None => info!("{DEVFREQ_CLASS_DIR}: no GPU devfreq load node"),
+    }
+
     // 检查是否可以监控GPU负载
     if !is_good {
         error!("Can't Monitor GPU Loading!");
@@ -464,6 +1050,265 @@ pub fn utilization_init() -> Result<()> {
         ));
     }
 
+    info!(
+        "Detected load sources: {}",
+        detected_load_sources().join(", ")
+    );
     info!("Test Finished.");
     Ok(())
 }
+
+// 负载源读取会修改全局的计数器和负载源状态，相关测试串行执行
+#[cfg(test)]
+static TEST_STATE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 测试中读取负载前持有，避免并行的测试互相修改负载源状态
+#[cfg(test)]
+pub fn lock_load_state() -> std::sync::MutexGuard<'static, ()> {
+    TEST_STATE_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::node_reader::MapReader;
+
+    use super::lock_load_state as lock_state;
+
+    #[test]
+    fn mali_parses_value_after_equals() {
+        let reader = MapReader::new(&[(PROC_MALI_LOAD, "gpu/cljs0/cljs1=52\n")]);
+        assert_eq!(mali_read(&reader).unwrap(), Some(52));
+
+        reader.insert(PROC_MALI_LOAD, "gpu/cljs0/cljs1=abc\n");
+        assert_eq!(mali_read(&reader).unwrap(), None);
+        reader.insert(PROC_MALI_LOAD, "52\n");
+        assert_eq!(mali_read(&reader).unwrap(), None);
+    }
+
+    #[test]
+    fn mtk_parses_active_value() {
+        let reader = MapReader::new(&[(PROC_MTK_LOAD, "ACTIVE=37\n")]);
+        assert_eq!(mtk_read(&reader).unwrap(), Some(37));
+
+        reader.insert(PROC_MTK_LOAD, "IDLE=63\n");
+        assert_eq!(mtk_read(&reader).unwrap(), None);
+        reader.insert(PROC_MTK_LOAD, "ACTIVE=\n");
+        assert_eq!(mtk_read(&reader).unwrap(), None);
+    }
+
+    #[test]
+    fn ged_uses_third_column_as_idle() {
+        let reader = MapReader::new(&[(KERNEL_LOAD, "10 20 35\n")]);
+        assert_eq!(
+            ged_utilization_read(&reader, KERNEL_LOAD).unwrap(),
+            Some(65)
+        );
+
+        reader.insert(KERNEL_LOAD, "10 20\n");
+        assert_eq!(ged_utilization_read(&reader, KERNEL_LOAD).unwrap(), None);
+        reader.insert(KERNEL_LOAD, "10 20 x\n");
+        assert_eq!(ged_utilization_read(&reader, KERNEL_LOAD).unwrap(), None);
+    }
+
+    #[test]
+    fn module_ged_nodes() {
+        let reader = MapReader::new(&[(MODULE_LOAD, "42\n"), (MODULE_IDLE, "40\n")]);
+        assert_eq!(module_ged_load_read(&reader).unwrap(), Some(42));
+        assert_eq!(module_ged_idle_read(&reader).unwrap(), Some(60));
+
+        reader.insert(MODULE_LOAD, "busy\n");
+        assert!(module_ged_load_read(&reader).is_err());
+    }
+
+    #[test]
+    fn gpufreq_parses_gpu_loading_line() {
+        let reader = MapReader::new(&[(
+            GPU_FREQ_LOAD_PATH,
+            "g_cur_gpu_freq = 500000\ngpu_loading = 73\n",
+        )]);
+        assert_eq!(gpufreq_read(&reader).unwrap(), Some(73));
+
+        reader.insert(GPU_FREQ_LOAD_PATH, "g_cur_gpu_freq = 500000\n");
+        assert_eq!(gpufreq_read(&reader).unwrap(), None);
+
+        // 读取失败时标记节点不可用
+        reader.insert_failing(GPU_FREQ_LOAD_PATH);
+        assert_eq!(gpufreq_read(&reader).unwrap(), None);
+        assert!(!reader.is_available(GPU_FREQ_LOAD_PATH));
+    }
+
+    #[test]
+    fn debug_dvfs_uses_counter_deltas() {
+        let _guard = lock_state();
+        reset_precise_load_state();
+        let reader = MapReader::new(&[(DEBUG_DVFS_LOAD, "busy idle protm\n100 300 0\n")]);
+        assert_eq!(debug_dvfs_read(&reader).unwrap(), Some(25));
+
+        reader.insert(DEBUG_DVFS_LOAD, "busy idle protm\n200 350 50\n");
+        assert_eq!(debug_dvfs_read(&reader).unwrap(), Some(75));
+
+        // 计数器没有变化时没有负载
+        assert_eq!(debug_dvfs_read(&reader).unwrap(), None);
+
+        reader.insert(DEBUG_DVFS_LOAD, "200 350 50\n");
+        assert_eq!(debug_dvfs_read(&reader).unwrap(), None);
+        reset_precise_load_state();
+    }
+
+    #[test]
+    fn dvfs_snapshot_load_matches_previous_formula() {
+        // 原有计算方式：直接相减，总增量不为正时没有负载，负载为负时取0
+        fn previous_formula(current: DvfsSnapshot, previous: DvfsSnapshot) -> Option<i32> {
+            let diff_busy = current.busy - previous.busy;
+            let diff_idle = current.idle - previous.idle;
+            let diff_protm = current.protm - previous.protm;
+            let total = diff_busy + diff_idle + diff_protm;
+            (total > 0).then(|| (((diff_busy + diff_protm) * 100 / total) as i32).max(0))
+        }
+
+        let snapshot = |busy, idle, protm| DvfsSnapshot { busy, idle, protm };
+        let cases = [
+            (snapshot(100, 300, 0), snapshot(200, 350, 50), Some(75)),
+            (snapshot(0, 0, 0), snapshot(300, 700, 0), Some(30)),
+            (snapshot(500, 500, 0), snapshot(500, 500, 0), None),
+            // 计数器被重置
+            (snapshot(1000, 1000, 0), snapshot(100, 2000, 0), Some(0)),
+            (snapshot(1000, 1000, 10), snapshot(10, 10, 0), None),
+        ];
+        for (previous, current, expected) in cases {
+            assert_eq!(current.load_since(&previous), expected);
+            assert_eq!(previous_formula(current, previous), expected);
+        }
+    }
+
+    #[test]
+    fn v1_var_dump_formats() {
+        let reader = MapReader::new(&[(
+            GPU_FREQ_LOAD_PATH,
+            "[GPU]\nidx: 3, freq: 850000, vgpu: 80000, vsram_gpu: 90000\n",
+        )]);
+        assert_eq!(read_v1_gpu_freq_from_var_dump(&reader).unwrap(), 850000);
+
+        reader.insert(
+            GPU_FREQ_LOAD_PATH,
+            "Freq: 700000, Vgpu: 75000, Vsram_gpu: 85000\n",
+        );
+        assert_eq!(read_v1_gpu_freq_from_var_dump(&reader).unwrap(), 700000);
+
+        reader.insert(GPU_FREQ_LOAD_PATH, "g_cur_freq = 500000\n");
+        assert_eq!(read_v1_gpu_freq_from_var_dump(&reader).unwrap(), 500000);
+
+        reader.insert(GPU_FREQ_LOAD_PATH, "idx: x, freq: fast, vgpu: 0\n");
+        assert!(read_v1_gpu_freq_from_var_dump(&reader).is_err());
+    }
+
+    fn source_index(name: &str) -> usize {
+        LOAD_SOURCES
+            .iter()
+            .position(|source| source.name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn out_of_range_loads_are_rejected() {
+        let mtk = source_index("mtk_mali");
+        let reader = MapReader::new(&[(PROC_MTK_LOAD, "ACTIVE=-5\n")]);
+        assert_eq!(read_source(&reader, mtk).unwrap(), None);
+        reader.insert(PROC_MTK_LOAD, "ACTIVE=250\n");
+        assert_eq!(read_source(&reader, mtk).unwrap(), None);
+
+        reader.insert(PROC_MTK_LOAD, "ACTIVE=0\n");
+        assert_eq!(read_source(&reader, mtk).unwrap(), Some(0));
+        reader.insert(PROC_MTK_LOAD, "ACTIVE=100\n");
+        assert_eq!(read_source(&reader, mtk).unwrap(), Some(100));
+    }
+
+    #[test]
+    fn out_of_range_load_falls_through() {
+        let _guard = lock_state();
+        let reader = MapReader::new(&[
+            (PROC_MTK_LOAD, "ACTIVE=250\n"),
+            (PROC_MALI_LOAD, "gpu=45\n"),
+        ]);
+        assert_eq!(auto_load(&reader).unwrap(), 45);
+        assert_eq!(active_load_source(), Some("mali"));
+    }
+
+    #[test]
+    fn auto_load_skips_zero_sources() {
+        let _guard = lock_state();
+        let reader = MapReader::new(&[(PROC_MTK_LOAD, "ACTIVE=0\n"), (PROC_MALI_LOAD, "gpu=40\n")]);
+        assert_eq!(auto_load(&reader).unwrap(), 40);
+        assert_eq!(active_load_source(), Some("mali"));
+
+        // 所有负载源都为0时使用第一个为0的负载源
+        reader.insert(PROC_MALI_LOAD, "gpu=0\n");
+        assert_eq!(auto_load(&reader).unwrap(), 0);
+        assert_eq!(active_load_source(), Some("mtk_mali"));
+    }
+
+    #[test]
+    fn auto_load_skips_malformed_sources() {
+        let _guard = lock_state();
+        let reader = MapReader::new(&[
+            (PROC_MTK_LOAD, "garbage\n"),
+            (MODULE_LOAD, "busy\n"),
+            (KERNEL_LOAD, "1 2 70\n"),
+        ]);
+        assert_eq!(auto_load(&reader).unwrap(), 30);
+        assert_eq!(active_load_source(), Some("ged_kernel"));
+
+        // 没有可用负载源时返回-1
+        assert_eq!(auto_load(&MapReader::default()).unwrap(), -1);
+        assert_eq!(active_load_source(), None);
+    }
+
+    #[test]
+    fn fused_load_from_two_sources() {
+        let _guard = lock_state();
+        let reader =
+            MapReader::new(&[(PROC_MTK_LOAD, "ACTIVE=30\n"), (PROC_MALI_LOAD, "gpu=71\n")]);
+        assert_eq!(fused_load(&reader, LoadFusion::Median).unwrap(), 51);
+        assert_eq!(fused_load(&reader, LoadFusion::Mean).unwrap(), 51);
+        assert_eq!(fused_load(&reader, LoadFusion::Max).unwrap(), 71);
+        assert_eq!(active_load_source(), Some("mtk_mali"));
+
+        // 读取失败的负载源不参与合并
+        reader.insert(PROC_MALI_LOAD, "gpu=abc\n");
+        assert_eq!(fused_load(&reader, LoadFusion::Max).unwrap(), 30);
+
+        // 没有可用负载源时返回-1
+        assert_eq!(
+            fused_load(&MapReader::default(), LoadFusion::Mean).unwrap(),
+            -1
+        );
+        assert_eq!(active_load_source(), None);
+    }
+
+    #[test]
+    fn load_fusion_combine() {
+        let loads = [20, 90, 40];
+        assert_eq!(LoadFusion::First.combine(&loads), Some(20));
+        assert_eq!(LoadFusion::Max.combine(&loads), Some(90));
+        assert_eq!(LoadFusion::Mean.combine(&loads), Some(50));
+        assert_eq!(LoadFusion::Median.combine(&loads), Some(40));
+        assert_eq!(LoadFusion::Median.combine(&[20, 90, 40, 60]), Some(50));
+        assert_eq!(LoadFusion::Mean.combine(&[]), None);
+    }
+
+    #[test]
+    fn unavailable_sources_report_unknown_load() {
+        let _guard = lock_state();
+        let reader = MapReader::default();
+        reader.insert_failing(PROC_MTK_LOAD);
+        reader.insert_failing(PROC_MALI_LOAD);
+        reader.insert(KERNEL_LOAD, "1 2\n");
+        assert_eq!(read_gpu_load(&reader).unwrap(), -1);
+        assert_eq!(active_load_source(), None);
+
+        // 负载源恢复后重新返回负载
+        reader.insert(PROC_MALI_LOAD, "gpu=35\n");
+        assert_eq!(read_gpu_load(&reader).unwrap(), 35);
+    }
+}
@@ -1,7 +1,16 @@
+pub mod battery;
 pub mod config_parser;
+pub mod config_report;
+pub mod control_socket;
+pub mod devfreq;
 pub mod file_path;
 pub mod foreground_app;
 pub mod freq_table;
 pub mod freq_table_parser;
+pub mod health_check;
 pub mod load_monitor;
 pub mod node_monitor;
+pub mod node_reader;
+pub mod screen;
+pub mod thermal;
+pub mod touch_boost;
@@ -1,13 +1,25 @@
+use std::{
+    fs,
+    os::unix::io::{AsRawFd, RawFd},
+    thread,
+    time::{Duration, Instant},
+};
+
 use anyhow::Result;
 use inotify::WatchMask;
 use log::{debug, error, info, warn};
 
 use crate::{
     datasource::{file_path::*, freq_table_parser::freq_table_read},
-    model::gpu::GPU,
+    model::{
+        gpu::GPU,
+        settings_queue::{submit_settings_change, SettingsChange},
+    },
     utils::{
+        constants::strategy,
+        event_loop::PollHandler,
         file_operate::{check_read_simple, read_file},
-        inotify::InotifyWatcher,
+        inotify::{InotifyWatcher, REARM_POLL_MS},
     },
 };
 
@@ -19,238 +31,301 @@ const NORMAL_MODE_UP_RATE_DELAY: u64 = 50; // 普通模式使用50ms的升频延
 const GAME_MODE_DOWN_THRESHOLD: i64 = 27; // 游戏模式保持原有的27次阈值
 const NORMAL_MODE_DOWN_THRESHOLD: i64 = 10; // 普通模式使用更低的10次阈值，更积极降频
 
-pub fn monitor_gaming(mut gpu: GPU) -> Result<()> {
-    // 设置线程名称（在Rust中无法轻易设置当前线程名称）
-    info!("{GAME_THREAD} Start");
+/// 游戏模式监控处理器
+pub struct GameModeHandler {
+    inotify: InotifyWatcher,
+}
 
-    // 默认设置为非游戏模式
-    gpu.set_gaming_mode(false);
+impl GameModeHandler {
+    pub fn new() -> Result<Self> {
+        // 设置线程名称（在Rust中无法轻易设置当前线程名称）
+        info!("{GAME_THREAD} Start");
+        let game_mode_path = resolve_path(PathKey::GameMode);
 
-    // 检查游戏模式文件路径
-    if !check_read_simple(GPU_GOVERNOR_GAME_MODE_PATH) {
-        // 如果文件不存在，记录日志
-        info!("Game mode file does not exist: {GPU_GOVERNOR_GAME_MODE_PATH}");
-    } else {
-        info!("Using game mode path: {GPU_GOVERNOR_GAME_MODE_PATH}");
-
-        // 初始读取游戏模式状态
-        if let Ok(buf) = read_file(GPU_GOVERNOR_GAME_MODE_PATH, 3) {
-            let value = buf.trim().parse::<i32>().unwrap_or(0);
-            let is_gaming = value != 0;
-            gpu.set_gaming_mode(is_gaming);
-
-            // 根据初始游戏模式设置不同的升频延迟和降频阈值
-            let up_rate_delay = if is_gaming {
-                GAME_MODE_UP_RATE_DELAY
-            } else {
-                NORMAL_MODE_UP_RATE_DELAY
-            };
-
-            let down_threshold = if is_gaming {
-                GAME_MODE_DOWN_THRESHOLD
-            } else {
-                NORMAL_MODE_DOWN_THRESHOLD
-            };
-
-            gpu.set_up_rate_delay(up_rate_delay);
-            gpu.set_down_threshold(down_threshold);
-            info!(
-                "Initial game mode {}",
-                if is_gaming { "enabled" } else { "disabled" }
-            );
-
-            // 设置初始高级调速器参数
-            if is_gaming {
-                // 游戏模式：更激进的升频，更保守的降频
-                gpu.set_load_thresholds(5, 20, 60, 85); // 更低的高负载阈值，更快进入高负载区域
-                gpu.set_load_stability_threshold(2); // 更低的稳定性阈值，更快响应负载变化
-                gpu.set_aggressive_down(false); // 禁用激进降频，保持性能
-
-                // 设置游戏模式的滞后阈值和去抖动时间
-                gpu.set_hysteresis_thresholds(65, 40); // 游戏模式使用更宽松的滞后阈值，更容易升频
-                gpu.set_debounce_times(10, 30); // 游戏模式使用更短的去抖动时间，更快响应
-
-                // 设置游戏模式的自适应采样参数
-                gpu.set_adaptive_sampling(true, 8, 50); // 游戏模式使用更短的采样间隔范围
-
-                debug!("Initial game mode enabled: Using performance-oriented governor settings");
-                debug!("Game mode hysteresis: up=65%, down=40%, debounce: up=10ms, down=30ms");
-            } else {
-                // 普通模式：更保守的升频，更激进的降频
-                gpu.set_load_thresholds(10, 30, 70, 90); // 默认负载阈值
-                gpu.set_load_stability_threshold(3); // 默认稳定性阈值
-                gpu.set_aggressive_down(true); // 启用激进降频，节省功耗
-
-                // 设置普通模式的滞后阈值和去抖动时间
-                gpu.set_hysteresis_thresholds(75, 30); // 普通模式使用更严格的滞后阈值，更难升频
-                gpu.set_debounce_times(20, 50); // 普通模式使用更长的去抖动时间，更稳定
-
-                // 设置普通模式的自适应采样参数
-                gpu.set_adaptive_sampling(true, 10, 100); // 普通模式使用更宽的采样间隔范围
-
-                debug!("Initial game mode disabled: Using power-saving governor settings");
-                debug!("Normal mode hysteresis: up=75%, down=30%, debounce: up=20ms, down=50ms");
+        // 检查游戏模式文件路径
+        if !check_read_simple(game_mode_path) {
+            // 如果文件不存在，记录日志
+            info!("Game mode file does not exist: {game_mode_path}");
+        } else {
+            info!("Using game mode path: {game_mode_path}");
+        }
+
+        // 初始读取游戏模式状态，读取失败时默认为普通模式
+        let is_gaming = read_game_mode().unwrap_or_else(|e| {
+            info!("Failed to read initial game mode value ({e}), defaulting to non-gaming mode");
+            false
+        });
+        info!(
+            "Initial game mode {}",
+            if is_gaming { "enabled" } else { "disabled" }
+        );
+        submit_settings_change(SettingsChange::GameMode(is_gaming));
+
+        // 设置文件监控
+        let mut inotify = InotifyWatcher::new()?;
+        inotify.add(game_mode_path, WatchMask::CLOSE_WRITE | WatchMask::MODIFY)?;
+
+        Ok(Self { inotify })
+    }
+
+    // 游戏模式文件变化后重新读取，由调频循环应用调速器参数
+    fn update_game_mode(&mut self) {
+        let is_gaming = match read_game_mode() {
+            Ok(is_gaming) => is_gaming,
+            Err(e) => {
+                // 文件不存在或读取失败时设置为非游戏模式
+                debug!("Failed to read game mode file: {e}, setting to non-gaming mode");
+                false
             }
+        };
+        submit_settings_change(SettingsChange::GameMode(is_gaming));
+    }
+}
 
-            info!("Initial game mode value: {value}");
-        } else {
-            info!("Failed to read initial game mode value, defaulting to non-gaming mode");
-            // 默认为普通模式
-            gpu.set_up_rate_delay(NORMAL_MODE_UP_RATE_DELAY);
-            gpu.set_down_threshold(NORMAL_MODE_DOWN_THRESHOLD);
-            info!(
-                "Setting default up rate delay to {NORMAL_MODE_UP_RATE_DELAY}ms, down threshold to {NORMAL_MODE_DOWN_THRESHOLD}"
-            );
-
-            // 设置默认高级调速器参数（普通模式）
-            gpu.set_load_thresholds(10, 30, 70, 90); // 默认负载阈值
-            gpu.set_load_stability_threshold(3); // 默认稳定性阈值
-            gpu.set_aggressive_down(true); // 启用激进降频，节省功耗
-
-            // 设置普通模式的滞后阈值和去抖动时间
-            gpu.set_hysteresis_thresholds(75, 30); // 普通模式使用更严格的滞后阈值，更难升频
-            gpu.set_debounce_times(20, 50); // 普通模式使用更长的去抖动时间，更稳定
-
-            // 设置普通模式的自适应采样参数
-            gpu.set_adaptive_sampling(true, 10, 100); // 普通模式使用更宽的采样间隔范围
-
-            debug!("Default mode: Using power-saving governor settings");
-            debug!("Default hysteresis: up=75%, down=30%, debounce: up=20ms, down=50ms");
+// 读取游戏模式文件，非0表示游戏模式
+fn read_game_mode() -> Result<bool> {
+    let game_mode_path = resolve_path(PathKey::GameMode);
+    if !check_read_simple(game_mode_path) {
+        return Err(anyhow::anyhow!(
+            "Game mode file does not exist: {game_mode_path}"
+        ));
+    }
+
+    let buf = read_file(game_mode_path, 3)?;
+    Ok(buf.trim().parse::<i32>().unwrap_or(0) != 0)
+}
+
+/// 应用游戏模式或普通模式的调速器参数
+pub fn apply_game_mode(gpu: &mut GPU, is_gaming: bool) {
+    // 切换模式时保留配置文件中的采样间隔
+    let sampling_interval = gpu.frequency_strategy.get_sampling_interval();
+    gpu.set_gaming_mode(is_gaming);
+    gpu.frequency_strategy_mut()
+        .set_sampling_interval(sampling_interval);
+
+    // 根据游戏模式设置不同的升频延迟和降频阈值
+    let up_rate_delay = if is_gaming {
+        GAME_MODE_UP_RATE_DELAY
+    } else {
+        NORMAL_MODE_UP_RATE_DELAY
+    };
+
+    let down_threshold = if is_gaming {
+        GAME_MODE_DOWN_THRESHOLD
+    } else {
+        NORMAL_MODE_DOWN_THRESHOLD
+    };
+
+    gpu.set_up_rate_delay(up_rate_delay);
+    gpu.set_down_threshold(down_threshold);
+
+    // 更新高级调速器参数
+    if is_gaming {
+        // 游戏模式：更激进的升频，更保守的降频
+        gpu.set_load_thresholds(5, 20, 60, 85); // 更低的高负载阈值，更快进入高负载区域
+        gpu.set_load_stability_threshold(2); // 更低的稳定性阈值，更快响应负载变化
+        gpu.set_aggressive_down(false); // 禁用激进降频，保持性能
+
+        // 设置游戏模式的滞后阈值和去抖动时间
+        gpu.set_hysteresis_thresholds(65, 40); // 游戏模式使用更宽松的滞后阈值，更容易升频
+        gpu.set_debounce_times(10, 30); // 游戏模式使用更短的去抖动时间，更快响应
+
+        // 设置游戏模式的自适应采样参数
+        gpu.set_adaptive_sampling(true, 8, 50); // 游戏模式使用更短的采样间隔范围
+        debug!("Game mode enabled: Using performance-oriented governor settings");
+        debug!("Game mode hysteresis: up=65%, down=40%, debounce: up=10ms, down=30ms");
+    } else {
+        // 普通模式：更保守的升频，更激进的降频
+        gpu.set_load_thresholds(10, 30, 70, 90); // 默认负载阈值
+        gpu.set_load_stability_threshold(3); // 默认稳定性阈值
+        gpu.set_aggressive_down(true); // 启用激进降频，节省功耗
+
+        // 设置普通模式的滞后阈值和去抖动时间
+        gpu.set_hysteresis_thresholds(75, 30); // 普通模式使用更严格的滞后阈值，更难升频
+        gpu.set_debounce_times(20, 50); // 普通模式使用更长的去抖动时间，更稳定
+
+        // 设置普通模式的自适应采样参数
+        gpu.set_adaptive_sampling(true, 10, 100); // 普通模式使用更宽的采样间隔范围
+
+        debug!("Game mode disabled: Using power-saving governor settings");
+        debug!("Normal mode hysteresis: up=75%, down=30%, debounce: up=20ms, down=50ms");
+    }
+
+    debug!("Game mode changed: {is_gaming}");
+}
+
+impl PollHandler for GameModeHandler {
+    fn name(&self) -> &'static str {
+        GAME_THREAD
+    }
+
+    fn poll_fd(&self) -> Option<RawFd> {
+        Some(self.inotify.as_raw_fd())
+    }
+
+    fn on_readable(&mut self) -> Result<()> {
+        self.inotify.check_events()?;
+        self.update_game_mode();
+        Ok(())
+    }
+
+    fn tick_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(REARM_POLL_MS))
+    }
+
+    fn on_tick(&mut self) -> Result<()> {
+        // 游戏模式文件被删除后重新出现时重新读取
+        if self.inotify.rearm() {
+            self.update_game_mode();
         }
+        Ok(())
     }
+}
 
-    // 设置文件监控
-    let mut inotify = InotifyWatcher::new()?;
-    inotify.add(
-        GPU_GOVERNOR_GAME_MODE_PATH,
-        WatchMask::CLOSE_WRITE | WatchMask::MODIFY,
-    )?;
+pub fn monitor_gaming() -> Result<()> {
+    let mut handler = GameModeHandler::new()?;
 
     // 主循环
     loop {
-        inotify.wait_and_handle()?;
-
-        // 检查文件是否存在
-        if !check_read_simple(GPU_GOVERNOR_GAME_MODE_PATH) {
-            // 如果文件不存在，设置为非游戏模式
-            gpu.set_gaming_mode(false);
-            debug!("Game mode file no longer exists, setting to non-gaming mode");
-            continue;
-        }
+        handler.inotify.wait_and_handle()?;
+        handler.update_game_mode();
+    }
+}
 
-        // 读取文件内容
-        match read_file(GPU_GOVERNOR_GAME_MODE_PATH, 3) {
-            Ok(buf) => {
-                let value = buf.trim().parse::<i32>().unwrap_or(0);
-                let is_gaming = value != 0;
-                gpu.set_gaming_mode(is_gaming);
-
-                // 根据游戏模式设置不同的升频延迟和降频阈值
-                let up_rate_delay = if is_gaming {
-                    GAME_MODE_UP_RATE_DELAY
-                } else {
-                    NORMAL_MODE_UP_RATE_DELAY
-                };
-
-                let down_threshold = if is_gaming {
-                    GAME_MODE_DOWN_THRESHOLD
-                } else {
-                    NORMAL_MODE_DOWN_THRESHOLD
-                };
-
-                gpu.set_up_rate_delay(up_rate_delay);
-                gpu.set_down_threshold(down_threshold);
-                debug!(
-                    "Game mode {}",
-                    if is_gaming { "enabled" } else { "disabled" }
-                );
-
-                // 更新高级调速器参数
-                if is_gaming {
-                    // 游戏模式：更激进的升频，更保守的降频
-                    gpu.set_load_thresholds(5, 20, 60, 85); // 更低的高负载阈值，更快进入高负载区域
-                    gpu.set_load_stability_threshold(2); // 更低的稳定性阈值，更快响应负载变化
-                    gpu.set_aggressive_down(false); // 禁用激进降频，保持性能
-
-                    // 设置游戏模式的滞后阈值和去抖动时间
-                    gpu.set_hysteresis_thresholds(65, 40); // 游戏模式使用更宽松的滞后阈值，更容易升频
-                    gpu.set_debounce_times(10, 30); // 游戏模式使用更短的去抖动时间，更快响应
-
-                    // 设置游戏模式的自适应采样参数
-                    gpu.set_adaptive_sampling(true, 8, 50); // 游戏模式使用更短的采样间隔范围
-                    debug!("Game mode enabled: Using performance-oriented governor settings");
-                    debug!("Game mode hysteresis: up=65%, down=40%, debounce: up=10ms, down=30ms");
-                } else {
-                    // 普通模式：更保守的升频，更激进的降频
-                    gpu.set_load_thresholds(10, 30, 70, 90); // 默认负载阈值
-                    gpu.set_load_stability_threshold(3); // 默认稳定性阈值
-                    gpu.set_aggressive_down(true); // 启用激进降频，节省功耗
-
-                    // 设置普通模式的滞后阈值和去抖动时间
-                    gpu.set_hysteresis_thresholds(75, 30); // 普通模式使用更严格的滞后阈值，更难升频
-                    gpu.set_debounce_times(20, 50); // 普通模式使用更长的去抖动时间，更稳定
-
-                    // 设置普通模式的自适应采样参数
-                    gpu.set_adaptive_sampling(true, 10, 100); // 普通模式使用更宽的采样间隔范围
-
-                    debug!("Game mode disabled: Using power-saving governor settings");
-                    debug!(
-                        "Normal mode hysteresis: up=75%, down=30%, debounce: up=20ms, down=50ms"
-                    );
-                }
-
-                debug!("Game mode changed: {is_gaming}");
-            }
-            Err(e) => {
-                warn!("Failed to read game mode file: {e}");
-                // 如果读取失败，设置为非游戏模式
-                gpu.set_gaming_mode(false);
+/// 频率表和TOML配置监控处理器
+pub struct ConfigHandler {
+    inotify: InotifyWatcher,
+    config_file: String,
+    // 上次提交重新加载的TOML配置内容，内容不变时不重新加载
+    toml_content: Option<String>,
+    // 最近一次文件变化的时间，变化停止一段时间后再重新加载，避免读到写了一半的文件
+    changed_at: Option<Instant>,
+}
+
+impl ConfigHandler {
+    pub fn new(mut gpu: GPU) -> Result<Self> {
+        // 设置线程名称（在Rust中无法轻易设置当前线程名称）
+        info!("{CONF_THREAD} Start");
+
+        // 使用频率表配置文件
+        let config_file = resolve_path(PathKey::FreqTable).to_string();
+
+        // 检查频率表配置文件是否存在
+        if !check_read_simple(&config_file) {
+            error!("CONFIG NOT FOUND: {}", std::io::Error::last_os_error());
+            return Err(anyhow::anyhow!(
+                "Frequency table config file not found: {}",
+                config_file
+            ));
+        };
+
+        info!("Using Config: {config_file}");
+
+        // 使用read_freq_ge和read_freq_le方法获取频率范围
+        let min_freq = gpu.get_min_freq();
+        let max_freq = gpu.get_max_freq();
+        // 使用read_freq_ge方法获取大于等于特定频率的最小频率
+        let target_freq = 600000; // 600MHz
+        let _ge_freq = gpu.read_freq_ge(target_freq);
+        // 使用read_freq_le方法获取小于等于特定频率的最大频率
+        let target_freq2 = 800000; // 800MHz
+        let _le_freq = gpu.read_freq_le(target_freq2);
+
+        // 从GPU对象获取margin值
+        let margin = gpu.get_margin();
+
+        info!("Config values: min_freq={min_freq}KHz, max_freq={max_freq}KHz, margin={margin}%");
+
+        let mut inotify = InotifyWatcher::new()?;
+        inotify.add(&config_file, WatchMask::CLOSE_WRITE | WatchMask::MODIFY)?;
+
+        // 同时监控TOML配置，内容变化时在运行中重新加载策略
+        let config_toml = resolve_path(PathKey::ConfigToml);
+        let toml_content = fs::read_to_string(config_toml).ok();
+        if check_read_simple(config_toml) {
+            if let Err(e) = inotify.add(config_toml, WatchMask::CLOSE_WRITE | WatchMask::MODIFY) {
+                warn!("Failed to watch TOML config {config_toml}: {e}");
             }
         }
+
+        // 初始读取频率表配置
+        freq_table_read(&config_file, &mut gpu)?;
+
+        Ok(Self {
+            inotify,
+            config_file,
+            toml_content,
+            changed_at: None,
+        })
     }
-}
 
-pub fn monitor_config(mut gpu: GPU) -> Result<()> {
-    // 设置线程名称（在Rust中无法轻易设置当前线程名称）
-    info!("{CONF_THREAD} Start");
+    // 配置变化后由调频循环重新读取频率表，TOML配置等变化停止后再检查
+    fn reload(&mut self) -> Result<()> {
+        debug!("Frequency table config changed: {}", self.config_file);
+        submit_settings_change(SettingsChange::ReloadFreqTable);
+        self.changed_at = Some(Instant::now());
+        Ok(())
+    }
 
-    // 使用频率表配置文件
-    let config_file = FREQ_TABLE_CONFIG_FILE.to_string();
+    // 变化停止超过防抖时间后，TOML配置内容有变化时由调频循环重新加载
+    fn reload_toml_if_settled(&mut self) {
+        let debounce = Duration::from_millis(strategy::CONFIG_RELOAD_DEBOUNCE_MS);
+        if self
+            .changed_at
+            .is_none_or(|since| since.elapsed() < debounce)
+        {
+            return;
+        }
+        self.changed_at = None;
 
-    // 检查频率表配置文件是否存在
-    if !check_read_simple(&config_file) {
-        error!("CONFIG NOT FOUND: {}", std::io::Error::last_os_error());
-        return Err(anyhow::anyhow!(
-            "Frequency table config file not found: {}",
-            config_file
-        ));
-    };
+        let content = fs::read_to_string(resolve_path(PathKey::ConfigToml)).ok();
+        if content.is_none() || content == self.toml_content {
+            return;
+        }
+        info!("TOML config changed, reloading");
+        submit_settings_change(SettingsChange::ReloadConfig);
+        self.toml_content = content;
+    }
+}
 
-    info!("Using Config: {config_file}");
+impl PollHandler for ConfigHandler {
+    fn name(&self) -> &'static str {
+        CONF_THREAD
+    }
 
-    // 使用read_freq_ge和read_freq_le方法获取频率范围
-    let min_freq = gpu.get_min_freq();
-    let max_freq = gpu.get_max_freq();
-    // 使用read_freq_ge方法获取大于等于特定频率的最小频率
-    let target_freq = 600000; // 600MHz
-    let _ge_freq = gpu.read_freq_ge(target_freq);
-    // 使用read_freq_le方法获取小于等于特定频率的最大频率
-    let target_freq2 = 800000; // 800MHz
-    let _le_freq = gpu.read_freq_le(target_freq2);
+    fn poll_fd(&self) -> Option<RawFd> {
+        Some(self.inotify.as_raw_fd())
+    }
 
-    // 从GPU对象获取margin值
-    let margin = gpu.get_margin();
+    fn on_readable(&mut self) -> Result<()> {
+        self.inotify.check_events()?;
+        self.reload()
+    }
 
-    info!("Config values: min_freq={min_freq}KHz, max_freq={max_freq}KHz, margin={margin}%");
+    fn tick_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(
+            strategy::CONFIG_RELOAD_DEBOUNCE_MS.min(REARM_POLL_MS),
+        ))
+    }
 
-    let mut inotify = InotifyWatcher::new()?;
-    inotify.add(&config_file, WatchMask::CLOSE_WRITE | WatchMask::MODIFY)?;
+    fn on_tick(&mut self) -> Result<()> {
+        // 频率表配置被删除后重新出现时重新读取
+        if self.inotify.rearm() {
+            self.reload()?;
+        }
+        self.reload_toml_if_settled();
+        Ok(())
+    }
+}
 
-    // 初始读取频率表配置
-    freq_table_read(&config_file, &mut gpu)?;
+pub fn monitor_config(gpu: GPU) -> Result<()> {
+    let mut handler = ConfigHandler::new(gpu)?;
 
     loop {
-        inotify.wait_and_handle()?;
-        freq_table_read(&config_file, &mut gpu)?;
+        handler.inotify.wait_and_handle()?;
+        handler.reload()?;
+        // 等待写入完成后再检查TOML配置
+        thread::sleep(Duration::from_millis(strategy::CONFIG_RELOAD_DEBOUNCE_MS));
+        handler.reload_toml_if_settled();
     }
 }
@@ -0,0 +1,83 @@
+use anyhow::Result;
+
+use crate::utils::{
+    file_operate::read_file,
+    file_status::{get_status, write_status},
+};
+
+/// 节点读取接口 - 负载和频率读取通过该接口访问节点，解析逻辑不直接依赖真实文件系统
+pub trait NodeReader {
+    /// 读取节点的全部内容，max_len为预分配的缓冲区大小
+    fn read(&self, path: &str, max_len: usize) -> Result<String>;
+    /// 节点是否可用
+    fn is_available(&self, path: &str) -> bool;
+    /// 标记节点不可用，后续读取将跳过该节点
+    fn mark_unavailable(&self, path: &str);
+}
+
+/// 读取真实文件系统的节点，可用状态记录在全局节点状态表中
+pub struct SysfsReader;
+
+impl NodeReader for SysfsReader {
+    fn read(&self, path: &str, max_len: usize) -> Result<String> {
+        read_file(path, max_len)
+    }
+
+    fn is_available(&self, path: &str) -> bool {
+        get_status(path)
+    }
+
+    fn mark_unavailable(&self, path: &str) {
+        write_status(path, false);
+    }
+}
+
+/// 测试用的节点读取器，节点内容保存在内存中，不存在的节点视为不可用
+#[cfg(test)]
+#[derive(Default)]
+pub struct MapReader {
+    // 节点路径到内容，None表示节点存在但读取失败
+    nodes: std::cell::RefCell<std::collections::HashMap<String, Option<String>>>,
+}
+
+#[cfg(test)]
+impl MapReader {
+    pub fn new(nodes: &[(&str, &str)]) -> Self {
+        let reader = Self::default();
+        for &(path, content) in nodes {
+            reader.insert(path, content);
+        }
+        reader
+    }
+
+    /// 设置节点内容
+    pub fn insert(&self, path: &str, content: &str) {
+        self.nodes
+            .borrow_mut()
+            .insert(path.to_string(), Some(content.to_string()));
+    }
+
+    /// 添加一个存在但读取失败的节点
+    pub fn insert_failing(&self, path: &str) {
+        self.nodes.borrow_mut().insert(path.to_string(), None);
+    }
+}
+
+#[cfg(test)]
+impl NodeReader for MapReader {
+    fn read(&self, path: &str, _max_len: usize) -> Result<String> {
+        match self.nodes.borrow().get(path) {
+            Some(Some(content)) => Ok(content.clone()),
+            Some(None) => Err(anyhow::anyhow!("Failed to read from file: {path}")),
+            None => Err(anyhow::anyhow!("Failed to open file for reading: {path}")),
+        }
+    }
+
+    fn is_available(&self, path: &str) -> bool {
+        self.nodes.borrow().contains_key(path)
+    }
+
+    fn mark_unavailable(&self, path: &str) {
+        self.nodes.borrow_mut().remove(path);
+    }
+}
@@ -0,0 +1,108 @@
+use std::{thread, time::Duration};
+
+use anyhow::{anyhow, Result};
+use log::{debug, info, warn};
+
+use crate::{
+    datasource::file_path::*,
+    model::freq_limits::{set_max_cap, LimitSource},
+    utils::{
+        constants::strategy,
+        event_loop::PollHandler,
+        file_operate::{check_read_simple, read_file},
+        numeric::parse_i64,
+    },
+};
+
+/// 查找屏幕状态节点：优先使用配置的节点，否则依次尝试常见的背光亮度节点，都不可读时返回None
+pub fn detect_screen_node(configured: Option<&str>) -> Option<String> {
+    if let Some(node) = configured {
+        if check_read_simple(node) {
+            return Some(node.to_string());
+        }
+        warn!("Screen state node not readable: {node}, trying auto-detect");
+    }
+
+    let node = SCREEN_BRIGHTNESS_NODES
+        .iter()
+        .find(|node| check_read_simple(node))
+        .map(|node| node.to_string());
+    if node.is_none() {
+        info!("No readable screen state node, assuming screen is always on");
+    }
+    node
+}
+
+// 背光亮度为0视为熄屏
+fn read_screen_on(node: &str) -> Result<bool> {
+    let buf = read_file(node, 16)?;
+    let brightness = parse_i64(buf.trim())
+        .ok_or_else(|| anyhow!("Failed to parse screen state from {node}: {}", buf.trim()))?;
+    Ok(brightness != 0)
+}
+
+/// 熄屏处理器 - 熄屏时将最高频率限制为最低频率，亮屏后立即解除
+pub struct ScreenStateHandler {
+    node: String,
+    min_freq: i64,
+    screen_on: bool,
+}
+
+impl ScreenStateHandler {
+    pub fn new(node: String, min_freq: i64) -> Result<Self> {
+        info!("{SCREEN_THREAD} Start");
+        info!("Using screen state node: {node}");
+
+        Ok(Self {
+            node,
+            min_freq,
+            screen_on: true,
+        })
+    }
+
+    // 读取屏幕状态，读取失败时按亮屏处理，不影响正常调频
+    fn update(&mut self) {
+        let screen_on = read_screen_on(&self.node)
+            .inspect_err(|e| debug!("Failed to read screen state: {e}"))
+            .unwrap_or(true);
+        if screen_on == self.screen_on {
+            return;
+        }
+
+        if screen_on {
+            info!("Screen on, restoring normal scaling");
+        } else {
+            info!("Screen off, capping to {}KHz", self.min_freq);
+        }
+        self.screen_on = screen_on;
+        set_max_cap(
+            LimitSource::ScreenOff,
+            (!screen_on).then_some(self.min_freq),
+        );
+    }
+}
+
+impl PollHandler for ScreenStateHandler {
+    fn name(&self) -> &'static str {
+        SCREEN_THREAD
+    }
+
+    fn tick_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(strategy::SCREEN_POLL_MS))
+    }
+
+    fn on_tick(&mut self) -> Result<()> {
+        self.update();
+        Ok(())
+    }
+}
+
+/// 定期读取屏幕状态，熄屏时限制为最低频率
+pub fn monitor_screen_state(node: String, min_freq: i64) -> Result<()> {
+    let mut handler = ScreenStateHandler::new(node, min_freq)?;
+
+    loop {
+        handler.update();
+        thread::sleep(Duration::from_millis(strategy::SCREEN_POLL_MS));
+    }
+}
@@ -0,0 +1,217 @@
+use std::{path::Path, sync::Mutex, thread, time::Duration};
+
+use anyhow::{anyhow, Result};
+use log::{debug, info, warn};
+
+use crate::{
+    datasource::file_path::*,
+    model::{
+        freq_limits::{set_max_cap, LimitSource},
+        gpu::GPU,
+    },
+    utils::{
+        constants::strategy, event_loop::PollHandler, file_operate::read_file, numeric::parse_i64,
+    },
+};
+
+// 最近一次读取的各温区温度（毫摄氏度），读取失败时为None
+static THERMAL_READINGS: Mutex<Vec<(String, Option<i64>)>> = Mutex::new(Vec::new());
+
+/// 最近一次读取的各温区温度（毫摄氏度），读取失败的温区为None
+pub fn get_thermal_readings() -> Vec<(String, Option<i64>)> {
+    THERMAL_READINGS.lock().unwrap().clone()
+}
+
+// 温区可以写成thermal_zone名称或温度节点的绝对路径
+fn zone_temp_path(zone: &str) -> String {
+    if zone.starts_with('/') {
+        zone.to_string()
+    } else {
+        format!("{THERMAL_CLASS_DIR}/{zone}/temp")
+    }
+}
+
+// 读取一个温区的温度（毫摄氏度）
+fn read_zone_temp(path: &str) -> Result<i64> {
+    let buf = read_file(path, 32)?;
+    parse_i64(buf.trim()).ok_or_else(|| anyhow!("Failed to parse temperature from {path}"))
+}
+
+/// 温度限频档位 - 温度达到阈值时将最高频率限制到对应频率
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ThermalStep {
+    /// 档位名称，用于日志
+    pub name: &'static str,
+    /// 阈值（摄氏度）
+    pub temp_c: i32,
+    /// 频率上限（KHz）
+    pub cap_freq: i64,
+}
+
+/// 根据配置生成温度限频档位，按阈值从低到高排列，为空表示禁用
+pub fn thermal_steps(gpu: &GPU) -> Vec<ThermalStep> {
+    let strategy = &gpu.frequency_strategy;
+    if strategy.thermal_zones.is_empty() {
+        return Vec::new();
+    }
+
+    let mut steps = Vec::new();
+    if strategy.thermal_cap_freq > 0 {
+        steps.push(ThermalStep {
+            name: "cap",
+            temp_c: strategy.thermal_cap_temp,
+            cap_freq: strategy.thermal_cap_freq,
+        });
+    }
+    // 预警限制到中间频率，临界限制到最低频率
+    if strategy.thermal_warn_temp > 0 {
+        steps.push(ThermalStep {
+            name: "warn",
+            temp_c: strategy.thermal_warn_temp,
+            cap_freq: gpu.get_middle_freq(),
+        });
+    }
+    if strategy.thermal_critical_temp > 0 {
+        steps.push(ThermalStep {
+            name: "critical",
+            temp_c: strategy.thermal_critical_temp,
+            cap_freq: gpu.get_min_freq(),
+        });
+    }
+    steps.sort_by_key(|step| step.temp_c);
+    steps
+}
+
+/// 温度限频处理器 - 取所有温区中的最高温度，按达到的最高档位限制最高频率
+pub struct ThermalCapHandler {
+    zones: Vec<(String, String)>,
+    steps: Vec<ThermalStep>,
+    // 当前生效的档位索引，None表示未限频
+    level: Option<usize>,
+}
+
+impl ThermalCapHandler {
+    pub fn new(zones: &[String], steps: Vec<ThermalStep>) -> Result<Self> {
+        info!("{THERMAL_THREAD} Start");
+
+        let zones: Vec<(String, String)> = zones
+            .iter()
+            .map(|zone| (zone.clone(), zone_temp_path(zone)))
+            .collect();
+        for (zone, path) in &zones {
+            if !Path::new(path).exists() {
+                warn!("Thermal zone {zone} not found: {path}");
+            }
+        }
+        for step in &steps {
+            info!(
+                "Thermal {}: {}KHz above {}C",
+                step.name, step.cap_freq, step.temp_c
+            );
+        }
+        info!(
+            "Thermal zones: {:?}",
+            zones.iter().map(|(zone, _)| zone).collect::<Vec<_>>()
+        );
+
+        Ok(Self {
+            zones,
+            steps,
+            level: None,
+        })
+    }
+
+    // 根据最高温度选择档位，降档需要降到当前档位阈值以下一定温度，避免在阈值附近反复切换
+    fn select_level(&self, temp: Option<i64>) -> Option<usize> {
+        let temp = temp?;
+        let level = self
+            .steps
+            .iter()
+            .rposition(|step| temp >= step.temp_c as i64 * 1000);
+        match self.level {
+            Some(current) if level.is_none_or(|level| level < current) => {
+                let release_temp =
+                    (self.steps[current].temp_c - strategy::THERMAL_CAP_HYSTERESIS_C) as i64 * 1000;
+                if temp >= release_temp {
+                    Some(current)
+                } else {
+                    level
+                }
+            }
+            _ => level,
+        }
+    }
+
+    // 档位的频率上限，取该档位及以下所有档位中最严格的值，避免高档位反而放宽限制
+    fn level_cap_freq(&self, level: usize) -> i64 {
+        self.steps[..=level]
+            .iter()
+            .map(|step| step.cap_freq)
+            .min()
+            .unwrap_or(self.steps[level].cap_freq)
+    }
+
+    // 读取所有温区，跳过无法读取的温区，按最高温度决定是否限频
+    fn update(&mut self) {
+        let readings: Vec<(String, Option<i64>)> = self
+            .zones
+            .iter()
+            .map(|(zone, path)| {
+                let temp = read_zone_temp(path)
+                    .inspect_err(|e| debug!("Skipping thermal zone {zone}: {e}"))
+                    .ok();
+                (zone.clone(), temp)
+            })
+            .collect();
+        let hottest = readings
+            .iter()
+            .filter_map(|(zone, temp)| temp.map(|temp| (zone.as_str(), temp)))
+            .max_by_key(|&(_, temp)| temp);
+
+        // 只在档位变化时输出日志
+        let level = self.select_level(hottest.map(|(_, temp)| temp));
+        if level != self.level {
+            let (zone, temp) = hottest.unwrap_or(("none", 0));
+            match level {
+                Some(level) => info!(
+                    "Thermal {} cap applied: {}KHz ({zone}: {temp}mC)",
+                    self.steps[level].name,
+                    self.level_cap_freq(level)
+                ),
+                None => info!("Thermal cap released ({zone}: {temp}mC)"),
+            }
+            self.level = level;
+        }
+        set_max_cap(
+            LimitSource::Thermal,
+            level.map(|level| self.level_cap_freq(level)),
+        );
+
+        *THERMAL_READINGS.lock().unwrap() = readings;
+    }
+}
+
+impl PollHandler for ThermalCapHandler {
+    fn name(&self) -> &'static str {
+        THERMAL_THREAD
+    }
+
+    fn tick_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(strategy::THERMAL_POLL_MS))
+    }
+
+    fn on_tick(&mut self) -> Result<()> {
+        self.update();
+        Ok(())
+    }
+}
+
+/// 定期读取温区温度，过热时限制最高频率
+pub fn monitor_thermal_cap(zones: &[String], steps: Vec<ThermalStep>) -> Result<()> {
+    let mut handler = ThermalCapHandler::new(zones, steps)?;
+
+    loop {
+        handler.update();
+        thread::sleep(Duration::from_millis(strategy::THERMAL_POLL_MS));
+    }
+}
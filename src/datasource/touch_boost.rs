@@ -0,0 +1,150 @@
+use std::{
+    fs::{self, File},
+    io::Read,
+    mem,
+    os::unix::io::{AsRawFd, RawFd},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, info, warn};
+
+use crate::{
+    datasource::file_path::*,
+    model::freq_limits::{set_temporary_floor, LimitSource},
+    utils::event_loop::PollHandler,
+};
+
+// 输入事件类型和编码（linux/input-event-codes.h）
+const EV_KEY: u16 = 0x01;
+const EV_ABS: u16 = 0x03;
+const BTN_TOUCH: u16 = 0x14a;
+const ABS_MT_POSITION_X: u32 = 0x35;
+const ABS_MT_TRACKING_ID: u16 = 0x39;
+
+// 检查输入设备是否支持多点触控坐标
+fn is_touch_device(event_name: &str) -> bool {
+    let caps_path = format!("{INPUT_CLASS_DIR}/{event_name}/device/capabilities/abs");
+    let Ok(caps) = fs::read_to_string(&caps_path) else {
+        return false;
+    };
+
+    // 能力位图按从高到低的顺序以十六进制字输出，最后一个字包含低位
+    caps.split_whitespace()
+        .last()
+        .and_then(|word| u64::from_str_radix(word, 16).ok())
+        .is_some_and(|bits| bits & (1 << ABS_MT_POSITION_X) != 0)
+}
+
+// 查找触摸屏输入设备
+fn find_touch_device() -> Result<PathBuf> {
+    let entries = fs::read_dir(INPUT_DEVICE_DIR)
+        .with_context(|| format!("Failed to read input device dir: {INPUT_DEVICE_DIR}"))?;
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("event"))
+        .collect();
+    names.sort();
+
+    names
+        .iter()
+        .find(|name| is_touch_device(name))
+        .map(|name| Path::new(INPUT_DEVICE_DIR).join(name))
+        .ok_or_else(|| anyhow!("No touchscreen input device found"))
+}
+
+// 判断事件是否表示新的触摸按下
+fn is_touch_down(event: &libc::input_event) -> bool {
+    match event.type_ {
+        EV_KEY => event.code == BTN_TOUCH && event.value == 1,
+        EV_ABS => event.code == ABS_MT_TRACKING_ID && event.value >= 0,
+        _ => false,
+    }
+}
+
+/// 触摸升频处理器
+pub struct TouchBoostHandler {
+    file: File,
+    device: PathBuf,
+    boost_freq: i64,
+    duration: Duration,
+    buf: Vec<u8>,
+}
+
+impl TouchBoostHandler {
+    pub fn new(boost_freq: i64, boost_ms: u64) -> Result<Self> {
+        info!("{TOUCH_BOOST_THREAD} Start");
+
+        let device = find_touch_device()?;
+        let file = File::open(&device)
+            .with_context(|| format!("Failed to open touch device: {}", device.display()))?;
+        info!(
+            "Touch boost: {boost_freq}KHz for {boost_ms}ms on {}",
+            device.display()
+        );
+
+        Ok(Self {
+            file,
+            device,
+            boost_freq,
+            duration: Duration::from_millis(boost_ms),
+            buf: vec![0u8; mem::size_of::<libc::input_event>() * 64],
+        })
+    }
+
+    // 读取一批输入事件，检测到触摸按下时提高频率下限
+    fn read_events(&mut self) -> Result<()> {
+        let len = match self.file.read(&mut self.buf)? {
+            0 => return Err(anyhow!("Touch device closed: {}", self.device.display())),
+            len => len,
+        };
+
+        let event_size = mem::size_of::<libc::input_event>();
+        let touched = self.buf[..len].chunks_exact(event_size).any(|chunk| {
+            // SAFETY: chunk长度等于input_event大小，使用非对齐读取
+            let event =
+                unsafe { std::ptr::read_unaligned(chunk.as_ptr() as *const libc::input_event) };
+            is_touch_down(&event)
+        });
+
+        if touched {
+            debug!("Touch detected, boosting to {}KHz", self.boost_freq);
+            set_temporary_floor(LimitSource::TouchBoost, self.boost_freq, self.duration);
+        }
+
+        Ok(())
+    }
+}
+
+impl PollHandler for TouchBoostHandler {
+    fn name(&self) -> &'static str {
+        TOUCH_BOOST_THREAD
+    }
+
+    fn poll_fd(&self) -> Option<RawFd> {
+        Some(self.file.as_raw_fd())
+    }
+
+    fn on_readable(&mut self) -> Result<()> {
+        self.read_events()
+    }
+}
+
+/// 监控触摸事件，在触摸时临时提高频率下限
+pub fn monitor_touch_boost(boost_freq: i64, boost_ms: u64) -> Result<()> {
+    let mut handler = TouchBoostHandler::new(boost_freq, boost_ms)?;
+
+    loop {
+        // 阻塞读取，直到有输入事件
+        if let Err(e) = handler.read_events() {
+            if e.downcast_ref::<std::io::Error>().is_none() {
+                return Err(e);
+            }
+            warn!("Failed to read touch device: {e}");
+            std::thread::sleep(Duration::from_secs(1));
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! 调速器状态读取的C接口，供悬浮窗等原生应用链接
+//!
+//! 调速器运行在独立进程中，这里通过控制套接字的`state`命令读取最新快照，
+//! 返回单行JSON：freq、freq_idx、boot_freq、load、load_min、load_avg、load_max、
+//! margin、governor、active_profile、precise
+//!
+//! 内存所有权：`gov_state_json`返回的字符串由本库分配，调用方必须且只能通过
+//! `gov_free_string`释放一次，不能使用C的`free`
+
+use std::{
+    ffi::{c_char, CString},
+    io::{BufRead, BufReader, Write},
+    os::unix::net::UnixStream,
+    ptr,
+    time::Duration,
+};
+
+// 与调速器的控制套接字默认路径和覆盖环境变量一致（见datasource/file_path.rs）
+const CONTROL_SOCKET_PATH: &str = "/data/adb/gpu_governor/gpu_gov.sock";
+const CONTROL_SOCKET_ENV: &str = "MALIGOV_SOCKET";
+// 调速器无响应时的读写超时
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+// 控制套接字路径，环境变量优先
+fn socket_path() -> String {
+    std::env::var(CONTROL_SOCKET_ENV)
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| CONTROL_SOCKET_PATH.to_string())
+}
+
+// 发送state命令并返回JSON，调速器未运行或回复错误时返回None
+fn read_state_json(path: &str) -> Option<String> {
+    let mut stream = UnixStream::connect(path).ok()?;
+    stream.set_read_timeout(Some(TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(TIMEOUT)).ok()?;
+    stream.write_all(b"state\n").ok()?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).ok()?;
+    reply.trim_end().strip_prefix("OK ").map(str::to_string)
+}
+
+/// 读取调速器状态，返回以NUL结尾的单行JSON
+///
+/// 调速器未运行或无法读取时返回空指针。返回的字符串必须通过`gov_free_string`释放
+#[no_mangle]
+pub extern "C" fn gov_state_json() -> *mut c_char {
+    read_state_json(&socket_path())
+        .and_then(|json| CString::new(json).ok())
+        .map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// 释放`gov_state_json`返回的字符串，传入空指针时不做任何操作
+///
+/// # Safety
+///
+/// `value`必须是`gov_state_json`返回且尚未释放的指针
+#[no_mangle]
+pub unsafe extern "C" fn gov_free_string(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{ffi::CStr, os::unix::net::UnixListener, thread};
+
+    const STATE: &str = "{\"freq\":850000,\"load\":42,\"governor\":\"simple\"}";
+
+    // 模拟控制套接字，对每个连接回复一行
+    fn serve_once(name: &str, reply: String) -> String {
+        let path = std::env::temp_dir().join(format!("gpugovernor-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut command = String::new();
+            BufReader::new(stream).read_line(&mut command).unwrap();
+            assert_eq!(command, "state\n");
+            writeln!(writer, "{reply}").unwrap();
+        });
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn state_json_is_returned_and_freed() {
+        let path = serve_once("ffi.sock", format!("OK {STATE}"));
+        std::env::set_var(CONTROL_SOCKET_ENV, &path);
+
+        let json = gov_state_json();
+        assert!(!json.is_null());
+        assert_eq!(unsafe { CStr::from_ptr(json) }.to_str().unwrap(), STATE);
+        unsafe {
+            gov_free_string(json);
+            gov_free_string(ptr::null_mut());
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn error_reply_or_missing_socket_returns_none() {
+        let path = serve_once("ffi-err.sock", "ERR unknown command: state".to_string());
+        assert_eq!(read_state_json(&path), None);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(read_state_json("/nonexistent/gpu_gov.sock"), None);
+    }
+}
@@ -3,36 +3,75 @@ mod datasource;
 mod model;
 mod utils;
 
-use std::{path::Path, thread, time::Duration};
+use std::{
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
 
 use anyhow::Result;
-use log::{error, info, warn};
+use log::{debug, error, info, warn};
 
 use crate::{
     datasource::{
-        config_parser::load_config,
+        battery::{monitor_battery, BatteryHandler},
+        config_parser::{
+            load_config, read_freq_table_source, read_log_format, read_pid_file_path,
+            FreqTableSource,
+        },
+        control_socket::monitor_control_socket,
         file_path::*,
-        foreground_app::monitor_foreground_app,
+        foreground_app::{monitor_foreground_app, ForegroundAppHandler},
         freq_table::gpufreq_table_init,
-        freq_table_parser::freq_table_read,
-        load_monitor::utilization_init,
-        node_monitor::{monitor_config, monitor_gaming},
+        freq_table_parser::{freq_table_read, freq_table_read_devfreq},
+        health_check::run_config_health_check,
+        load_monitor::{get_gpu_current_freq, is_precise_load_available, utilization_init},
+        node_monitor::{monitor_config, monitor_gaming, ConfigHandler, GameModeHandler},
+        screen::{detect_screen_node, monitor_screen_state, ScreenStateHandler},
+        thermal::{monitor_thermal_cap, thermal_steps, ThermalCapHandler},
+        touch_boost::{monitor_touch_boost, TouchBoostHandler},
+    },
+    model::{
+        effective_config::{publish_effective_config, should_log_effective_config},
+        governor_mode::GovernorMode,
+        governor_state::{get_boot_freq, set_boot_freq},
+        gpu::GPU,
+        opp_table::publish_opp_table,
+        stats::{get_stats, suggest_tuning},
     },
-    model::gpu::GPU,
     utils::{
-        constants::strategy, file_status::get_status,
-        log_level_manager::start_unified_log_level_monitor, logger::init_logger,
+        constants::strategy,
+        event_loop::{get_event_loop_mode, run_event_loop, EventLoopMode, PollHandler},
+        log_level_manager::{start_unified_log_level_monitor, unified_log_level_handler},
+        log_rotation::{monitor_log_rotation, LogRotationHandler},
+        logger::{init_logger, set_log_format},
+        pid_file::PidFile,
+        shutdown::install_shutdown_handler,
+        supervisor::Supervisor,
     },
 };
 
-/// 初始化GPU配置
-fn initialize_gpu_config(gpu: &mut GPU) -> Result<()> {
+/// 初始化GPU配置，返回TOML策略配置是否成功应用
+fn initialize_gpu_config(gpu: &mut GPU) -> Result<bool> {
     // 先初始化负载监控
     utilization_init()?;
 
+    // 按配置从devfreq读取频率表，失败时回退到频率表配置文件
+    let mut table_loaded = false;
+    if read_freq_table_source() == FreqTableSource::Devfreq {
+        info!("Reading frequency table from devfreq");
+        match freq_table_read_devfreq(gpu) {
+            Ok(()) => table_loaded = true,
+            Err(e) => warn!("Failed to read devfreq frequency table: {e}, falling back to file"),
+        }
+    }
+
     // 读取频率表配置文件
-    let config_file = FREQ_TABLE_CONFIG_FILE;
-    if Path::new(config_file).exists() {
+    let config_file = resolve_path(PathKey::FreqTable);
+    if table_loaded {
+        debug!("Frequency table loaded from devfreq, skipping {config_file}");
+    } else if Path::new(config_file).exists() {
         info!("Reading frequency table config file: {config_file}");
         freq_table_read(config_file, gpu)
             .map_err(|e| anyhow::anyhow!("Failed to read frequency table config file: {}", e))?;
@@ -44,85 +83,240 @@ fn initialize_gpu_config(gpu: &mut GPU) -> Result<()> {
     }
 
     // 尝试加载TOML策略配置
-    if Path::new(CONFIG_TOML_FILE).exists() {
-        info!("Reading TOML config file: {CONFIG_TOML_FILE}");
-        if let Err(e) = load_config(gpu) {
-            warn!("Failed to load TOML config: {e}, using default settings");
+    let mut config_applied = false;
+    let config_toml = resolve_path(PathKey::ConfigToml);
+    if Path::new(config_toml).exists() {
+        info!("Reading TOML config file: {config_toml}");
+        let is_empty = std::fs::read_to_string(config_toml)
+            .map(|content| content.trim().is_empty())
+            .unwrap_or(false);
+        if is_empty {
+            warn!("TOML config file is empty: {config_toml}, using built-in defaults");
+        } else if let Err(e) = load_config(gpu) {
+            warn!("Failed to load TOML config: {e}, using built-in defaults");
+        } else {
+            config_applied = true;
         }
     } else {
-        warn!("TOML config file not found: {CONFIG_TOML_FILE}, using default settings");
+        warn!("TOML config file not found: {config_toml}, using built-in defaults");
     }
 
     // 初始化GPU频率表
     gpufreq_table_init(gpu)?;
 
     // 设置精确模式
-    gpu.set_precise(get_status(DEBUG_DVFS_LOAD) || get_status(DEBUG_DVFS_LOAD_OLD));
+    gpu.set_precise(is_precise_load_available());
 
-    Ok(())
+    Ok(config_applied)
+}
+
+/// 将初始化成功的处理器加入事件循环，失败时跳过，不影响其他监控
+fn push_handler<H: PollHandler + 'static>(
+    handlers: &mut Vec<Box<dyn PollHandler>>,
+    handler: Result<H>,
+    name: &str,
+) {
+    match handler {
+        Ok(handler) => handlers.push(Box::new(handler)),
+        Err(e) => error!("{name} monitor error: {e}"),
+    }
+}
+
+/// 启动单线程事件循环，所有监控共用一个线程
+fn start_event_loop_thread(supervisor: &mut Supervisor, gpu: GPU) {
+    supervisor.spawn(EVENT_LOOP_THREAD, move || {
+        let gpu = gpu.clone();
+        let mut handlers: Vec<Box<dyn PollHandler>> = Vec::new();
+
+        push_handler(&mut handlers, GameModeHandler::new(), "Gaming");
+        push_handler(&mut handlers, ConfigHandler::new(gpu.clone()), "Config");
+        push_handler(
+            &mut handlers,
+            ForegroundAppHandler::new(
+                &gpu,
+                Duration::from_secs(strategy::FOREGROUND_APP_STARTUP_DELAY),
+            ),
+            "Foreground app",
+        );
+        push_handler(
+            &mut handlers,
+            unified_log_level_handler(),
+            "Unified log level",
+        );
+        push_handler(&mut handlers, LogRotationHandler::new(), "Log rotation");
+
+        let touch_boost_freq = gpu.frequency_strategy.touch_boost_freq;
+        let touch_boost_ms = gpu.frequency_strategy.touch_boost_ms;
+        if touch_boost_freq > 0 && touch_boost_ms > 0 {
+            push_handler(
+                &mut handlers,
+                TouchBoostHandler::new(touch_boost_freq, touch_boost_ms),
+                "Touch boost",
+            );
+        }
+
+        let thermal_steps = thermal_steps(&gpu);
+        if !thermal_steps.is_empty() {
+            push_handler(
+                &mut handlers,
+                ThermalCapHandler::new(&gpu.frequency_strategy.thermal_zones, thermal_steps),
+                "Thermal cap",
+            );
+        }
+
+        if let Some(node) = detect_screen_node(gpu.frequency_strategy.screen_state_node.as_deref())
+        {
+            push_handler(
+                &mut handlers,
+                ScreenStateHandler::new(node, gpu.get_min_freq()),
+                "Screen state",
+            );
+        }
+
+        let battery_saver_threshold = gpu.frequency_strategy.battery_saver_threshold;
+        if battery_saver_threshold > 0 {
+            push_handler(
+                &mut handlers,
+                BatteryHandler::new(battery_saver_threshold),
+                "Battery saver",
+            );
+        }
+
+        run_event_loop(handlers)
+    });
 }
 
-/// 启动监控线程
+/// 启动监控线程，由守护线程在监控线程退出后重新启动
 fn start_monitoring_threads(gpu: GPU) {
+    let mut supervisor = Supervisor::default();
+
+    // 控制套接字线程，一个连接可能持续发送命令，单线程模式下也使用独立线程，避免阻塞其他监控
+    let gpu_clone4 = gpu.clone();
+    supervisor.spawn(CONTROL_SOCKET_THREAD, move || {
+        monitor_control_socket(gpu_clone4.clone())
+    });
+
+    // 单线程模式：其余监控复用同一个poll循环
+    if get_event_loop_mode() == EventLoopMode::Single {
+        info!("Using single-threaded event loop for monitors");
+        start_event_loop_thread(&mut supervisor, gpu);
+        supervisor.start();
+        return;
+    }
+
     // 游戏监控线程
-    let gpu_clone1 = gpu.clone();
-    thread::Builder::new()
-        .name(GAME_THREAD.to_string())
-        .spawn(move || {
-            if let Err(e) = monitor_gaming(gpu_clone1) {
-                error!("Gaming monitor error: {e}");
-            }
-        })
-        .expect("Failed to spawn gaming monitor thread");
+    supervisor.spawn(GAME_THREAD, monitor_gaming);
 
     // 配置监控线程
     let gpu_clone2 = gpu.clone();
-    thread::Builder::new()
-        .name(CONF_THREAD.to_string())
-        .spawn(move || {
-            if let Err(e) = monitor_config(gpu_clone2) {
-                error!("Config monitor error: {e}");
-            }
-        })
-        .expect("Failed to spawn config monitor thread");
+    supervisor.spawn(CONF_THREAD, move || monitor_config(gpu_clone2.clone()));
 
-    // 前台应用监控线程（延迟启动）
-    thread::Builder::new()
-        .name(FOREGROUND_APP_THREAD.to_string())
-        .spawn(move || {
+    // 前台应用监控线程（仅首次启动时延迟，重启时立即运行）
+    let gpu_clone3 = gpu.clone();
+    let startup_delay = AtomicBool::new(true);
+    supervisor.spawn(FOREGROUND_APP_THREAD, move || {
+        if startup_delay.swap(false, Ordering::SeqCst) {
             info!(
                 "Foreground app monitor will start in {} seconds",
                 strategy::FOREGROUND_APP_STARTUP_DELAY
             );
             thread::sleep(Duration::from_secs(strategy::FOREGROUND_APP_STARTUP_DELAY));
             info!("Starting foreground app monitor now");
+        }
+
+        monitor_foreground_app(gpu_clone3.clone())
+    });
+
+    // 触摸升频线程（仅在配置了升频频率时启动）
+    let touch_boost_freq = gpu.frequency_strategy.touch_boost_freq;
+    let touch_boost_ms = gpu.frequency_strategy.touch_boost_ms;
+    if touch_boost_freq > 0 && touch_boost_ms > 0 {
+        supervisor.spawn(TOUCH_BOOST_THREAD, move || {
+            monitor_touch_boost(touch_boost_freq, touch_boost_ms)
+        });
+    }
+
+    // 温度限频线程（仅在配置了温区和至少一个限频档位时启动）
+    let thermal_zones = gpu.frequency_strategy.thermal_zones.clone();
+    let thermal_steps = thermal_steps(&gpu);
+    if !thermal_steps.is_empty() {
+        supervisor.spawn(THERMAL_THREAD, move || {
+            monitor_thermal_cap(&thermal_zones, thermal_steps.clone())
+        });
+    }
+
+    // 熄屏限频线程（仅在找到可读的屏幕状态节点时启动）
+    if let Some(node) = detect_screen_node(gpu.frequency_strategy.screen_state_node.as_deref()) {
+        let min_freq = gpu.get_min_freq();
+        supervisor.spawn(SCREEN_THREAD, move || {
+            monitor_screen_state(node.clone(), min_freq)
+        });
+    }
+
+    // 电池省电线程（仅在配置了电量阈值时启动）
+    let battery_saver_threshold = gpu.frequency_strategy.battery_saver_threshold;
+    if battery_saver_threshold > 0 {
+        supervisor.spawn(BATTERY_THREAD, move || {
+            monitor_battery(battery_saver_threshold)
+        });
+    }
 
-            if let Err(e) = monitor_foreground_app() {
-                error!("Foreground app monitor error: {e}");
+    // 统一的日志等级监控线程
+    supervisor.spawn(LOG_LEVEL_MONITOR_THREAD, start_unified_log_level_monitor);
+
+    // 日志轮转线程
+    supervisor.spawn(LOG_ROTATION_THREAD, monitor_log_rotation);
+
+    supervisor.start();
+}
+
+/// 记录调速器接管前驱动的频率
+fn capture_boot_freq(gpu: &GPU) {
+    match get_gpu_current_freq(!gpu.is_gpuv2()) {
+        Ok(freq) if freq > 0 => set_boot_freq(freq),
+        Ok(freq) => warn!("Invalid boot frequency: {freq}"),
+        Err(e) => warn!("Failed to read boot frequency: {e}"),
+    }
+}
+
+/// 初始化启动频率
+fn initialize_startup_freq(gpu: &mut GPU) {
+    let config_len = gpu.get_config_list().len() as i64;
+    let mut startup_idx = gpu.frequency().startup_opp_index;
+    if startup_idx < 0 || startup_idx >= config_len {
+        warn!("Startup OPP index {startup_idx} is out of range (0..{config_len}), using index 0");
+        startup_idx = 0;
+    }
+
+    // 沿用驱动当前频率，避免启动时直接降到最低频率
+    if gpu.frequency().startup_use_current_freq {
+        match get_gpu_current_freq(!gpu.is_gpuv2()) {
+            Ok(current_freq) if current_freq > 0 => {
+                let freq = gpu.read_freq_ge(current_freq);
+                startup_idx = gpu.frequency().read_freq_index(freq);
+                info!("Starting from current driver frequency: {current_freq}KHz -> {freq}KHz");
             }
-        })
-        .expect("Failed to spawn foreground app monitor thread");
-
-    // 统一的日志等级监控线程（包含日志轮转功能）
-    thread::Builder::new()
-        .name(LOG_LEVEL_MONITOR_THREAD.to_string())
-        .spawn(move || {
-            if let Err(e) = start_unified_log_level_monitor() {
-                error!("Unified log level monitor error: {e}");
+            Ok(current_freq) => {
+                warn!("Invalid current driver frequency: {current_freq}, using startup OPP index {startup_idx}");
             }
-        })
-        .expect("Failed to spawn log level monitor thread");
+            Err(e) => {
+                warn!("Failed to read current driver frequency: {e}, using startup OPP index {startup_idx}");
+            }
+        }
+    }
+
+    gpu.set_cur_freq(gpu.get_freq_by_index(startup_idx));
+    gpu.frequency_mut().cur_freq_idx = startup_idx;
+    gpu.frequency_mut().gen_cur_volt();
+    info!("Startup OPP index: {startup_idx}");
 }
 
-/// 配置GPU策略
-fn configure_gpu_strategy(gpu: &mut GPU) {
-    // 使用超简化的90%升频策略
-    gpu.configure_strategy(
-        0,                                 // 无余量
-        1,                                 // 降频阈值
-        strategy::SAMPLING_INTERVAL_120HZ, // 120Hz采样
-        true,                              // 激进降频
-    );
+/// 配置GPU策略，TOML配置已应用时保留配置中的值
+fn configure_gpu_strategy(gpu: &mut GPU, config_applied: bool) {
+    // 使用均衡模式（超简化的90%升频策略）作为内置默认值
+    if !config_applied {
+        gpu.apply_mode(GovernorMode::Balanced);
+    }
 
     // 其他策略设置
     gpu.frequency_strategy_mut().set_load_stability_threshold(1);
@@ -133,13 +327,78 @@ fn configure_gpu_strategy(gpu: &mut GPU) {
     );
 }
 
+/// 输出最终生效的策略参数及其来源
+fn log_effective_strategy(gpu: &GPU, config_applied: bool) {
+    let source = if config_applied {
+        "config"
+    } else {
+        "built-in default"
+    };
+    let strategy = &gpu.frequency_strategy;
+
+    info!("Effective strategy values:");
+    info!("  margin = {}% ({source})", strategy.margin);
+    info!("  down_threshold = {} ({source})", strategy.down_threshold);
+    info!(
+        "  sampling_interval = {}ms ({source})",
+        strategy.sampling_interval
+    );
+    info!(
+        "  aggressive_down = {} ({source})",
+        strategy.aggressive_down
+    );
+    info!(
+        "  very_high_load_threshold = {}% ({source})",
+        strategy.very_high_load_threshold
+    );
+    info!(
+        "  idle_threshold = {}% ({source})",
+        gpu.idle_manager.idle_threshold
+    );
+    info!("  governor = {:?} ({source})", strategy.governor);
+    info!(
+        "  load_stability_threshold = {} (built-in default)",
+        strategy.load_stability_threshold
+    );
+    info!(
+        "  adaptive_sampling = {} (built-in default)",
+        strategy.adaptive_sampling
+    );
+    if strategy.fixed_freq > 0 {
+        info!(
+            "  fixed_freq = {}KHz, pinned to {}KHz, hold at idle: {} ({source})",
+            strategy.fixed_freq,
+            gpu.frequency().read_freq_nearest(strategy.fixed_freq),
+            strategy.fixed_freq_hold_idle
+        );
+    }
+}
+
+/// 发布最终生效的配置，按配置选项输出到日志
+fn log_effective_config(gpu: &GPU) {
+    match publish_effective_config(gpu) {
+        Ok(toml) if should_log_effective_config() => {
+            info!("Effective config:");
+            for line in toml.lines() {
+                info!("  {line}");
+            }
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Failed to serialize effective config: {e}"),
+    }
+}
+
 /// 显示系统信息
 fn display_system_info(gpu: &GPU) {
     info!("Monitor Inited");
     info!("{MAIN_THREAD} Start");
 
     // 频率信息
-    info!("BootFreq: {}KHz", gpu.get_cur_freq());
+    match get_boot_freq() {
+        Some(boot_freq) => info!("BootFreq: {boot_freq}KHz"),
+        None => info!("BootFreq: unknown"),
+    }
+    info!("StartupFreq: {}KHz", gpu.get_cur_freq());
     info!(
         "Driver: gpufreq{}",
         if gpu.is_gpuv2() { "v2" } else { "v1" }
@@ -149,7 +408,7 @@ fn display_system_info(gpu: &GPU) {
         if gpu.is_precise() { "Yes" } else { "No" }
     );
     info!("Max Freq: {}KHz", gpu.get_max_freq());
-    info!("Middle Freq: {}KHz", gpu.get_middle_freq());
+    info!("Middle Freq: {}KHz", gpu.get_mid_range_freq());
     info!("Min Freq: {}KHz", gpu.get_min_freq());
     info!("Current Margin: {}%", gpu.get_margin());
 
@@ -163,6 +422,12 @@ fn display_system_info(gpu: &GPU) {
                 "Disabled"
             }
         );
+        if gpu.dcs_load_scaling {
+            info!(
+                "DCS Load Scaling: pause at {}%, resume at {}%",
+                gpu.dcs_high_load, gpu.dcs_low_load
+            );
+        }
         info!(
             "V2 Driver Down Threshold: {} times",
             gpu.get_down_threshold()
@@ -200,6 +465,12 @@ fn display_ddr_info(gpu: &GPU) {
             if freq_table.len() > 3 {
                 info!("  ... and {} more options", freq_table.len() - 3);
             }
+
+            // 调试级别输出完整的DDR频率表
+            debug!("Full DDR frequency table ({} options):", freq_table.len());
+            for (i, (opp, desc)) in freq_table.iter().enumerate() {
+                debug!("  Option {}: OPP={}, Description: {}", i + 1, opp, desc);
+            }
         }
         Err(e) => {
             warn!("Failed to get DDR frequency table: {e}");
@@ -229,7 +500,11 @@ fn main() -> Result<()> {
         }
     }
 
+    // 写入PID文件，防止多个实例同时争抢OPP节点（在初始化日志前检查，避免清空运行中实例的日志）
+    let pid_file = PidFile::acquire(read_pid_file_path())?;
+
     // 初始化日志
+    set_log_format(read_log_format());
     init_logger()?;
 
     // 版本信息写入到日志文件
@@ -238,12 +513,26 @@ fn main() -> Result<()> {
     info!("{}", crate::utils::constants::SPECIAL);
     info!("{}", crate::utils::constants::VERSION);
 
+    if let Some(stale_pid) = pid_file.stale_pid() {
+        warn!("Overwrote stale PID file left by PID {stale_pid}");
+    }
+    info!("PID file: {}", pid_file.path().display());
+    if let Err(e) = install_shutdown_handler() {
+        warn!("{e}");
+    }
+    for (env_var, path) in overridden_paths() {
+        info!("Path overridden by {env_var}: {path}");
+    }
+
     // 初始化GPU
     let mut gpu = GPU::new();
     info!("Loading");
 
     // 初始化GPU配置
-    initialize_gpu_config(&mut gpu)?;
+    let config_applied = initialize_gpu_config(&mut gpu)?;
+
+    // 在第一次写入频率之前记录驱动的启动频率
+    capture_boot_freq(&gpu);
 
     // 启动监控线程
     start_monitoring_threads(gpu.clone());
@@ -252,17 +541,42 @@ fn main() -> Result<()> {
     thread::sleep(Duration::from_secs(5));
 
     // 初始化频率和电压
-    gpu.set_cur_freq(gpu.get_freq_by_index(0));
-    gpu.frequency_mut().gen_cur_volt();
+    initialize_startup_freq(&mut gpu);
 
     // 配置策略
-    configure_gpu_strategy(&mut gpu);
+    configure_gpu_strategy(&mut gpu, config_applied);
+    log_effective_strategy(&gpu, config_applied);
+    log_effective_config(&gpu);
+    publish_opp_table(&gpu);
 
     // 显示系统信息
     display_system_info(&gpu);
 
+    // 对照驱动能力检查配置
+    run_config_health_check(&gpu);
+
     info!("Advanced GPU Governor Started");
 
     // 开始频率调整
-    gpu.adjust_gpufreq()
+    gpu.adjust_gpufreq()?;
+
+    // 退出前输出调参建议，仅供参考，不会自动应用
+    log_tuning_suggestions();
+    Ok(())
+}
+
+fn log_tuning_suggestions() {
+    let stats = get_stats();
+    info!(
+        "Session stats: {:.0}s, {} frequency changes",
+        stats.total_time().as_secs_f64(),
+        stats.freq_changes
+    );
+    let suggestions = suggest_tuning(&stats);
+    if suggestions.is_empty() {
+        info!("No tuning suggestions");
+    }
+    for suggestion in suggestions {
+        info!("Tuning suggestion: {suggestion}");
+    }
 }
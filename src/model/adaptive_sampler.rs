@@ -0,0 +1,144 @@
+use std::collections::VecDeque;
+
+/// 自适应采样间隔 - 根据最近若干次负载的方差调整采样间隔
+///
+/// 负载稳定（方差不超过阈值）时逐步延长间隔以减少唤醒，负载波动时减半间隔以加快响应
+#[derive(Clone, Default)]
+pub struct AdaptiveSampler {
+    /// 计算方差的采样数，0表示不启用
+    window: usize,
+    /// 方差阈值（负载百分比的平方）
+    variance_threshold: f64,
+    /// 最近的负载采样
+    loads: VecDeque<i32>,
+    /// 当前采样间隔（毫秒），尚未计算时为0
+    interval: u64,
+}
+
+impl AdaptiveSampler {
+    pub fn new(window: usize, variance_threshold: f64) -> Self {
+        Self {
+            window,
+            variance_threshold,
+            loads: VecDeque::with_capacity(window),
+            interval: 0,
+        }
+    }
+
+    /// 是否启用
+    pub fn is_enabled(&self) -> bool {
+        self.window > 0
+    }
+
+    /// 加入一个采样并返回限制在[min_interval, max_interval]内的采样间隔，无效负载（负数）不影响方差
+    pub fn push(&mut self, load: i32, min_interval: u64, max_interval: u64) -> u64 {
+        let max_interval = max_interval.max(min_interval);
+        self.interval = self.interval.clamp(min_interval, max_interval);
+        if !self.is_enabled() || load < 0 {
+            return self.interval;
+        }
+
+        self.loads.push_back(load);
+        while self.loads.len() > self.window {
+            self.loads.pop_front();
+        }
+
+        // 窗口未填满时保持当前间隔
+        let Some(variance) = self.variance() else {
+            return self.interval;
+        };
+        self.interval = if variance <= self.variance_threshold {
+            // 每次延长当前间隔的1/4，至少1ms
+            (self.interval + (self.interval / 4).max(1)).min(max_interval)
+        } else {
+            (self.interval / 2).max(min_interval)
+        };
+        self.interval
+    }
+
+    /// 窗口内负载的方差，窗口未填满时为None
+    pub fn variance(&self) -> Option<f64> {
+        if !self.is_enabled() || self.loads.len() < self.window {
+            return None;
+        }
+        let count = self.loads.len() as f64;
+        let mean = self.loads.iter().map(|&load| load as f64).sum::<f64>() / count;
+        let variance = self
+            .loads
+            .iter()
+            .map(|&load| {
+                let diff = load as f64 - mean;
+                diff * diff
+            })
+            .sum::<f64>()
+            / count;
+        Some(variance)
+    }
+
+    /// 当前采样间隔，尚未计算时为0
+    pub fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    /// 清除采样，下一次采样从最小间隔重新开始
+    pub fn reset(&mut self) {
+        self.loads.clear();
+        self.interval = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constant_load_widens_interval() {
+        let mut sampler = AdaptiveSampler::new(4, 25.0);
+        // 窗口未填满时保持最小间隔
+        for _ in 0..3 {
+            assert_eq!(sampler.push(50, 16, 160), 16);
+        }
+        assert_eq!(sampler.variance(), None);
+
+        assert_eq!(sampler.push(50, 16, 160), 20);
+        assert_eq!(sampler.push(50, 16, 160), 25);
+        assert_eq!(sampler.push(50, 16, 160), 31);
+        for _ in 0..20 {
+            sampler.push(50, 16, 160);
+        }
+        assert_eq!(sampler.interval(), 160);
+    }
+
+    #[test]
+    fn noisy_load_narrows_interval() {
+        let mut sampler = AdaptiveSampler::new(4, 25.0);
+        for _ in 0..30 {
+            sampler.push(50, 16, 160);
+        }
+        assert_eq!(sampler.interval(), 160);
+
+        sampler.push(10, 16, 160);
+        assert!(sampler.variance().unwrap() > 25.0);
+        assert_eq!(sampler.interval(), 80);
+        for load in [90, 10, 90, 10] {
+            sampler.push(load, 16, 160);
+        }
+        assert_eq!(sampler.interval(), 16);
+    }
+
+    #[test]
+    fn invalid_load_and_disabled_keep_interval() {
+        let mut sampler = AdaptiveSampler::new(2, 25.0);
+        sampler.push(50, 16, 160);
+        assert_eq!(sampler.push(50, 16, 160), 20);
+        assert_eq!(sampler.push(-1, 16, 160), 20);
+
+        sampler.reset();
+        assert_eq!(sampler.interval(), 0);
+
+        let mut disabled = AdaptiveSampler::new(0, 25.0);
+        assert!(!disabled.is_enabled());
+        assert_eq!(disabled.push(50, 16, 160), 16);
+        assert_eq!(disabled.push(90, 16, 160), 16);
+    }
+}
@@ -0,0 +1,172 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use log::{debug, info};
+use once_cell::sync::Lazy;
+
+use crate::model::{
+    freq_limits::{
+        clear_temporary_floor, set_max_cap, set_min_floor, set_temporary_floor, LimitSource,
+    },
+    package_pattern::PackagePattern,
+};
+
+/// 应用配置 - 针对特定前台应用的调速器行为
+#[derive(Clone, Debug, Default)]
+pub struct AppProfile {
+    /// 应用包名，可以是通配符或正则（见PackagePattern）
+    pub package: String,
+    /// 加载时编译的包名匹配规则
+    pub pattern: PackagePattern,
+    /// 前台时禁用DCS
+    pub disable_dcs: bool,
+    /// 前台时禁止调整DDR频率
+    pub disable_ddr_scaling: bool,
+    /// 切换到前台时临时升到最高频率
+    pub launch_boost: bool,
+    /// 启动升频持续时间（毫秒）
+    pub launch_boost_ms: u64,
+    /// 前台时的最低频率（KHz），不在频率表中时向上吸附
+    pub min_freq: Option<i64>,
+    /// 前台时的最高频率（KHz），不在频率表中时向下吸附
+    pub max_freq: Option<i64>,
+}
+
+// 全局应用配置列表，由配置加载写入，前台应用监控读取
+static APP_PROFILES: Lazy<Mutex<Vec<AppProfile>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+// 当前前台应用配置生效的抑制状态，每次前台切换时整体重算，不修改全局设置
+static DCS_SUPPRESSED: AtomicBool = AtomicBool::new(false);
+static DDR_SCALING_SUPPRESSED: AtomicBool = AtomicBool::new(false);
+// 当前生效的应用配置包名，前台应用没有匹配的配置时为None
+static ACTIVE_PROFILE: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+/// 替换应用配置列表
+pub fn set_app_profiles(profiles: Vec<AppProfile>) {
+    info!("Loaded {} app profiles", profiles.len());
+    *APP_PROFILES.lock().unwrap() = profiles;
+}
+
+/// 获取应用配置列表
+pub fn get_app_profiles() -> Vec<AppProfile> {
+    APP_PROFILES.lock().unwrap().clone()
+}
+
+// 查找前台应用对应的配置：精确包名优先，其次是第一个匹配的通配符或正则配置
+fn find_profile<'a>(profiles: &'a [AppProfile], package: &str) -> Option<&'a AppProfile> {
+    profiles
+        .iter()
+        .find(|profile| profile.pattern.is_exact() && profile.pattern.matches(package))
+        .or_else(|| {
+            profiles
+                .iter()
+                .find(|profile| !profile.pattern.is_exact() && profile.pattern.matches(package))
+        })
+}
+
+/// 根据前台应用包名应用对应的配置，没有匹配时解除所有抑制和频率范围，返回是否有匹配的配置
+pub fn apply_app_profile(package: &str) -> bool {
+    let profiles = APP_PROFILES.lock().unwrap();
+    let profile = find_profile(&profiles, package);
+
+    let disable_dcs = profile.is_some_and(|profile| profile.disable_dcs);
+    let disable_ddr_scaling = profile.is_some_and(|profile| profile.disable_ddr_scaling);
+
+    if DCS_SUPPRESSED.swap(disable_dcs, Ordering::SeqCst) != disable_dcs {
+        info!(
+            "DCS {} by app profile ({package})",
+            if disable_dcs {
+                "suppressed"
+            } else {
+                "restored"
+            }
+        );
+    }
+    if DDR_SCALING_SUPPRESSED.swap(disable_ddr_scaling, Ordering::SeqCst) != disable_ddr_scaling {
+        info!(
+            "DDR scaling {} by app profile ({package})",
+            if disable_ddr_scaling {
+                "suppressed"
+            } else {
+                "restored"
+            }
+        );
+    }
+    // 启动升频：以最高频率作为临时下限，到期后自动恢复正常调频，切换到其他应用时立即结束
+    match profile.filter(|profile| profile.launch_boost && profile.launch_boost_ms > 0) {
+        Some(profile) => {
+            info!(
+                "Launch boost for {package}: max frequency for {}ms",
+                profile.launch_boost_ms
+            );
+            set_temporary_floor(
+                LimitSource::LaunchBoost,
+                i64::MAX,
+                Duration::from_millis(profile.launch_boost_ms),
+            );
+        }
+        None => clear_temporary_floor(LimitSource::LaunchBoost),
+    }
+    // 频率范围由调频循环按频率表吸附，没有匹配时回到全局配置
+    let min_freq = profile.and_then(|profile| profile.min_freq);
+    let max_freq = profile.and_then(|profile| profile.max_freq);
+    if min_freq.is_some() || max_freq.is_some() {
+        info!("Frequency range for {package}: min={min_freq:?} max={max_freq:?}");
+    }
+    set_min_floor(LimitSource::AppProfile, min_freq);
+    set_max_cap(LimitSource::AppProfile, max_freq);
+
+    *ACTIVE_PROFILE.lock().unwrap() = profile.map(|profile| profile.package.clone());
+    debug!("Applied app profile for {package}: {profile:?}");
+    profile.is_some()
+}
+
+/// 当前生效的应用配置包名
+pub fn get_active_profile() -> Option<String> {
+    ACTIVE_PROFILE.lock().unwrap().clone()
+}
+
+/// 当前前台应用是否禁用DCS
+pub fn is_dcs_suppressed() -> bool {
+    DCS_SUPPRESSED.load(Ordering::SeqCst)
+}
+
+/// 当前前台应用是否禁止调整DDR频率
+pub fn is_ddr_scaling_suppressed() -> bool {
+    DDR_SCALING_SUPPRESSED.load(Ordering::SeqCst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(package: &str, min_freq: i64) -> AppProfile {
+        AppProfile {
+            package: package.to_string(),
+            pattern: PackagePattern::parse(package).unwrap(),
+            min_freq: Some(min_freq),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn exact_profile_wins_over_pattern() {
+        let profiles = vec![
+            profile("com.miHoYo.*", 1),
+            profile("re:com\\.miHoYo\\..*", 2),
+            profile("com.miHoYo.GenshinImpact", 3),
+        ];
+        let found = |package| find_profile(&profiles, package).and_then(|profile| profile.min_freq);
+
+        // 列在通配符之后的精确包名优先
+        assert_eq!(found("com.miHoYo.GenshinImpact"), Some(3));
+        // 没有精确匹配时使用第一个匹配的规则
+        assert_eq!(found("com.miHoYo.hkrpg"), Some(1));
+        assert_eq!(found("com.tencent.tmgp.sgame"), None);
+    }
+}
@@ -3,7 +3,7 @@ use log::{debug, info, warn};
 use std::path::Path;
 
 use crate::datasource::file_path::*;
-use crate::utils::file_helper::FileHelper;
+use crate::utils::{constants::strategy, file_helper::FileHelper};
 
 /// DDR频率管理器 - 负责内存频率控制
 #[derive(Clone)]
@@ -16,6 +16,14 @@ pub struct DdrManager {
     pub ddr_v2_supported_freqs: Vec<i64>,
     /// 是否使用v2驱动
     pub gpuv2: bool,
+    /// 是否根据GPU负载调整内存频率
+    pub load_scaling: bool,
+    /// GPU负载达到该值时提高内存频率
+    pub high_load: i32,
+    /// GPU负载降到该值时恢复自动模式
+    pub low_load: i32,
+    /// 当前固定的内存频率是否由负载调整设置
+    pub load_boosted: bool,
 }
 
 impl DdrManager {
@@ -25,11 +33,18 @@ impl DdrManager {
             ddr_freq: 0,
             ddr_v2_supported_freqs: Vec::new(),
             gpuv2: false,
+            load_scaling: false,
+            high_load: strategy::DDR_HIGH_LOAD_DEFAULT,
+            low_load: strategy::DDR_LOW_LOAD_DEFAULT,
+            load_boosted: false,
         }
     }
 
     /// 设置DDR频率
     pub fn set_ddr_freq(&mut self, freq: i64) -> Result<()> {
+        // 其他来源设置的频率优先，负载调整不再接管
+        self.load_boosted = false;
+
         // 如果频率是999，表示不固定内存频率，让系统自己选择
         if freq == 999 {
             self.ddr_freq = if self.gpuv2 {
@@ -88,6 +103,58 @@ impl DdrManager {
         self.write_ddr_freq()
     }
 
+    /// 设置根据GPU负载调整内存频率的开关和高低负载阈值，两个阈值之间的区间作为迟滞
+    pub fn set_load_scaling(&mut self, enabled: bool, high_load: i32, low_load: i32) {
+        let (high_load, low_load) = (high_load.clamp(0, 100), low_load.clamp(0, 100));
+        if low_load < high_load {
+            self.high_load = high_load;
+            self.low_load = low_load;
+        } else {
+            warn!(
+                "Invalid DDR load thresholds (high {high_load}%, low {low_load}%), using defaults"
+            );
+            self.high_load = strategy::DDR_HIGH_LOAD_DEFAULT;
+            self.low_load = strategy::DDR_LOW_LOAD_DEFAULT;
+        }
+        self.load_scaling = enabled;
+
+        // 关闭时交还由负载调整固定的内存频率
+        if !enabled && self.load_boosted {
+            if let Err(e) = self.set_ddr_freq(999) {
+                warn!("Failed to restore auto DDR mode: {e}");
+            }
+        }
+        debug!(
+            "Set DDR load scaling: {enabled} (high {}%, low {}%)",
+            self.high_load, self.low_load
+        );
+    }
+
+    /// 根据GPU负载调整内存频率：高负载时固定为映射的DDR_OPP，低负载时恢复自动模式，
+    /// 内存频率已被其他来源固定时不调整
+    pub fn update_for_load(&mut self, load: i32, ddr_opp: i64) -> Result<()> {
+        if !self.load_scaling || (self.ddr_freq_fixed && !self.load_boosted) {
+            return Ok(());
+        }
+
+        if load >= self.high_load {
+            if self.load_boosted && self.ddr_freq == ddr_opp {
+                return Ok(());
+            }
+            if self.load_boosted {
+                debug!("DDR follows GPU frequency to OPP {ddr_opp}");
+            } else {
+                info!("GPU load {load}%, raising DDR to OPP {ddr_opp}");
+            }
+            self.set_ddr_freq(ddr_opp)?;
+            self.load_boosted = true;
+        } else if load <= self.low_load && self.load_boosted {
+            info!("GPU load {load}%, returning DDR to auto mode");
+            self.set_ddr_freq(999)?;
+        }
+        Ok(())
+    }
+
     /// 写入DDR频率
     pub fn write_ddr_freq(&self) -> Result<()> {
         if !self.ddr_freq_fixed {
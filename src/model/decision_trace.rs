@@ -0,0 +1,86 @@
+use std::fmt;
+
+/// 修改调频目标的阶段
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceStage {
+    /// 持续高负载轮换
+    SustainedRotation,
+    /// 负载持续上升时的预测升频
+    Predictive,
+    /// 降频延迟
+    DownscaleDelay,
+    /// 持续高负载抬高的动态下限
+    SustainedFloor,
+    /// 单次调频步长限制
+    StepLimit,
+    /// 频率下限（触摸/掉帧升频等）
+    Floor,
+    /// 频率上限（后台限制等）
+    Cap,
+}
+
+/// 调频决策追踪 - 记录一次决策的输入和每个阶段对目标频率的修改
+#[derive(Clone, Debug, Default)]
+pub struct DecisionTrace {
+    /// 原始负载
+    pub raw_load: i32,
+    /// 窗口平均后的负载
+    pub smoothed_load: i32,
+    /// 经过最低负载限制后用于决策的负载
+    pub effective_load: i32,
+    /// 决策前的频率
+    pub current_freq: i64,
+    /// 按负载需求计算的频率（仅需求升频模式）
+    pub demand_freq: Option<i64>,
+    /// 调速器给出的目标频率（修正前）
+    pub governor_target: i64,
+    /// 各阶段的修改 (阶段, 修改前, 修改后)
+    pub modifiers: Vec<(TraceStage, i64, i64)>,
+    /// 最终频率
+    pub final_freq: i64,
+    /// 最终频率索引
+    pub final_idx: i64,
+    /// 是否处于空闲状态（未进行调频）
+    pub idle: bool,
+}
+
+impl DecisionTrace {
+    pub fn new(raw_load: i32, smoothed_load: i32, effective_load: i32, current_freq: i64) -> Self {
+        Self {
+            raw_load,
+            smoothed_load,
+            effective_load,
+            current_freq,
+            final_freq: current_freq,
+            ..Self::default()
+        }
+    }
+
+    /// 记录一个阶段的修改，目标未变化时不记录
+    pub fn modify(&mut self, stage: TraceStage, from: i64, to: i64) {
+        if from != to {
+            self.modifiers.push((stage, from, to));
+        }
+    }
+}
+
+impl fmt::Display for DecisionTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "load raw={}% smoothed={}% effective={}%, cur={}KHz",
+            self.raw_load, self.smoothed_load, self.effective_load, self.current_freq
+        )?;
+        if self.idle {
+            return write!(f, ", idle");
+        }
+        if let Some(demand) = self.demand_freq {
+            write!(f, ", demand={demand}KHz")?;
+        }
+        write!(f, ", target={}KHz", self.governor_target)?;
+        for (stage, from, to) in &self.modifiers {
+            write!(f, ", {stage:?}: {from}->{to}KHz")?;
+        }
+        write!(f, ", final={}KHz (idx {})", self.final_freq, self.final_idx)
+    }
+}
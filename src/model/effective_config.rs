@@ -0,0 +1,350 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use anyhow::Result;
+use log::debug;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::{
+    datasource::load_monitor::{
+        get_disabled_load_sources, get_load_calibration, get_load_fusion, get_load_source_order,
+        get_precise_load_node, get_read_cache_ttl,
+    },
+    model::{
+        app_profile::get_app_profiles, governor_state::is_enabled, gpu::GPU,
+        heartbeat::get_heartbeat, snapshot::get_status_file, telemetry::get_telemetry,
+    },
+    utils::{
+        event_loop::get_event_loop_mode,
+        log_rotation::get_log_rotation_config,
+        logger::{get_log_format, get_max_log_rate},
+        numeric::get_numeric_format,
+        thread_sched::get_thread_sched,
+    },
+};
+
+/// 最终生效的配置 - 合并配置文件、默认值和覆盖项后的结果，用于导出为TOML
+#[derive(Serialize)]
+pub struct EffectiveConfig {
+    global: EffectiveGlobal,
+    strategy: EffectiveStrategy,
+    load_calibration: BTreeMap<String, f64>,
+    thread_sched: BTreeMap<String, EffectiveThreadSched>,
+    curve_point: Vec<EffectiveCurvePoint>,
+    volt_offset_freq: Vec<EffectiveVoltOffsetFreq>,
+    app_profile: Vec<EffectiveAppProfile>,
+}
+
+#[derive(Serialize)]
+struct EffectiveGlobal {
+    enabled: bool,
+    idle_threshold: i32,
+    startup_opp_index: i64,
+    startup_use_current_freq: bool,
+    governor: String,
+    background_max_freq: i64,
+    min_write_interval_us: u64,
+    numeric_format: String,
+    touch_boost_freq: i64,
+    touch_boost_ms: u64,
+    upscale_mode: String,
+    event_loop: String,
+    frame_drop_boost_freq: i64,
+    frame_drop_boost_ms: u64,
+    max_margin: i64,
+    sustained_opp_set: Vec<i64>,
+    sustained_window_ms: u64,
+    downscale_delay_ms: u64,
+    idle_grace_ms: u64,
+    fixed_freq: i64,
+    fixed_freq_hold_idle: bool,
+    ddr_load_scaling: bool,
+    ddr_high_load: i32,
+    ddr_low_load: i32,
+    dcs_load_scaling: bool,
+    dcs_high_load: i32,
+    dcs_low_load: i32,
+    allowed_freqs: Vec<i64>,
+    blocked_freqs: Vec<i64>,
+    volt_offset: i64,
+    min_volt: i64,
+    max_undervolt: i64,
+    volt_tolerance: i64,
+    reject_unknown_volt: bool,
+    max_step_up: u32,
+    max_step_down: u32,
+    predictive_steps: u32,
+    predictive_samples: usize,
+    log_effective_config: bool,
+    min_effective_load: i32,
+    load_window_ms: u64,
+    time_weighted_load: bool,
+    foreground_poll_ms: u64,
+    foreground_read_timeout_ms: u64,
+    decision_trace: bool,
+    verify_high_opp_index: Option<i64>,
+    load_stats_window_ms: u64,
+    precise_load_node: String,
+    load_fusion: String,
+    load_source_order: Vec<&'static str>,
+    disabled_load_sources: Vec<&'static str>,
+    read_cache_ttl_ms: u64,
+    frame_budget_ms: f64,
+    on_freq_mismatch: String,
+    sustained_floor_rise_secs: u64,
+    sustained_floor_decay_secs: u64,
+    status_file: Option<String>,
+    telemetry_file: Option<String>,
+    telemetry_stride: u32,
+    heartbeat_file: Option<String>,
+    heartbeat_interval_ms: u64,
+    thermal_zones: Vec<String>,
+    thermal_cap_temp: i32,
+    thermal_cap_freq: i64,
+    thermal_warn_temp: i32,
+    thermal_critical_temp: i32,
+    screen_state_node: Option<String>,
+    battery_saver_threshold: u32,
+    battery_saver_max_freq: i64,
+    config_apply_delay_ms: u64,
+    log_format: String,
+    max_log_lines_per_sec: u32,
+    log_max_size_kb: u64,
+    log_keep_files: u32,
+    unmatched_app_profile: String,
+    v2_snap_tiebreak: String,
+    opp_reset_value: String,
+    load_ema_alpha: f64,
+    adaptive_sampling_window: usize,
+    adaptive_variance_threshold: f64,
+}
+
+#[derive(Serialize)]
+struct EffectiveStrategy {
+    very_high_load_threshold: i32,
+    margin: i64,
+    down_threshold: i64,
+    aggressive_down: bool,
+    sampling_interval: u64,
+    load_stability_threshold: i32,
+    adaptive_sampling: bool,
+}
+
+#[derive(Serialize)]
+struct EffectiveCurvePoint {
+    load: i32,
+    freq: i64,
+}
+
+#[derive(Serialize)]
+struct EffectiveVoltOffsetFreq {
+    freq: i64,
+    offset: i64,
+}
+
+#[derive(Serialize)]
+struct EffectiveThreadSched {
+    cpu_mask: Option<u64>,
+    nice: Option<i32>,
+}
+
+#[derive(Serialize)]
+struct EffectiveAppProfile {
+    package: String,
+    disable_dcs: bool,
+    disable_ddr_scaling: bool,
+    launch_boost: bool,
+    launch_boost_ms: u64,
+    min_freq: Option<i64>,
+    max_freq: Option<i64>,
+}
+
+// 枚举值按配置文件中的写法输出
+fn config_name<T: std::fmt::Debug>(value: T) -> String {
+    format!("{value:?}").to_lowercase()
+}
+
+impl EffectiveConfig {
+    /// 从GPU状态和全局设置生成生效配置
+    pub fn capture(gpu: &GPU) -> Self {
+        let strategy = &gpu.frequency_strategy;
+        let frequency = gpu.frequency();
+        let (telemetry_file, telemetry_stride) = get_telemetry();
+        let (heartbeat_file, heartbeat_interval_ms) = get_heartbeat();
+        let (log_max_size_kb, log_keep_files) = get_log_rotation_config();
+
+        Self {
+            global: EffectiveGlobal {
+                enabled: is_enabled(),
+                idle_threshold: gpu.idle_manager.idle_threshold,
+                startup_opp_index: frequency.startup_opp_index,
+                startup_use_current_freq: frequency.startup_use_current_freq,
+                governor: config_name(strategy.governor),
+                background_max_freq: strategy.background_max_freq,
+                min_write_interval_us: frequency.min_write_interval_us,
+                numeric_format: config_name(get_numeric_format()),
+                touch_boost_freq: strategy.touch_boost_freq,
+                touch_boost_ms: strategy.touch_boost_ms,
+                upscale_mode: config_name(strategy.upscale_mode),
+                event_loop: config_name(get_event_loop_mode()),
+                frame_drop_boost_freq: strategy.frame_drop_boost_freq,
+                frame_drop_boost_ms: strategy.frame_drop_boost_ms,
+                max_margin: strategy.max_margin,
+                sustained_opp_set: strategy.sustained_opp_set.clone(),
+                sustained_window_ms: strategy.sustained_window_ms,
+                downscale_delay_ms: strategy.downscale_delay_ms,
+                idle_grace_ms: strategy.idle_grace_ms,
+                fixed_freq: strategy.fixed_freq,
+                fixed_freq_hold_idle: strategy.fixed_freq_hold_idle,
+                ddr_load_scaling: gpu.ddr_manager().load_scaling,
+                ddr_high_load: gpu.ddr_manager().high_load,
+                ddr_low_load: gpu.ddr_manager().low_load,
+                dcs_load_scaling: gpu.dcs_load_scaling,
+                dcs_high_load: gpu.dcs_high_load,
+                dcs_low_load: gpu.dcs_low_load,
+                allowed_freqs: frequency.allowed_freqs.clone(),
+                blocked_freqs: frequency.blocked_freqs.clone(),
+                volt_offset: frequency.volt_offset.global(),
+                min_volt: frequency.volt_offset.min_volt(),
+                max_undervolt: frequency.volt_offset.max_undervolt(),
+                volt_tolerance: frequency.volt_tolerance,
+                reject_unknown_volt: frequency.reject_unknown_volt,
+                max_step_up: strategy.max_step_up,
+                max_step_down: strategy.max_step_down,
+                predictive_steps: strategy.predictive_steps,
+                predictive_samples: strategy.predictive_samples,
+                log_effective_config: should_log_effective_config(),
+                min_effective_load: strategy.min_effective_load,
+                load_window_ms: strategy.load_window_ms,
+                time_weighted_load: strategy.time_weighted_load,
+                foreground_poll_ms: strategy.foreground_poll_ms,
+                foreground_read_timeout_ms: strategy.foreground_read_timeout_ms,
+                decision_trace: strategy.decision_trace,
+                verify_high_opp_index: frequency.verify_high_opp_index,
+                load_stats_window_ms: strategy.load_stats_window_ms,
+                precise_load_node: config_name(get_precise_load_node()),
+                load_fusion: config_name(get_load_fusion()),
+                load_source_order: get_load_source_order(),
+                disabled_load_sources: get_disabled_load_sources(),
+                read_cache_ttl_ms: get_read_cache_ttl(),
+                frame_budget_ms: strategy.frame_budget_ms,
+                on_freq_mismatch: config_name(frequency.on_freq_mismatch),
+                sustained_floor_rise_secs: strategy.sustained_floor_rise_secs,
+                sustained_floor_decay_secs: strategy.sustained_floor_decay_secs,
+                status_file: get_status_file(),
+                telemetry_file,
+                telemetry_stride,
+                heartbeat_file,
+                heartbeat_interval_ms,
+                thermal_zones: strategy.thermal_zones.clone(),
+                thermal_cap_temp: strategy.thermal_cap_temp,
+                thermal_cap_freq: strategy.thermal_cap_freq,
+                thermal_warn_temp: strategy.thermal_warn_temp,
+                thermal_critical_temp: strategy.thermal_critical_temp,
+                screen_state_node: strategy.screen_state_node.clone(),
+                battery_saver_threshold: strategy.battery_saver_threshold,
+                battery_saver_max_freq: strategy.battery_saver_max_freq,
+                config_apply_delay_ms: strategy.config_apply_delay_ms,
+                log_format: config_name(get_log_format()),
+                max_log_lines_per_sec: get_max_log_rate(),
+                log_max_size_kb,
+                log_keep_files,
+                unmatched_app_profile: strategy.unmatched_app_profile.clone(),
+                v2_snap_tiebreak: config_name(frequency.v2_snap_tiebreak),
+                opp_reset_value: frequency.opp_reset_value.config_name().to_string(),
+                load_ema_alpha: strategy.load_ema_alpha,
+                adaptive_sampling_window: strategy.adaptive_sampling_window,
+                adaptive_variance_threshold: strategy.adaptive_variance_threshold,
+            },
+            strategy: EffectiveStrategy {
+                very_high_load_threshold: strategy.very_high_load_threshold,
+                margin: strategy.margin,
+                down_threshold: strategy.down_threshold,
+                aggressive_down: strategy.aggressive_down,
+                sampling_interval: strategy.sampling_interval,
+                load_stability_threshold: strategy.load_stability_threshold,
+                adaptive_sampling: strategy.adaptive_sampling,
+            },
+            load_calibration: get_load_calibration()
+                .into_iter()
+                .map(|(name, factor)| (name.to_string(), factor))
+                .collect(),
+            thread_sched: get_thread_sched()
+                .into_iter()
+                .map(|(name, sched)| {
+                    (
+                        name.to_string(),
+                        EffectiveThreadSched {
+                            cpu_mask: sched.cpu_mask,
+                            nice: sched.nice,
+                        },
+                    )
+                })
+                .collect(),
+            curve_point: strategy
+                .load_curve
+                .points()
+                .iter()
+                .map(|&(load, freq)| EffectiveCurvePoint { load, freq })
+                .collect(),
+            volt_offset_freq: frequency
+                .volt_offset
+                .per_freq()
+                .iter()
+                .map(|(&freq, &offset)| EffectiveVoltOffsetFreq { freq, offset })
+                .collect(),
+            app_profile: get_app_profiles()
+                .into_iter()
+                .map(|profile| EffectiveAppProfile {
+                    package: profile.package,
+                    disable_dcs: profile.disable_dcs,
+                    disable_ddr_scaling: profile.disable_ddr_scaling,
+                    launch_boost: profile.launch_boost,
+                    launch_boost_ms: profile.launch_boost_ms,
+                    min_freq: profile.min_freq,
+                    max_freq: profile.max_freq,
+                })
+                .collect(),
+        }
+    }
+
+    /// 序列化为TOML
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+}
+
+// 启动时是否将生效配置输出到日志
+static LOG_EFFECTIVE_CONFIG: AtomicBool = AtomicBool::new(false);
+
+/// 设置启动时是否输出生效配置
+pub fn set_log_effective_config(enabled: bool) {
+    LOG_EFFECTIVE_CONFIG.store(enabled, Ordering::SeqCst);
+    debug!("Set log effective config: {enabled}");
+}
+
+/// 启动时是否输出生效配置
+pub fn should_log_effective_config() -> bool {
+    LOG_EFFECTIVE_CONFIG.load(Ordering::SeqCst)
+}
+
+// 全局生效配置的TOML文本，启动完成后发布，供控制套接字导出
+static EFFECTIVE_CONFIG: Lazy<Mutex<String>> = Lazy::new(|| Mutex::new(String::new()));
+
+/// 发布生效配置，返回TOML文本
+pub fn publish_effective_config(gpu: &GPU) -> Result<String> {
+    let toml = EffectiveConfig::capture(gpu).to_toml()?;
+    *EFFECTIVE_CONFIG.lock().unwrap() = toml.clone();
+    Ok(toml)
+}
+
+/// 获取已发布的生效配置
+pub fn get_effective_config() -> String {
+    EFFECTIVE_CONFIG.lock().unwrap().clone()
+}
@@ -0,0 +1,24 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use log::debug;
+use once_cell::sync::Lazy;
+
+// 最近一次上报的GPU帧时间（毫秒）及上报时间
+static LATEST_FRAME_TIME: Lazy<Mutex<Option<(f64, Instant)>>> = Lazy::new(|| Mutex::new(None));
+
+/// 上报一次GPU帧时间（毫秒）
+pub fn report_frame_time(frame_ms: f64) {
+    *LATEST_FRAME_TIME.lock().unwrap() = Some((frame_ms, Instant::now()));
+    debug!("Frame time reported: {frame_ms:.2}ms");
+}
+
+/// 获取最近的帧时间，超过max_age未上报时返回None
+pub fn latest_frame_time(max_age: Duration) -> Option<f64> {
+    let latest = *LATEST_FRAME_TIME.lock().unwrap();
+    latest
+        .filter(|(_, reported)| reported.elapsed() <= max_age)
+        .map(|(frame_ms, _)| frame_ms)
+}
@@ -0,0 +1,107 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use log::debug;
+use once_cell::sync::Lazy;
+
+/// 频率限制来源 - 各个监控线程通过不同的来源设置上下限，互不覆盖
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LimitSource {
+    /// 后台应用频率上限
+    Background,
+    /// 触摸升频下限
+    TouchBoost,
+    /// 掉帧升频下限
+    FrameDrop,
+    /// 应用启动升频下限
+    LaunchBoost,
+    /// 外部工具请求的短时升频下限（如界面切换）
+    FloorBoost,
+    /// 温度过高时的频率上限
+    Thermal,
+    /// 前台应用配置的频率范围
+    AppProfile,
+    /// 低电量时的频率上限
+    Battery,
+    /// 外部工具设置的频率上限
+    Control,
+    /// 熄屏时的最低频率上限
+    ScreenOff,
+}
+
+#[derive(Default)]
+struct FreqLimits {
+    max_caps: HashMap<LimitSource, i64>,
+    min_floors: HashMap<LimitSource, i64>,
+    // 临时下限，到期后自动失效
+    temporary_floors: HashMap<LimitSource, (i64, Instant)>,
+}
+
+// 全局频率限制表，由监控线程写入，调频循环读取
+static FREQ_LIMITS: Lazy<Mutex<FreqLimits>> = Lazy::new(|| Mutex::new(FreqLimits::default()));
+
+/// 设置或清除某个来源的频率上限
+pub fn set_max_cap(source: LimitSource, cap: Option<i64>) {
+    let mut limits = FREQ_LIMITS.lock().unwrap();
+    let changed = match cap {
+        Some(freq) => limits.max_caps.insert(source, freq) != Some(freq),
+        None => limits.max_caps.remove(&source).is_some(),
+    };
+    if changed {
+        debug!("Max frequency cap from {source:?}: {cap:?}");
+    }
+}
+
+/// 设置或清除某个来源的频率下限
+pub fn set_min_floor(source: LimitSource, floor: Option<i64>) {
+    let mut limits = FREQ_LIMITS.lock().unwrap();
+    let changed = match floor {
+        Some(freq) => limits.min_floors.insert(source, freq) != Some(freq),
+        None => limits.min_floors.remove(&source).is_some(),
+    };
+    if changed {
+        debug!("Min frequency floor from {source:?}: {floor:?}");
+    }
+}
+
+/// 设置某个来源的临时频率下限，重复设置会刷新到期时间
+pub fn set_temporary_floor(source: LimitSource, floor: i64, duration: Duration) {
+    let mut limits = FREQ_LIMITS.lock().unwrap();
+    let deadline = Instant::now() + duration;
+    let previous = limits.temporary_floors.insert(source, (floor, deadline));
+    if previous.is_none_or(|(freq, _)| freq != floor) {
+        debug!("Temporary frequency floor from {source:?}: {floor}KHz for {duration:?}");
+    }
+}
+
+/// 提前清除某个来源的临时频率下限
+pub fn clear_temporary_floor(source: LimitSource) {
+    let mut limits = FREQ_LIMITS.lock().unwrap();
+    if limits.temporary_floors.remove(&source).is_some() {
+        debug!("Temporary frequency floor from {source:?} cleared");
+    }
+}
+
+/// 获取当前生效的频率范围 (下限, 上限)，多个来源时取最严格的值
+pub fn get_effective_limits() -> (Option<i64>, Option<i64>) {
+    let mut limits = FREQ_LIMITS.lock().unwrap();
+    let now = Instant::now();
+    limits.temporary_floors.retain(|source, (_, deadline)| {
+        let active = *deadline > now;
+        if !active {
+            debug!("Temporary frequency floor from {source:?} expired");
+        }
+        active
+    });
+    let min_floor = limits
+        .min_floors
+        .values()
+        .copied()
+        .chain(limits.temporary_floors.values().map(|(freq, _)| *freq))
+        .max();
+    let max_cap = limits.max_caps.values().copied().min();
+    (min_floor, max_cap)
+}
@@ -1,8 +1,78 @@
 use anyhow::Result;
-use log::{debug, info, warn};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use log::{debug, info, log_enabled, trace, warn, Level};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use crate::{datasource::load_monitor::get_gpu_load, model::gpu::GPU, utils::constants::strategy};
+use crate::{
+    datasource::{
+        config_parser::{load_config, read_freq_table_source, FreqTableSource},
+        file_path::{resolve_path, PathKey},
+        freq_table::{gpufreq_table_init, validate_freq_table},
+        freq_table_parser::freq_table_read,
+        load_monitor::{
+            active_load_source, get_gpu_load, reprobe_load_sources, reset_precise_load_state,
+        },
+        node_monitor::apply_game_mode,
+    },
+    model::{
+        app_profile::{
+            apply_app_profile, get_active_profile, is_dcs_suppressed, is_ddr_scaling_suppressed,
+        },
+        decision_trace::{DecisionTrace, TraceStage},
+        effective_config::publish_effective_config,
+        frame_time::latest_frame_time,
+        freq_limits::{get_effective_limits, set_max_cap, LimitSource},
+        frequency_strategy::{GovernorType, UpscaleMode},
+        governor_mode::GovernorMode,
+        governor_state::{cooldown_remaining, is_enabled, set_enabled},
+        gpu::GPU,
+        heartbeat::{record_heartbeat, Heartbeat},
+        load_window::LoadWindow,
+        opp_table::publish_opp_table,
+        settings_queue::{drain_settings_changes, SettingsChange},
+        snapshot::{get_snapshot, publish_snapshot, GovernorSnapshot},
+        stats::{record_freq_change, record_residency},
+        telemetry::{record_telemetry, TelemetryRecord},
+    },
+    utils::{constants::strategy, shutdown::is_shutdown_requested},
+};
+
+// 禁用、冷却或负载未知状态下检查状态变化的间隔
+const DISABLED_POLL_MS: u64 = 500;
+
+/// 负载未知状态 - 所有负载源失效期间定期重新检测负载节点
+#[derive(Debug, Default)]
+struct LoadUnknownState {
+    /// 是否处于负载未知状态
+    active: bool,
+    /// 上次重新检测负载节点的时间（毫秒），进入负载未知状态时从此刻开始计时
+    last_reprobe: u64,
+}
+
+impl LoadUnknownState {
+    /// 记录一次负载未知的采样，距上次重新检测达到间隔时返回true
+    fn record(&mut self, current_time: u64) -> bool {
+        if !self.active {
+            self.active = true;
+            self.last_reprobe = current_time;
+            return false;
+        }
+        if current_time >= self.next_reprobe() {
+            self.last_reprobe = current_time;
+            return true;
+        }
+        false
+    }
+
+    /// 下一次重新检测负载节点的时间（毫秒）
+    fn next_reprobe(&self) -> u64 {
+        self.last_reprobe + strategy::LOAD_REPROBE_MS
+    }
+
+    /// 读到有效负载后退出负载未知状态，返回之前是否处于负载未知状态
+    fn recover(&mut self) -> bool {
+        std::mem::take(&mut self.active)
+    }
+}
 
 /// GPU频率调整引擎 - 负责执行智能调频算法
 pub struct FrequencyAdjustmentEngine;
@@ -18,21 +88,141 @@ impl FrequencyAdjustmentEngine {
             gpu.get_cur_freq()
         );
 
-        loop {
+        let mut last_sample = Instant::now();
+        // 上次采样的墙钟时间，单调时钟在挂起期间不计时，需要用墙钟检测挂起恢复
+        let mut last_wall_time = Self::get_current_time_ms();
+        let mut driver_default_written = false;
+        let mut in_cooldown = false;
+        // 负载未知（所有负载源失效）的状态
+        let mut load_unknown = LoadUnknownState::default();
+        // 负载统计窗口，只用于状态上报，不参与调频决策
+        let mut load_stats = LoadWindow::new(gpu.frequency_strategy.load_stats_window_ms, true);
+        while !is_shutdown_requested() {
+            // 按提交顺序应用其他线程提交的设置变更
+            Self::apply_settings_changes(gpu);
+
+            // 按配置的间隔写入心跳文件，禁用和冷却期间也写入
+            let now_ms = Self::get_current_time_ms();
+            record_heartbeat(now_ms, || {
+                Heartbeat::capture(gpu, now_ms, get_snapshot().load)
+            });
+
+            // 禁用时写入一次驱动默认值，然后空闲等待重新启用
+            if !is_enabled() {
+                if !driver_default_written {
+                    info!("Governor disabled, handing control back to driver DVFS");
+                    gpu.frequency_mut().write_driver_default()?;
+                    driver_default_written = true;
+                }
+                std::thread::sleep(Duration::from_millis(DISABLED_POLL_MS));
+                last_sample = Instant::now();
+                last_wall_time = now_ms;
+                continue;
+            }
+            if driver_default_written {
+                info!("Governor re-enabled");
+                driver_default_written = false;
+            }
+
+            // 冷却期间固定最低频率，优先于其他所有策略
+            if let Some(remaining) = cooldown_remaining() {
+                if !in_cooldown {
+                    info!("Cooldown active, pinning minimum frequency");
+                    in_cooldown = true;
+                }
+                Self::update_current_frequency(gpu)?;
+                Self::apply_cooldown(gpu)?;
+                std::thread::sleep(remaining.min(Duration::from_millis(DISABLED_POLL_MS)));
+                last_sample = Instant::now();
+                last_wall_time = now_ms;
+                continue;
+            }
+            if in_cooldown {
+                info!("Cooldown ended");
+                in_cooldown = false;
+            }
+
             let current_time = Self::get_current_time_ms();
 
+            // 挂起恢复后计数器和采样时间都已过期，丢弃累积的负载状态，下一次采样重新开始
+            let wall_gap = current_time.saturating_sub(last_wall_time);
+            last_wall_time = current_time;
+            if wall_gap > strategy::RESUME_GAP_MS {
+                info!("Resume detected ({wall_gap}ms since last sample), resetting load state");
+                Self::reset_load_state(gpu, &mut load_stats);
+            }
+
+            // 统计上一个周期的频率驻留时间
+            let now = Instant::now();
+            let elapsed = now - last_sample;
+            record_residency(gpu.get_cur_freq(), elapsed);
+            last_sample = now;
+
+            // 写入被限流推迟的频率
+            gpu.frequency_mut().flush_pending_write()?;
+
             // 更新当前GPU频率
             Self::update_current_frequency(gpu)?;
 
-            // 读取当前GPU负载
-            let load = get_gpu_load()?;
+            // 读取当前GPU负载，负载未知时固定间隔轮询（精确模式的采样不睡眠，避免空转）
+            let Some(load) = Self::sample_load(gpu, &mut load_unknown, current_time, get_gpu_load)?
+            else {
+                std::thread::sleep(Duration::from_millis(DISABLED_POLL_MS));
+                continue;
+            };
+            if load_unknown.recover() {
+                info!(
+                    "Load source {} available again, resuming frequency control",
+                    active_load_source().unwrap_or("unknown")
+                );
+                Self::reset_load_state(gpu, &mut load_stats);
+            }
 
-            // 处理负载
-            Self::process_load(gpu, load, current_time)?;
+            // 处理负载（决策使用指数平滑、窗口平均并经过最低负载限制后的值）
+            let strategy = gpu.frequency_strategy_mut();
+            strategy.update_adaptive_sampling(load);
+            strategy.load_trend.push(load);
+            let smoothed_load = strategy.load_smoother.push(load);
+            if smoothed_load != load {
+                debug!("Load {load}% smoothed to {smoothed_load}%");
+            }
+            let averaged_load = strategy
+                .load_window
+                .push(smoothed_load, elapsed.as_millis() as u64);
+            let effective_load = Self::apply_min_effective_load(gpu, averaged_load);
+            let mut decision =
+                DecisionTrace::new(load, averaged_load, effective_load, gpu.get_cur_freq());
+            gpu.update_dcs_for_load(decision.effective_load);
+            Self::process_load(gpu, &mut decision, current_time)?;
+            Self::update_ddr_for_load(gpu, decision.effective_load);
+
+            // 按配置的间隔写入遥测记录，未启用时直接跳过
+            record_telemetry(|| TelemetryRecord::capture(gpu, current_time, load, smoothed_load));
+
+            // 写入未生效且配置为重新检测时，重新检测驱动并校验频率表
+            if gpu.frequency_mut().take_reinit_request() {
+                Self::reinitialize_driver(gpu);
+            }
+
+            // 决策追踪默认关闭，开启后在trace级别输出
+            if gpu.frequency_strategy.decision_trace && log_enabled!(Level::Trace) {
+                trace!("Decision: {decision}");
+            }
+
+            // 发布状态快照
+            load_stats.push(load, elapsed.as_millis() as u64);
+            publish_snapshot(GovernorSnapshot::capture(gpu, load, &load_stats));
 
             // 应用采样睡眠
             Self::apply_sampling_sleep(gpu);
         }
+
+        info!("Shutdown requested, leaving adjustment loop");
+        // 退出前交还驱动默认OPP，避免GPU停留在最后写入的频率
+        if let Err(e) = gpu.frequency_mut().write_driver_default() {
+            warn!("Failed to restore driver default OPP on shutdown: {e}");
+        }
+        Ok(())
     }
 
     /// 获取当前时间戳（毫秒）
@@ -43,16 +233,55 @@ impl FrequencyAdjustmentEngine {
             .as_millis() as u64
     }
 
+    /// 将负载提升到配置的最低负载，保证基础响应速度
+    fn apply_min_effective_load(gpu: &GPU, load: i32) -> i32 {
+        let min_load = gpu.frequency_strategy.min_effective_load;
+        if load < min_load {
+            debug!("Load {load}% raised to min effective load {min_load}%");
+            return min_load;
+        }
+        load
+    }
+
+    /// 冷却时写入最低频率
+    fn apply_cooldown(gpu: &mut GPU) -> Result<()> {
+        let min_freq = gpu.get_freq_by_index(0);
+        if gpu.get_cur_freq() != min_freq {
+            Self::apply_frequency_change(gpu, min_freq, 0, Self::get_current_time_ms())?;
+        }
+        Ok(())
+    }
+
     /// 处理负载数据
-    fn process_load(gpu: &mut GPU, load: i32, current_time: u64) -> Result<()> {
-        // 检查空闲状态
-        if load <= gpu.idle_manager.idle_threshold {
+    fn process_load(gpu: &mut GPU, decision: &mut DecisionTrace, current_time: u64) -> Result<()> {
+        // 检查空闲状态，存在频率下限（如触摸升频）时仍需调整频率
+        let load = decision.effective_load;
+        let strategy = &gpu.frequency_strategy;
+        let fixed = strategy.fixed_freq > 0;
+        let hold_idle = fixed && strategy.fixed_freq_hold_idle;
+        if !hold_idle
+            && load <= gpu.idle_manager.idle_threshold
+            && get_effective_limits().0.is_none()
+        {
+            decision.idle = true;
+            if gpu.frequency_strategy.pending_reload_since.is_some() {
+                debug!("GPU idle, applying reloaded config");
+                Self::apply_pending_reload(gpu);
+            }
+            Self::apply_idle_grace(gpu, decision, current_time)?;
             Self::handle_idle_state(gpu);
             return Ok(());
         }
+        // 负载回升，取消等待中的空闲降频
+        gpu.frequency_strategy_mut().idle_since = None;
+
+        // 固定频率模式不评估负载
+        if fixed {
+            return Self::hold_fixed_freq(gpu, decision, current_time);
+        }
 
         // 执行简单的频率调整逻辑
-        Self::execute_frequency_adjustment(gpu, load, current_time)
+        Self::execute_frequency_adjustment(gpu, decision, current_time)
     }
 
     /// 更新当前GPU频率
@@ -76,6 +305,288 @@ impl FrequencyAdjustmentEngine {
         Ok(())
     }
 
+    /// 依次应用设置变更，保证并发提交的变更结果与提交顺序一致
+    fn apply_settings_changes(gpu: &mut GPU) {
+        for change in drain_settings_changes() {
+            debug!("Applying settings change: {change:?}");
+            match change {
+                SettingsChange::ReloadFreqTable => Self::request_freq_table_reload(gpu),
+                SettingsChange::GameMode(is_gaming) => apply_game_mode(gpu, is_gaming),
+                SettingsChange::AppProfile(package) => {
+                    Self::apply_foreground_profile(gpu, &package)
+                }
+                SettingsChange::Enabled(enabled) => set_enabled(enabled),
+                SettingsChange::ReloadConfig => Self::reload_config(gpu),
+                SettingsChange::SetMode(mode) => gpu.apply_mode(mode),
+                SettingsChange::LowBattery(low_battery) => {
+                    Self::apply_low_battery(gpu, low_battery)
+                }
+                SettingsChange::Precise(precise) => gpu.set_precise_runtime(precise),
+            }
+        }
+
+        // 等待时间已到时不再等待空闲
+        if let Some(since) = gpu.frequency_strategy.pending_reload_since {
+            let elapsed = Self::get_current_time_ms().saturating_sub(since);
+            if elapsed >= gpu.frequency_strategy.config_apply_delay_ms {
+                debug!("Config apply delay elapsed, applying reloaded config");
+                Self::apply_pending_reload(gpu);
+            }
+        }
+    }
+
+    /// 清除负载计数器、平均窗口和持续负载计时，使下一次采样不受之前状态影响
+    fn reset_load_state(gpu: &mut GPU, load_stats: &mut LoadWindow) {
+        reset_precise_load_state();
+        let strategy = gpu.frequency_strategy_mut();
+        strategy.load_window =
+            LoadWindow::new(strategy.load_window_ms, strategy.time_weighted_load);
+        strategy.load_smoother.reset();
+        strategy.adaptive_sampler.reset();
+        strategy.load_trend.reset();
+        strategy.high_load_since = None;
+        strategy.low_load_since = None;
+        strategy.idle_since = None;
+        strategy.sustained_floor_idx = 0;
+        strategy.sustained_floor_rising = false;
+        *load_stats = LoadWindow::new(strategy.load_stats_window_ms, true);
+    }
+
+    /// 应用前台应用的配置，没有匹配的应用配置时切换到unmatched_app_profile指定的模式
+    fn apply_foreground_profile(gpu: &mut GPU, package: &str) {
+        let matched = apply_app_profile(package);
+        let strategy = gpu.frequency_strategy_mut();
+        // 低电量省电优先于前台应用的模式
+        if strategy.low_battery {
+            return;
+        }
+        let Some(unmatched_params) = strategy.unmatched_mode_params.clone() else {
+            return;
+        };
+        let params = if matched {
+            strategy.mode_params.clone()
+        } else {
+            unmatched_params
+        };
+        debug!(
+            "Foreground app {package} {}, using {} mode params",
+            if matched { "matched" } else { "unmatched" },
+            if matched {
+                "configured"
+            } else {
+                strategy.unmatched_app_profile.as_str()
+            }
+        );
+        strategy.apply_mode_params(&params);
+    }
+
+    /// 低电量时切换到省电模式并按配置限制最高频率，恢复时回到配置的模式
+    fn apply_low_battery(gpu: &mut GPU, low_battery: bool) {
+        let strategy = gpu.frequency_strategy_mut();
+        if strategy.low_battery == low_battery {
+            return;
+        }
+        strategy.low_battery = low_battery;
+
+        if low_battery {
+            info!("Low battery, switching to powersave mode");
+        } else {
+            info!("Battery saver off, restoring configured mode");
+        }
+        Self::restore_mode_params(gpu);
+    }
+
+    /// 按当前的电量和前台应用状态重新应用模式参数和低电量频率上限
+    fn restore_mode_params(gpu: &mut GPU) {
+        let strategy = gpu.frequency_strategy_mut();
+        let params = if strategy.low_battery {
+            GovernorMode::Powersave.params()
+        } else {
+            match &strategy.unmatched_mode_params {
+                Some(params) if get_active_profile().is_none() => params.clone(),
+                _ => strategy.mode_params.clone(),
+            }
+        };
+        strategy.apply_mode_params(&params);
+
+        let max_freq = strategy.battery_saver_max_freq;
+        set_max_cap(
+            LimitSource::Battery,
+            (strategy.low_battery && max_freq > 0).then_some(max_freq),
+        );
+    }
+
+    /// 重新加载TOML配置，解析失败时保留当前配置
+    fn reload_config(gpu: &mut GPU) {
+        if let Err(e) = load_config(gpu) {
+            warn!("Rejected TOML config change: {e}, keeping running config");
+            return;
+        }
+        info!("TOML config reloaded");
+
+        // 与启动时的顺序一致：先应用模式参数，再应用游戏模式参数
+        Self::restore_mode_params(gpu);
+        let is_gaming = gpu.is_gaming_mode();
+        apply_game_mode(gpu, is_gaming);
+
+        if let Err(e) = publish_effective_config(gpu) {
+            warn!("Failed to serialize effective config: {e}");
+        }
+        publish_opp_table(gpu);
+    }
+
+    /// 配置变化后立即重新读取，或推迟到GPU空闲或等待时间结束时再读取，避免负载中途切换策略
+    fn request_freq_table_reload(gpu: &mut GPU) {
+        if gpu.frequency_strategy.config_apply_delay_ms == 0 {
+            Self::reload_freq_table(gpu);
+            return;
+        }
+        // 等待期间再次变化时沿用第一次变化的时间，保证最长等待时间不变
+        let strategy = gpu.frequency_strategy_mut();
+        if strategy.pending_reload_since.is_none() {
+            debug!(
+                "Deferring config reload for up to {}ms",
+                strategy.config_apply_delay_ms
+            );
+            strategy.pending_reload_since = Some(Self::get_current_time_ms());
+        }
+    }
+
+    /// 应用被推迟的配置重新加载
+    fn apply_pending_reload(gpu: &mut GPU) {
+        if gpu
+            .frequency_strategy_mut()
+            .pending_reload_since
+            .take()
+            .is_some()
+        {
+            Self::reload_freq_table(gpu);
+        }
+    }
+
+    /// 重新读取频率表配置文件，频率表来自devfreq时不覆盖
+    fn reload_freq_table(gpu: &mut GPU) {
+        if read_freq_table_source() != FreqTableSource::File {
+            debug!("Frequency table comes from devfreq, ignoring config file change");
+            return;
+        }
+        if let Err(e) = freq_table_read(resolve_path(PathKey::FreqTable), gpu) {
+            warn!("Failed to reload frequency table: {e}");
+            return;
+        }
+        publish_opp_table(gpu);
+    }
+
+    /// 重新检测驱动类型和v2频率表，并校验当前频率表
+    fn reinitialize_driver(gpu: &mut GPU) {
+        if let Err(e) = gpufreq_table_init(gpu) {
+            warn!("Failed to re-detect GPU driver: {e}");
+            return;
+        }
+        let unsupported = validate_freq_table(gpu);
+        publish_opp_table(gpu);
+        info!(
+            "GPU driver re-detected ({}), {unsupported} unsupported table entries",
+            if gpu.is_gpuv2() { "v2" } else { "v1" }
+        );
+    }
+
+    /// 固定频率模式：保持配置的频率（吸附到可用频率），各来源的频率上下限仍然生效
+    fn hold_fixed_freq(
+        gpu: &mut GPU,
+        decision: &mut DecisionTrace,
+        current_time: u64,
+    ) -> Result<()> {
+        let fixed_freq = gpu
+            .frequency()
+            .read_freq_nearest(gpu.frequency_strategy.fixed_freq);
+        decision.governor_target = fixed_freq;
+
+        let target_freq = Self::apply_freq_limits(gpu, decision, fixed_freq);
+        let target_idx = gpu.frequency().read_freq_index(target_freq);
+        decision.final_freq = target_freq;
+        decision.final_idx = target_idx;
+
+        if target_freq != gpu.get_cur_freq() {
+            debug!("Holding fixed frequency {target_freq}KHz");
+            Self::apply_frequency_change(gpu, target_freq, target_idx, current_time)?;
+        }
+        Ok(())
+    }
+
+    /// 读取负载，负载未知时保持范围中点附近的频率并按间隔重新检测负载节点，返回None
+    fn sample_load(
+        gpu: &mut GPU,
+        load_unknown: &mut LoadUnknownState,
+        current_time: u64,
+        read_load: impl FnOnce() -> Result<i32>,
+    ) -> Result<Option<i32>> {
+        let load = read_load()?;
+        if load >= 0 {
+            return Ok(Some(load));
+        }
+
+        if !load_unknown.active {
+            warn!("No load source available, holding mid-range frequency until one returns");
+        }
+        if load_unknown.record(current_time) {
+            reprobe_load_sources();
+        }
+        Self::hold_mid_range_freq(gpu, current_time)?;
+        Ok(None)
+    }
+
+    /// 负载未知时保持最接近频率范围中点的频率，仍受频率上下限约束
+    fn hold_mid_range_freq(gpu: &mut GPU, current_time: u64) -> Result<()> {
+        let mut decision = DecisionTrace::new(-1, -1, -1, gpu.get_cur_freq());
+        let target_freq = Self::unknown_load_target(gpu, &mut decision);
+        if target_freq != gpu.get_cur_freq() {
+            let target_idx = gpu.frequency().read_freq_index(target_freq);
+            debug!("Load unknown, holding {target_freq}KHz");
+            Self::apply_frequency_change(gpu, target_freq, target_idx, current_time)?;
+        }
+        Ok(())
+    }
+
+    /// 负载未知时的目标频率：范围中点附近的频率，经过频率上下限约束
+    fn unknown_load_target(gpu: &GPU, decision: &mut DecisionTrace) -> i64 {
+        let mid_range_freq = gpu.get_mid_range_freq();
+        decision.governor_target = mid_range_freq;
+        Self::apply_freq_limits(gpu, decision, mid_range_freq)
+    }
+
+    /// 持续空闲超过宽限时间后降到最低频率，按墙钟计时，不受采样间隔变化影响
+    fn apply_idle_grace(
+        gpu: &mut GPU,
+        decision: &mut DecisionTrace,
+        current_time: u64,
+    ) -> Result<()> {
+        let strategy = gpu.frequency_strategy_mut();
+        if strategy.idle_grace_ms == 0 {
+            return Ok(());
+        }
+
+        let since = *strategy.idle_since.get_or_insert(current_time);
+        let elapsed = current_time.saturating_sub(since);
+        if elapsed < strategy.idle_grace_ms {
+            debug!(
+                "Idle downclock pending ({elapsed}/{}ms)",
+                strategy.idle_grace_ms
+            );
+            return Ok(());
+        }
+
+        let min_freq = gpu.get_min_freq();
+        if gpu.get_cur_freq() != min_freq {
+            debug!("Idle for {elapsed}ms, dropping to {min_freq}KHz");
+            let min_idx = gpu.frequency().read_freq_index(min_freq);
+            Self::apply_frequency_change(gpu, min_freq, min_idx, current_time)?;
+            decision.final_freq = min_freq;
+            decision.final_idx = min_idx;
+        }
+        Ok(())
+    }
+
     /// 处理空闲状态
     fn handle_idle_state(gpu: &GPU) {
         let idle_sleep_time = if gpu.is_precise() { 200 } else { 160 };
@@ -84,14 +595,35 @@ impl FrequencyAdjustmentEngine {
     }
 
     /// 执行频率调整逻辑
-    fn execute_frequency_adjustment(gpu: &mut GPU, load: i32, current_time: u64) -> Result<()> {
+    fn execute_frequency_adjustment(
+        gpu: &mut GPU,
+        decision: &mut DecisionTrace,
+        current_time: u64,
+    ) -> Result<()> {
+        let load = decision.effective_load;
         debug!("Executing frequency adjustment for load: {load}%");
 
         let current_freq = gpu.get_cur_freq();
         let current_idx = gpu.frequency().cur_freq_idx;
         let max_idx = (gpu.get_config_list().len() - 1) as i64;
 
-        let (target_freq, target_idx) = if load >= strategy::ULTRA_SIMPLE_THRESHOLD {
+        let frame_time_target = if gpu.frequency_strategy.governor == GovernorType::FrameTime {
+            Self::frame_time_target(gpu, current_idx, max_idx)
+        } else {
+            None
+        };
+
+        let (target_freq, target_idx) = if let Some(target) = frame_time_target {
+            decision.governor_target = target.0;
+            target
+        } else if gpu.frequency_strategy.governor == GovernorType::Curve {
+            // 曲线调速器：插值目标频率后吸附到最接近的可用频率
+            let curve_freq = gpu.frequency_strategy.load_curve.interpolate(load);
+            let freq = gpu.frequency().read_freq_nearest(curve_freq);
+            debug!("Curve governor: load {load}% -> {curve_freq}KHz, snapped to {freq}KHz");
+            decision.governor_target = freq;
+            (freq, gpu.frequency().read_freq_index(freq))
+        } else if load >= strategy::ULTRA_SIMPLE_THRESHOLD {
             // 负载达到90%或以上，升频一级
             debug!(
                 "Load {}% >= {}%, upgrading frequency",
@@ -99,7 +631,19 @@ impl FrequencyAdjustmentEngine {
                 strategy::ULTRA_SIMPLE_THRESHOLD
             );
             let next_idx = (current_idx + 1).min(max_idx);
-            (gpu.get_freq_by_index(next_idx), next_idx)
+            let target = if gpu.frequency_strategy.upscale_mode == UpscaleMode::Demand {
+                Self::demand_upscale_target(gpu, decision, current_freq, next_idx)
+            } else {
+                (gpu.get_freq_by_index(next_idx), next_idx)
+            };
+            decision.governor_target = target.0;
+            match Self::sustained_rotation_target(gpu, current_time) {
+                Some(rotation) => {
+                    decision.modify(TraceStage::SustainedRotation, target.0, rotation.0);
+                    rotation
+                }
+                None => target,
+            }
         } else {
             // 负载低于90%，降频一级
             debug!(
@@ -107,10 +651,75 @@ impl FrequencyAdjustmentEngine {
                 load,
                 strategy::ULTRA_SIMPLE_THRESHOLD
             );
+            gpu.frequency_strategy_mut().high_load_since = None;
             let next_idx = (current_idx - 1).max(0);
-            (gpu.get_freq_by_index(next_idx), next_idx)
+            decision.governor_target = gpu.get_freq_by_index(next_idx);
+            (decision.governor_target, next_idx)
         };
 
+        // 负载持续上升时在调速器目标之上额外升频，不超过最高频率
+        let (target_freq, target_idx) = {
+            let strategy = &gpu.frequency_strategy;
+            if strategy.predictive_steps > 0 && strategy.load_trend.is_rising() {
+                let predicted_idx = (target_idx + strategy.predictive_steps as i64).min(max_idx);
+                let predicted_freq = gpu.get_freq_by_index(predicted_idx);
+                debug!("Load rising, predicting {predicted_freq}KHz");
+                decision.modify(TraceStage::Predictive, target_freq, predicted_freq);
+                (predicted_freq, predicted_idx)
+            } else {
+                (target_freq, target_idx)
+            }
+        };
+
+        // 降频需求持续足够久才执行，升频不受影响
+        let (target_freq, target_idx) =
+            if Self::should_delay_downscale(gpu, target_freq, current_freq, current_time) {
+                decision.modify(TraceStage::DownscaleDelay, target_freq, current_freq);
+                (current_freq, current_idx)
+            } else {
+                (target_freq, target_idx)
+            };
+
+        // 持续高负载抬高的动态下限
+        let (target_freq, target_idx) =
+            match Self::sustained_floor_index(gpu, load, current_idx, current_time) {
+                Some(floor_idx) if target_idx < floor_idx => {
+                    let floor_freq = gpu.get_freq_by_index(floor_idx);
+                    decision.modify(TraceStage::SustainedFloor, target_freq, floor_freq);
+                    (floor_freq, floor_idx)
+                }
+                _ => (target_freq, target_idx),
+            };
+
+        // 限制单次调频跨越的档数，之后的上下限不受步长限制
+        let (target_freq, target_idx) = {
+            let strategy = &gpu.frequency_strategy;
+            let limited_idx = Self::step_limited_index(
+                current_idx,
+                target_idx,
+                strategy.max_step_up,
+                strategy.max_step_down,
+            );
+            if limited_idx != target_idx {
+                let limited_freq = gpu.get_freq_by_index(limited_idx);
+                decision.modify(TraceStage::StepLimit, target_freq, limited_freq);
+                (limited_freq, limited_idx)
+            } else {
+                (target_freq, target_idx)
+            }
+        };
+
+        // 应用各来源的频率上下限
+        let limited_freq = Self::apply_freq_limits(gpu, decision, target_freq);
+        let (target_freq, target_idx) = if limited_freq != target_freq {
+            (limited_freq, gpu.frequency().read_freq_index(limited_freq))
+        } else {
+            (target_freq, target_idx)
+        };
+
+        decision.final_freq = target_freq;
+        decision.final_idx = target_idx;
+
         // 应用频率变化
         if target_freq != current_freq {
             Self::apply_frequency_change(gpu, target_freq, target_idx, current_time)?;
@@ -119,6 +728,171 @@ impl FrequencyAdjustmentEngine {
         Ok(())
     }
 
+    /// 将目标索引限制在当前索引上下各自允许的档数内，0表示不限制
+    fn step_limited_index(current_idx: i64, target_idx: i64, max_up: u32, max_down: u32) -> i64 {
+        if max_up > 0 && target_idx > current_idx + max_up as i64 {
+            current_idx + max_up as i64
+        } else if max_down > 0 && target_idx < current_idx - max_down as i64 {
+            current_idx - max_down as i64
+        } else {
+            target_idx
+        }
+    }
+
+    /// 帧时间调速器：帧时间接近预算时升频一级，余量充足时降频一级，否则保持当前频率
+    /// 没有最近的帧时间时返回None，由负载阈值逻辑决定
+    fn frame_time_target(gpu: &GPU, current_idx: i64, max_idx: i64) -> Option<(i64, i64)> {
+        let Some(frame_ms) =
+            latest_frame_time(Duration::from_millis(strategy::FRAME_TIME_MAX_AGE_MS))
+        else {
+            debug!("No recent frame time, falling back to load threshold");
+            return None;
+        };
+
+        let budget = gpu.frequency_strategy.frame_budget_ms;
+        let target_idx = if frame_ms >= budget * strategy::FRAME_TIME_UP_RATIO {
+            (current_idx + 1).min(max_idx)
+        } else if frame_ms <= budget * strategy::FRAME_TIME_DOWN_RATIO {
+            (current_idx - 1).max(0)
+        } else {
+            current_idx
+        };
+        debug!("Frame time governor: {frame_ms:.2}/{budget}ms -> idx {target_idx}");
+        Some((gpu.get_freq_by_index(target_idx), target_idx))
+    }
+
+    /// 持续高负载时每隔一段时间把下限抬高一级（不超过当前档位），负载降低后逐级回落
+    fn sustained_floor_index(
+        gpu: &mut GPU,
+        load: i32,
+        current_idx: i64,
+        current_time: u64,
+    ) -> Option<i64> {
+        let strategy = gpu.frequency_strategy_mut();
+        if strategy.sustained_floor_rise_secs == 0 {
+            return None;
+        }
+
+        let high_load = load >= strategy::ULTRA_SIMPLE_THRESHOLD;
+        if high_load != strategy.sustained_floor_rising {
+            strategy.sustained_floor_rising = high_load;
+            strategy.sustained_floor_since = current_time;
+        }
+
+        let step_secs = if high_load {
+            strategy.sustained_floor_rise_secs
+        } else {
+            strategy.sustained_floor_decay_secs
+        };
+        let elapsed = current_time.saturating_sub(strategy.sustained_floor_since);
+        if !high_load && step_secs == 0 {
+            strategy.sustained_floor_idx = 0;
+        } else if elapsed >= step_secs * 1000 {
+            strategy.sustained_floor_since = current_time;
+            let floor_idx = if high_load {
+                (strategy.sustained_floor_idx + 1).min(current_idx)
+            } else {
+                (strategy.sustained_floor_idx - 1).max(0)
+            };
+            if floor_idx != strategy.sustained_floor_idx {
+                debug!("Sustained floor moved to idx {floor_idx}");
+                strategy.sustained_floor_idx = floor_idx;
+            }
+        }
+
+        (strategy.sustained_floor_idx > 0).then_some(strategy.sustained_floor_idx)
+    }
+
+    /// 检查降频是否仍处于延迟期内
+    fn should_delay_downscale(
+        gpu: &mut GPU,
+        target_freq: i64,
+        current_freq: i64,
+        current_time: u64,
+    ) -> bool {
+        let strategy = gpu.frequency_strategy_mut();
+        if strategy.downscale_delay_ms == 0 || target_freq >= current_freq {
+            strategy.low_load_since = None;
+            return false;
+        }
+
+        let since = *strategy.low_load_since.get_or_insert(current_time);
+        let elapsed = current_time.saturating_sub(since);
+        if elapsed < strategy.downscale_delay_ms {
+            debug!(
+                "Downscale to {target_freq}KHz delayed ({elapsed}/{}ms)",
+                strategy.downscale_delay_ms
+            );
+            return true;
+        }
+
+        false
+    }
+
+    /// 持续高负载超过时间窗口后，每个窗口轮换到集合中的下一个频率以分散发热
+    fn sustained_rotation_target(gpu: &mut GPU, current_time: u64) -> Option<(i64, i64)> {
+        let strategy = gpu.frequency_strategy_mut();
+        if strategy.sustained_opp_set.is_empty() || strategy.sustained_window_ms == 0 {
+            return None;
+        }
+
+        let since = *strategy.high_load_since.get_or_insert(current_time);
+        let windows = current_time.saturating_sub(since) / strategy.sustained_window_ms;
+        if windows == 0 {
+            return None;
+        }
+
+        let set = &strategy.sustained_opp_set;
+        let freq = set[(windows - 1) as usize % set.len()];
+        let freq = gpu.frequency().read_freq_nearest(freq);
+        debug!("Sustained high load for {windows} windows, rotating to {freq}KHz");
+        Some((freq, gpu.frequency().read_freq_index(freq)))
+    }
+
+    /// 按负载需求计算升频目标：满足 负载×当前频率×(1+余量) 的最低频率，至少升一级
+    fn demand_upscale_target(
+        gpu: &GPU,
+        decision: &mut DecisionTrace,
+        current_freq: i64,
+        next_idx: i64,
+    ) -> (i64, i64) {
+        let margin = gpu.frequency_strategy.margin;
+        let load = decision.effective_load as i64;
+        let demand = current_freq * load / 100 * (100 + margin) / 100;
+        decision.demand_freq = Some(demand);
+        let demand_freq = gpu.read_freq_ge(demand);
+        let demand_idx = gpu.frequency().read_freq_index(demand_freq);
+        let target_idx = demand_idx.max(next_idx);
+        debug!("Demand upscale: {demand}KHz required, snapped to {demand_freq}KHz");
+        (gpu.get_freq_by_index(target_idx), target_idx)
+    }
+
+    /// 将目标频率限制在各来源设置的上下限内，上限优先于下限
+    fn apply_freq_limits(gpu: &GPU, decision: &mut DecisionTrace, target_freq: i64) -> i64 {
+        let (min_floor, max_cap) = get_effective_limits();
+        let mut freq = target_freq;
+
+        if let Some(floor) = min_floor {
+            let floor_freq = gpu.read_freq_ge(floor);
+            if freq < floor_freq {
+                debug!("Target {freq}KHz raised to floor {floor_freq}KHz");
+                decision.modify(TraceStage::Floor, freq, floor_freq);
+                freq = floor_freq;
+            }
+        }
+
+        if let Some(cap) = max_cap {
+            let cap_freq = gpu.read_freq_le(cap);
+            if freq > cap_freq {
+                debug!("Target {freq}KHz limited to cap {cap_freq}KHz");
+                decision.modify(TraceStage::Cap, freq, cap_freq);
+                freq = cap_freq;
+            }
+        }
+
+        freq
+    }
+
     /// 应用频率变化
     fn apply_frequency_change(
         gpu: &mut GPU,
@@ -132,12 +906,22 @@ impl FrequencyAdjustmentEngine {
         gpu.frequency_mut().cur_freq = new_freq;
         gpu.frequency_mut().cur_freq_idx = freq_index;
 
-        // 检查DCS条件
-        gpu.need_dcs = gpu.dcs_enable && gpu.is_gpuv2() && new_freq < gpu.get_min_freq();
+        // 检查DCS条件（前台应用配置可临时禁用DCS）
+        gpu.need_dcs = gpu.is_dcs_active()
+            && !is_dcs_suppressed()
+            && gpu.is_gpuv2()
+            && new_freq < gpu.get_min_freq();
 
         // 生成电压并写入
         gpu.frequency_mut().gen_cur_volt();
-        gpu.frequency().write_freq(gpu.need_dcs, gpu.is_idle())?;
+        debug!(
+            "Voltage for {new_freq}KHz: table={}, applied={}",
+            gpu.frequency().table_volt,
+            gpu.frequency().cur_volt
+        );
+        let (need_dcs, is_idle) = (gpu.need_dcs, gpu.is_idle());
+        gpu.frequency_mut().write_freq(need_dcs, is_idle)?;
+        record_freq_change();
 
         // 更新游戏模式下的DDR频率
         Self::update_ddr_if_gaming(gpu, new_freq)?;
@@ -152,7 +936,8 @@ impl FrequencyAdjustmentEngine {
 
     /// 在游戏模式下更新DDR频率
     fn update_ddr_if_gaming(gpu: &mut GPU, freq: i64) -> Result<()> {
-        if gpu.is_gaming_mode() {
+        // 前台应用配置禁止调整DDR频率时保持当前DDR状态
+        if gpu.is_gaming_mode() && !is_ddr_scaling_suppressed() {
             use crate::model::gpu::TabType;
             let ddr_opp = gpu.read_tab(TabType::FreqDram, freq);
             if ddr_opp > 0 || ddr_opp == crate::datasource::file_path::DDR_HIGHEST_FREQ {
@@ -164,15 +949,128 @@ impl FrequencyAdjustmentEngine {
         Ok(())
     }
 
+    /// 非游戏模式下按GPU负载调整DDR频率，DDR档位取当前GPU频率的映射
+    fn update_ddr_for_load(gpu: &mut GPU, load: i32) {
+        use crate::datasource::file_path::DDR_HIGHEST_FREQ;
+        use crate::model::gpu::TabType;
+
+        if !gpu.ddr_manager().load_scaling || gpu.is_gaming_mode() || is_ddr_scaling_suppressed() {
+            return;
+        }
+        // 映射中不是有效DDR_OPP值时使用最高内存频率
+        let ddr_opp = gpu.read_tab(TabType::FreqDram, gpu.get_cur_freq());
+        let ddr_opp = if (0..100).contains(&ddr_opp) {
+            ddr_opp
+        } else {
+            DDR_HIGHEST_FREQ
+        };
+        if let Err(e) = gpu.ddr_manager_mut().update_for_load(load, ddr_opp) {
+            warn!("Failed to update DDR frequency for load: {e}");
+        }
+    }
+
     /// 应用采样间隔睡眠
     fn apply_sampling_sleep(gpu: &GPU) {
         if gpu.is_precise() {
             return; // 精确模式不睡眠
         }
 
-        let sleep_time = gpu.frequency_strategy.get_sampling_interval();
+        let sleep_time = gpu.frequency_strategy.get_sleep_interval();
 
         debug!("Sleeping for {sleep_time}ms");
         std::thread::sleep(Duration::from_millis(sleep_time));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::{
+        file_path::{PROC_MALI_LOAD, PROC_MTK_LOAD},
+        load_monitor::{lock_load_state, read_gpu_load},
+        node_reader::MapReader,
+    };
+
+    #[test]
+    fn step_limit_caps_jump() {
+        // 从最低档直接跳到最高档时，每次最多前进2档
+        assert_eq!(
+            FrequencyAdjustmentEngine::step_limited_index(0, 10, 2, 2),
+            2
+        );
+        assert_eq!(
+            FrequencyAdjustmentEngine::step_limited_index(10, 0, 2, 2),
+            8
+        );
+        // 变化在限制内时直接采用目标索引
+        assert_eq!(FrequencyAdjustmentEngine::step_limited_index(3, 5, 2, 2), 5);
+        assert_eq!(FrequencyAdjustmentEngine::step_limited_index(3, 1, 2, 2), 1);
+        // 0表示不限制
+        assert_eq!(
+            FrequencyAdjustmentEngine::step_limited_index(0, 10, 0, 0),
+            10
+        );
+        assert_eq!(
+            FrequencyAdjustmentEngine::step_limited_index(10, 0, 2, 0),
+            0
+        );
+    }
+
+    #[test]
+    fn unknown_load_holds_mid_range_freq() {
+        let mut gpu = GPU::new();
+        gpu.frequency_mut()
+            .set_config_list(vec![100000, 200000, 300000, 400000, 1000000]);
+        let mut decision = DecisionTrace::new(-1, -1, -1, gpu.get_cur_freq());
+        // 范围中点550000最接近的档位是400000，而不是按索引取中间的300000
+        assert_eq!(
+            FrequencyAdjustmentEngine::unknown_load_target(&gpu, &mut decision),
+            400000
+        );
+        assert_eq!(decision.governor_target, 400000);
+    }
+
+    #[test]
+    fn unreadable_load_sources_hold_mid_range_freq() {
+        let _guard = lock_load_state();
+        let mut gpu = GPU::new();
+        gpu.frequency_mut()
+            .set_config_list(vec![100000, 200000, 300000, 400000, 1000000]);
+        let reader = MapReader::default();
+        reader.insert_failing(PROC_MTK_LOAD);
+        reader.insert_failing(PROC_MALI_LOAD);
+        let mut load_unknown = LoadUnknownState::default();
+
+        let load =
+            FrequencyAdjustmentEngine::sample_load(&mut gpu, &mut load_unknown, 5000, || {
+                read_gpu_load(&reader)
+            })
+            .unwrap();
+        assert_eq!(load, None);
+        assert_eq!(gpu.get_cur_freq(), 400000);
+        assert_eq!(gpu.frequency().cur_freq_idx, 3);
+        // 进入负载未知状态时从此刻开始计时，间隔到达后重新检测负载节点
+        assert!(load_unknown.active);
+        assert_eq!(
+            load_unknown.next_reprobe(),
+            5000 + strategy::LOAD_REPROBE_MS
+        );
+        assert!(!load_unknown.record(5000 + strategy::LOAD_REPROBE_MS - 1));
+        assert!(load_unknown.record(5000 + strategy::LOAD_REPROBE_MS));
+        assert_eq!(
+            load_unknown.next_reprobe(),
+            5000 + 2 * strategy::LOAD_REPROBE_MS
+        );
+
+        // 负载源恢复后返回负载并退出负载未知状态
+        reader.insert(PROC_MALI_LOAD, "gpu=35\n");
+        let load =
+            FrequencyAdjustmentEngine::sample_load(&mut gpu, &mut load_unknown, 6000, || {
+                read_gpu_load(&reader)
+            })
+            .unwrap();
+        assert_eq!(load, Some(35));
+        assert!(load_unknown.recover());
+        assert!(!load_unknown.recover());
+    }
+}
@@ -1,43 +1,389 @@
 use anyhow::Result;
-use log::{debug, warn};
-use std::collections::HashMap;
+use log::{debug, error, info, warn};
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
 use std::path::Path;
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc, Mutex,
+};
+use std::time::{Duration, Instant};
 
-use crate::datasource::file_path::*;
-use crate::utils::file_helper::FileHelper;
+use crate::datasource::{
+    file_path::*,
+    load_monitor::{get_gpu_current_freq, invalidate_read_cache},
+};
+use crate::model::{opp_table::OppEntry, volt_offset::VoltOffset};
+use crate::utils::{constants::strategy, file_helper::FileHelper};
+
+// 连续多少次因节点只读而写入失败后切换到仅监控模式
+const READ_ONLY_FAILURE_LIMIT: u32 = 10;
+
+// 按节点路径记录只读导致的连续写入失败次数，所有克隆共享
+// 按路径计数，其他节点（如电压节点）写入成功不会清零OPP节点的失败次数
+static READ_ONLY_FAILURES: Lazy<Mutex<HashMap<String, u32>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+// 仅监控模式：继续采样负载但不再写入OPP节点
+static MONITOR_ONLY: AtomicBool = AtomicBool::new(false);
+
+// v2频率吸附的累计偏差（KHz）和吸附次数，所有克隆共享
+static SNAP_ERROR_TOTAL_KHZ: AtomicU64 = AtomicU64::new(0);
+static SNAP_COUNT: AtomicU64 = AtomicU64::new(0);
+// 已警告过的请求频率，每个频率只警告一次
+static SNAP_WARNED: Lazy<Mutex<HashSet<i64>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// 电压校验对频率表电压的修改，to为0表示拒绝配置的电压，由驱动决定电压
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VoltAdjustment {
+    pub freq: i64,
+    pub from: i64,
+    pub to: i64,
+}
+
+/// 本次运行中v2频率吸附的次数和累计偏差（KHz）
+pub fn get_snap_error_stats() -> (u64, u64) {
+    (
+        SNAP_COUNT.load(Ordering::SeqCst),
+        SNAP_ERROR_TOTAL_KHZ.load(Ordering::SeqCst),
+    )
+}
+
+/// 是否处于仅监控模式
+pub fn is_monitor_only() -> bool {
+    MONITOR_ONLY.load(Ordering::SeqCst)
+}
+
+// 判断写入错误是否表示节点只读或无权限
+fn is_read_only_error(e: &io::Error) -> bool {
+    e.kind() == io::ErrorKind::PermissionDenied || e.raw_os_error() == Some(libc::EROFS)
+}
+
+// 复位电压和OPP节点时写入的值
+const VOLT_RESET: &str = "0 0";
+const OPP_RESET_MINUS_ONE: &str = "-1";
+const OPP_RESET_ZERO: &str = "0";
+// v2驱动复位OPP后等待多久再写入电压
+const V2_VOLT_WRITE_DELAY: Duration = Duration::from_millis(10);
+
+/// 写入模式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WriteMode {
+    /// 空闲时复位到驱动默认值
+    Idle,
+    /// DCS模式，交给驱动选择最低档位
+    Dcs,
+    /// 只写OPP，不指定电压
+    NoVolt,
+    /// 同时写入频率和电压
+    Normal,
+}
+
+impl WriteMode {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::Dcs => "DCS",
+            Self::NoVolt => "no-volt",
+            Self::Normal => "normal",
+        }
+    }
+}
+
+/// v2驱动复位OPP时写入的值
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OppResetValue {
+    /// 先写入-1，失败时写入0（默认）
+    Auto,
+    /// 只写入-1
+    MinusOne,
+    /// 只写入0，跳过不支持-1的驱动上必然失败的写入
+    Zero,
+}
+
+impl OppResetValue {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "auto" => Some(Self::Auto),
+            "minus_one" => Some(Self::MinusOne),
+            "zero" => Some(Self::Zero),
+            _ => None,
+        }
+    }
+
+    /// 配置文件中的写法
+    pub fn config_name(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::MinusOne => "minus_one",
+            Self::Zero => "zero",
+        }
+    }
+
+    // 生成复位OPP节点的写入步骤
+    fn step(self, opp_path: &'static str) -> WriteStep {
+        match self {
+            Self::Auto => WriteStep::WriteOr(
+                opp_path,
+                OPP_RESET_MINUS_ONE.to_string(),
+                OPP_RESET_ZERO.to_string(),
+            ),
+            Self::MinusOne => WriteStep::Write(opp_path, OPP_RESET_MINUS_ONE.to_string()),
+            Self::Zero => WriteStep::Write(opp_path, OPP_RESET_ZERO.to_string()),
+        }
+    }
+}
+
+/// 写入步骤
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum WriteStep {
+    /// 写入节点
+    Write(&'static str, String),
+    /// 写入节点，失败时改写备用值
+    WriteOr(&'static str, String, String),
+    /// 等待驱动处理上一步写入
+    Sleep(Duration),
+}
+
+/// 节点写入接口 - 写入计划通过该接口写入节点，测试中替换为记录写入的实现
+trait NodeWriter {
+    /// 写入节点，返回是否成功
+    fn write(&self, path: &str, content: &str) -> bool;
+}
+
+/// 写入计划 - 描述一次调频要按顺序写入哪些节点和值，由apply_opp统一执行
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct WritePlan {
+    mode: WriteMode,
+    steps: Vec<WriteStep>,
+}
+
+impl WritePlan {
+    /// 空闲模式：复位电压和OPP
+    fn idle(
+        gpuv2: bool,
+        opp_reset: OppResetValue,
+        volt_path: &'static str,
+        opp_path: &'static str,
+    ) -> Self {
+        let opp_step = if gpuv2 {
+            opp_reset.step(opp_path)
+        } else {
+            WriteStep::Write(opp_path, OPP_RESET_ZERO.to_string())
+        };
+        Self {
+            mode: WriteMode::Idle,
+            steps: vec![
+                WriteStep::Write(volt_path, VOLT_RESET.to_string()),
+                opp_step,
+            ],
+        }
+    }
+
+    /// DCS模式：复位电压，按配置复位OPP
+    fn dcs(opp_reset: OppResetValue, volt_path: &'static str, opp_path: &'static str) -> Self {
+        Self {
+            mode: WriteMode::Dcs,
+            steps: vec![
+                WriteStep::Write(volt_path, VOLT_RESET.to_string()),
+                opp_reset.step(opp_path),
+            ],
+        }
+    }
+
+    /// 无电压模式：复位电压后直接写入频率
+    fn no_volt(volt_path: &'static str, opp_path: &'static str, content: &str) -> Self {
+        Self {
+            mode: WriteMode::NoVolt,
+            steps: vec![
+                WriteStep::Write(volt_path, VOLT_RESET.to_string()),
+                WriteStep::Write(opp_path, content.to_string()),
+            ],
+        }
+    }
+
+    /// 正常模式：复位OPP后写入频率和电压
+    fn normal(
+        gpuv2: bool,
+        opp_reset: OppResetValue,
+        volt_path: &'static str,
+        opp_path: &'static str,
+        volt_content: &str,
+    ) -> Self {
+        let steps = if gpuv2 {
+            vec![
+                WriteStep::Write(volt_path, VOLT_RESET.to_string()),
+                opp_reset.step(opp_path),
+                WriteStep::Sleep(V2_VOLT_WRITE_DELAY),
+                WriteStep::Write(volt_path, volt_content.to_string()),
+            ]
+        } else {
+            vec![
+                WriteStep::Write(opp_path, OPP_RESET_ZERO.to_string()),
+                WriteStep::Write(volt_path, volt_content.to_string()),
+            ]
+        };
+        Self {
+            mode: WriteMode::Normal,
+            steps,
+        }
+    }
+
+    /// 按顺序执行写入步骤
+    fn execute(&self, writer: &dyn NodeWriter) {
+        for step in &self.steps {
+            match step {
+                WriteStep::Write(path, content) => {
+                    writer.write(path, content);
+                }
+                WriteStep::WriteOr(path, content, fallback) => {
+                    if !writer.write(path, content) {
+                        writer.write(path, fallback);
+                    }
+                }
+                WriteStep::Sleep(duration) => std::thread::sleep(*duration),
+            }
+        }
+    }
+}
+
+/// 回读频率与写入频率不一致时的处理方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FreqMismatchAction {
+    /// 只记录警告（默认）
+    Log,
+    /// 重新写入一次
+    Retry,
+    /// 重新检测驱动并校验频率表
+    Reinit,
+}
+
+impl FreqMismatchAction {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "log" => Some(Self::Log),
+            "retry" => Some(Self::Retry),
+            "reinit" => Some(Self::Reinit),
+            _ => None,
+        }
+    }
+}
+
+/// v2频率吸附时目标频率与两个支持频率距离相同时的选择
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum V2SnapTiebreak {
+    /// 选择较高的频率，保留性能余量（默认）
+    Up,
+    /// 选择较低的频率，节省功耗
+    Down,
+}
+
+impl V2SnapTiebreak {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "up" => Some(Self::Up),
+            "down" => Some(Self::Down),
+            _ => None,
+        }
+    }
+}
 
 /// 频率管理器 - 负责GPU频率的计算和调整逻辑
 #[derive(Clone)]
 pub struct FrequencyManager {
-    /// 可用频率列表
+    /// 可用频率列表（经过允许/禁止列表过滤）
     pub config_list: Vec<i64>,
-    /// 频率到电压的映射
+    /// 频率表中的全部频率（过滤前）
+    pub table_freqs: Vec<i64>,
+    /// 只允许使用的频率，为空表示不限制
+    pub allowed_freqs: Vec<i64>,
+    /// 禁止使用的频率
+    pub blocked_freqs: Vec<i64>,
+    /// 频率到电压的映射（经过校验）
     pub freq_volt: HashMap<i64, i64>,
+    /// 频率表中配置的电压（校验前）
+    pub loaded_freq_volt: HashMap<i64, i64>,
+    /// 驱动默认OPP表中的电压
+    pub def_volt: HashMap<i64, i64>,
+    /// 配置电压与驱动默认电压最多相差多少，0表示不校验
+    pub volt_tolerance: i64,
+    /// 驱动默认OPP表中没有的频率是否拒绝配置的电压
+    pub reject_unknown_volt: bool,
     /// 频率到DDR的映射
     pub freq_dram: HashMap<i64, i64>,
     /// 当前频率
     pub cur_freq: i64,
     /// 当前频率索引
     pub cur_freq_idx: i64,
-    /// 当前电压
+    /// 当前电压（实际写入的电压）
     pub cur_volt: i64,
+    /// 当前频率在频率表中的电压
+    pub table_volt: i64,
+    /// 在频率表电压上应用的偏移
+    pub volt_offset: VoltOffset,
     /// 是否使用v2驱动
     pub gpuv2: bool,
     /// v2驱动支持的频率列表
     pub v2_supported_freqs: Vec<i64>,
+    /// 启动时使用的频率索引
+    pub startup_opp_index: i64,
+    /// 启动时是否沿用驱动当前频率
+    pub startup_use_current_freq: bool,
+    /// 两次写入OPP节点的最小间隔（微秒，0表示不限制）
+    pub min_write_interval_us: u64,
+    /// 上次写入OPP节点的时间
+    pub last_write_time: Option<Instant>,
+    /// 被限流推迟的写入参数 (need_dcs, is_idle)
+    pub pending_write: Option<(bool, bool)>,
+    /// 升频到该索引及以上时回读确认写入是否生效，None表示不确认
+    pub verify_high_opp_index: Option<i64>,
+    /// 回读频率与写入不一致时的处理方式
+    pub on_freq_mismatch: FreqMismatchAction,
+    /// 是否需要由调频循环重新检测驱动
+    pub reinit_requested: bool,
+    /// v2频率吸附距离相同时的选择
+    pub v2_snap_tiebreak: V2SnapTiebreak,
+    /// v2驱动复位OPP时写入的值
+    pub opp_reset_value: OppResetValue,
+    /// 最近一次实际写入节点的频率索引（经过v2吸附后），尚未写入或空闲/DCS复位时为None
+    pub written_freq_idx: Option<i64>,
+    /// 写入锁，所有克隆共享，保证多步写入序列不会交错
+    write_lock: Arc<Mutex<()>>,
 }
 
 impl FrequencyManager {
     pub fn new() -> Self {
         Self {
             config_list: Vec::new(),
+            table_freqs: Vec::new(),
+            allowed_freqs: Vec::new(),
+            blocked_freqs: Vec::new(),
             freq_volt: HashMap::new(),
+            loaded_freq_volt: HashMap::new(),
+            def_volt: HashMap::new(),
+            volt_tolerance: strategy::VOLT_TOLERANCE_DEFAULT,
+            reject_unknown_volt: false,
             freq_dram: HashMap::new(),
             cur_freq: 0,
             cur_freq_idx: 0,
             cur_volt: 0,
+            table_volt: 0,
+            volt_offset: VoltOffset::default(),
             gpuv2: false,
             v2_supported_freqs: Vec::new(),
+            startup_opp_index: 0,
+            startup_use_current_freq: false,
+            min_write_interval_us: 0,
+            last_write_time: None,
+            pending_write: None,
+            verify_high_opp_index: None,
+            on_freq_mismatch: FreqMismatchAction::Log,
+            reinit_requested: false,
+            v2_snap_tiebreak: V2SnapTiebreak::Up,
+            opp_reset_value: OppResetValue::Auto,
+            written_freq_idx: None,
+            write_lock: Arc::new(Mutex::new(())),
         }
     }
 
@@ -55,42 +401,50 @@ impl FrequencyManager {
             .unwrap_or(0)
     }
 
-    /// 获取大于等于指定频率的最小频率
+    /// 获取大于等于指定频率的最小频率，超过最高频率时返回最高频率
     pub fn read_freq_ge(&self, freq: i64) -> i64 {
         debug!("readFreqGe={freq}");
         if freq <= 0 {
             return *self.config_list.last().unwrap_or(&0);
         }
-        for &cfreq in &self.config_list {
-            if cfreq >= freq {
-                return cfreq;
-            }
-        }
-        *self.config_list.last().unwrap_or(&0)
+        // 频率表按升序排列，二分查找第一个不小于目标的频率
+        let idx = self.config_list.partition_point(|&cfreq| cfreq < freq);
+        self.config_list
+            .get(idx)
+            .or_else(|| self.config_list.last())
+            .copied()
+            .unwrap_or(0)
     }
 
-    /// 获取小于等于指定频率的最大频率
+    /// 获取小于等于指定频率的最大频率，低于最低频率时返回最低频率
     pub fn read_freq_le(&self, freq: i64) -> i64 {
         debug!("readFreqLe={freq}");
         if freq <= 0 {
             return *self.config_list.first().unwrap_or(&0);
         }
-        for &cfreq in self.config_list.iter().rev() {
-            if cfreq <= freq {
-                return cfreq;
-            }
-        }
-        *self.config_list.first().unwrap_or(&0)
+        // 二分查找最后一个不大于目标的频率
+        let idx = self.config_list.partition_point(|&cfreq| cfreq <= freq);
+        idx.checked_sub(1)
+            .and_then(|idx| self.config_list.get(idx))
+            .or_else(|| self.config_list.first())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// 获取最接近指定频率的可用频率
+    pub fn read_freq_nearest(&self, freq: i64) -> i64 {
+        self.config_list
+            .iter()
+            .copied()
+            .min_by_key(|&cfreq| (cfreq - freq).abs())
+            .unwrap_or(0)
     }
 
-    /// 获取频率对应的索引
+    /// 获取频率对应的索引，频率不在表中时返回0
     pub fn read_freq_index(&self, freq: i64) -> i64 {
-        for (i, &cfreq) in self.config_list.iter().enumerate() {
-            if cfreq == freq {
-                return i as i64;
-            }
-        }
-        0
+        self.config_list
+            .binary_search(&freq)
+            .map_or(0, |idx| idx as i64)
     }
 
     /// 获取最高频率
@@ -103,7 +457,7 @@ impl FrequencyManager {
         *self.config_list.first().unwrap_or(&0)
     }
 
-    /// 获取中等频率
+    /// 获取中等频率（按索引取中间档位，保留用于兼容）
     pub fn get_middle_freq(&self) -> i64 {
         if self.config_list.is_empty() {
             return 0;
@@ -112,6 +466,12 @@ impl FrequencyManager {
         self.config_list[mid_idx]
     }
 
+    /// 获取频率范围中点附近的频率，即最接近 (最低频率+最高频率)/2 的可用频率
+    pub fn get_mid_range_freq(&self) -> i64 {
+        let mid = (self.get_min_freq() + self.get_max_freq()) / 2;
+        self.read_freq_nearest(mid)
+    }
+
     /// 获取第二高频率
     pub fn get_second_highest_freq(&self) -> i64 {
         if self.config_list.len() < 2 {
@@ -126,18 +486,52 @@ impl FrequencyManager {
             return target_freq;
         }
 
-        let mut closest_freq = self.v2_supported_freqs[0];
-        let mut min_diff = (target_freq - closest_freq).abs();
+        // 距离相同时按配置选择较高或较低的频率，结果与支持频率列表的顺序无关
+        let tiebreak = self.v2_snap_tiebreak;
+        self.v2_supported_freqs
+            .iter()
+            .copied()
+            .min_by_key(|&freq| {
+                let diff = (target_freq - freq).abs();
+                match tiebreak {
+                    V2SnapTiebreak::Up => (diff, -freq),
+                    V2SnapTiebreak::Down => (diff, freq),
+                }
+            })
+            .unwrap_or(target_freq)
+    }
 
-        for &freq in &self.v2_supported_freqs {
-            let diff = (target_freq - freq).abs();
-            if diff < min_diff {
-                min_diff = diff;
-                closest_freq = freq;
-            }
+    /// 设置v2驱动复位OPP时写入的值
+    pub fn set_opp_reset_value(&mut self, value: OppResetValue) {
+        self.opp_reset_value = value;
+        debug!("Set OPP reset value: {value:?}");
+    }
+
+    /// 设置v2频率吸附距离相同时的选择
+    pub fn set_v2_snap_tiebreak(&mut self, tiebreak: V2SnapTiebreak) {
+        self.v2_snap_tiebreak = tiebreak;
+        debug!("Set v2 snap tiebreak: {tiebreak:?}");
+    }
+
+    // 记录写入时的v2频率吸附，偏差较大时提示频率表与驱动OPP不匹配
+    fn record_snap(requested: i64, snapped: i64) {
+        let error = (requested - snapped).unsigned_abs();
+        if error == 0 {
+            return;
         }
 
-        closest_freq
+        SNAP_COUNT.fetch_add(1, Ordering::SeqCst);
+        SNAP_ERROR_TOTAL_KHZ.fetch_add(error, Ordering::SeqCst);
+
+        if error > strategy::V2_SNAP_WARN_KHZ as u64
+            && SNAP_WARNED.lock().unwrap().insert(requested)
+        {
+            warn!(
+                "Frequency {requested}KHz is not supported by the driver, snapped to {snapped}KHz ({error}KHz off), check the frequency table"
+            );
+        } else {
+            debug!("Frequency {requested}KHz snapped to {snapped}KHz ({error}KHz off)");
+        }
     }
 
     /// 生成当前电压
@@ -146,7 +540,8 @@ impl FrequencyManager {
         let freq_to_use = self.get_closest_v2_supported_freq(self.cur_freq);
 
         // 获取电压值，优先使用频率-电压表，如果没有则尝试使用默认电压表
-        self.cur_volt = self.get_volt(freq_to_use);
+        self.table_volt = self.get_volt(freq_to_use);
+        self.cur_volt = self.volt_offset.apply(freq_to_use, self.table_volt);
 
         self.cur_volt
     }
@@ -168,8 +563,96 @@ impl FrequencyManager {
         Ok(())
     }
 
+    /// 写入驱动默认值（复位电压和OPP），将调频交还给驱动自带的DVFS
+    pub fn write_driver_default(&mut self) -> Result<()> {
+        let write_lock = Arc::clone(&self.write_lock);
+        let _guard = write_lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        self.pending_write = None;
+        self.written_freq_idx = None;
+
+        let (volt_path, opp_path) = if self.gpuv2 {
+            (GPUFREQV2_VOLT, GPUFREQV2_OPP)
+        } else {
+            (GPUFREQ_VOLT, GPUFREQ_OPP)
+        };
+        if !Path::new(volt_path).exists() || !Path::new(opp_path).exists() {
+            return Ok(());
+        }
+
+        // 交还驱动时直接写入，不受写入退避影响，否则节点处于退避期间时会停留在最后写入的频率
+        debug!("Writing driver default OPP and voltage");
+        write_reset_node(volt_path, VOLT_RESET);
+        if self.gpuv2 {
+            match self.opp_reset_value {
+                OppResetValue::Auto => {
+                    if let Err(e) = fs::write(opp_path, OPP_RESET_MINUS_ONE) {
+                        debug!("Writing {OPP_RESET_MINUS_ONE} to {opp_path} failed ({e}), trying {OPP_RESET_ZERO}");
+                        write_reset_node(opp_path, OPP_RESET_ZERO);
+                    }
+                }
+                OppResetValue::MinusOne => write_reset_node(opp_path, OPP_RESET_MINUS_ONE),
+                OppResetValue::Zero => write_reset_node(opp_path, OPP_RESET_ZERO),
+            }
+        } else {
+            write_reset_node(opp_path, OPP_RESET_ZERO);
+            // v1驱动需要重新打开DVFS
+            if Path::new(MALI_DVFS_ENABLE).exists() {
+                write_reset_node(MALI_DVFS_ENABLE, "1");
+            }
+        }
+        invalidate_read_cache();
+
+        Ok(())
+    }
+
+    /// 设置两次写入OPP节点的最小间隔
+    pub fn set_min_write_interval(&mut self, interval_us: u64) {
+        self.min_write_interval_us = interval_us;
+        debug!("Set min OPP write interval: {interval_us}us");
+    }
+
+    /// 检查写入是否需要因限流而推迟，推迟时记录最新的写入参数
+    fn defer_write(&mut self, need_dcs: bool, is_idle: bool) -> bool {
+        if self.min_write_interval_us == 0 {
+            return false;
+        }
+
+        let min_interval = Duration::from_micros(self.min_write_interval_us);
+        match self.last_write_time {
+            Some(last) if last.elapsed() < min_interval => {
+                // 限流期间只保留最新的决策
+                self.pending_write = Some((need_dcs, is_idle));
+                debug!("OPP write deferred by rate limiter");
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 写入被限流推迟的频率（如果限流间隔已过）
+    pub fn flush_pending_write(&mut self) -> Result<()> {
+        if let Some((need_dcs, is_idle)) = self.pending_write {
+            self.write_freq(need_dcs, is_idle)?;
+        }
+        Ok(())
+    }
+
     /// 写入频率到系统文件
-    pub fn write_freq(&self, need_dcs: bool, is_idle: bool) -> Result<()> {
+    pub fn write_freq(&mut self, need_dcs: bool, is_idle: bool) -> Result<()> {
+        if is_monitor_only() {
+            return Ok(());
+        }
+        if self.defer_write(need_dcs, is_idle) {
+            return Ok(());
+        }
+        self.pending_write = None;
+        self.last_write_time = Some(Instant::now());
+
+        // 持有写入锁直到整个写入序列完成，避免其他线程的写入插入到复位和写入步骤之间
+        let write_lock = Arc::clone(&self.write_lock);
+        let _guard = write_lock.lock().unwrap_or_else(|e| e.into_inner());
+
         // 第一步：确保DVFS处于关闭状态（仅对v1驱动）
         if !self.gpuv2 {
             self.ensure_dvfs_disabled()?;
@@ -177,16 +660,22 @@ impl FrequencyManager {
 
         // 根据驱动类型获取要使用的频率
         let freq_to_use = if self.gpuv2 {
-            self.get_closest_v2_supported_freq(self.cur_freq)
+            let snapped = self.get_closest_v2_supported_freq(self.cur_freq);
+            if !is_idle {
+                Self::record_snap(self.cur_freq, snapped);
+            }
+            snapped
         } else {
             self.cur_freq
         };
 
+        debug!(
+            "Writing freq {}KHz (snapped: {freq_to_use}KHz), table volt: {}, applied volt: {}",
+            self.cur_freq, self.table_volt, self.cur_volt
+        );
+
         let content = freq_to_use.to_string();
         let volt_content = format!("{} {}", freq_to_use, self.cur_volt);
-        let volt_reset = "0 0";
-        let opp_reset_minus_one = "-1";
-        let opp_reset_zero = "0";
 
         let volt_path = if self.gpuv2 {
             GPUFREQV2_VOLT
@@ -206,114 +695,206 @@ impl FrequencyManager {
 
         // 确定写入模式
         if is_idle {
-            self.write_idle_mode(volt_path, opp_path, volt_reset, opp_reset_zero)?;
-        } else if need_dcs && self.gpuv2 && self.cur_freq_idx == 0 {
-            self.write_dcs_mode(
+            self.apply_opp(&WritePlan::idle(
+                self.gpuv2,
+                self.opp_reset_value,
                 volt_path,
                 opp_path,
-                volt_reset,
-                opp_reset_minus_one,
-                opp_reset_zero,
-            )?;
-        } else if self.cur_volt == 0 {
-            self.write_no_volt_mode(volt_path, opp_path, volt_reset, &content)?;
+            ))?;
+            self.written_freq_idx = None;
+        } else if need_dcs && self.gpuv2 && self.cur_freq_idx == 0 {
+            self.apply_opp(&WritePlan::dcs(self.opp_reset_value, volt_path, opp_path))?;
+            self.written_freq_idx = None;
         } else {
-            self.write_normal_mode(
-                volt_path,
-                opp_path,
-                volt_reset,
-                opp_reset_minus_one,
-                opp_reset_zero,
-                &volt_content,
-            )?;
+            self.write_target_freq(volt_path, opp_path, &content, &volt_content)?;
+
+            // 升到高档位时回读确认，写入未生效时按配置处理
+            if self.needs_verify() && !self.verify_written(freq_to_use) {
+                self.handle_freq_mismatch(
+                    volt_path,
+                    opp_path,
+                    &content,
+                    &volt_content,
+                    freq_to_use,
+                )?;
+            }
+            self.record_written_freq(freq_to_use);
         }
 
+        Self::check_read_only_nodes(opp_path);
+
         Ok(())
     }
 
-    /// 空闲模式写入
-    fn write_idle_mode(
+    // 按是否有电压选择写入模式写入目标频率
+    fn write_target_freq(
         &self,
-        volt_path: &str,
-        opp_path: &str,
-        volt_reset: &str,
-        opp_reset_zero: &str,
+        volt_path: &'static str,
+        opp_path: &'static str,
+        content: &str,
+        volt_content: &str,
     ) -> Result<()> {
-        debug!("Writing in idle mode");
-        if self.gpuv2 {
-            FileHelper::write_string_safe(volt_path, volt_reset);
-            let result = FileHelper::write_string_safe(opp_path, "-1");
-            if !result {
-                FileHelper::write_string_safe(opp_path, opp_reset_zero);
-            }
+        let plan = if self.cur_volt == 0 {
+            WritePlan::no_volt(volt_path, opp_path, content)
         } else {
-            FileHelper::write_string_safe(volt_path, volt_reset);
-            FileHelper::write_string_safe(opp_path, opp_reset_zero);
-        }
-        Ok(())
+            WritePlan::normal(
+                self.gpuv2,
+                self.opp_reset_value,
+                volt_path,
+                opp_path,
+                volt_content,
+            )
+        };
+        self.apply_opp(&plan)
     }
 
-    /// DCS模式写入
-    fn write_dcs_mode(
-        &self,
-        volt_path: &str,
-        opp_path: &str,
-        volt_reset: &str,
-        opp_reset_minus_one: &str,
-        opp_reset_zero: &str,
-    ) -> Result<()> {
-        debug!("Writing in DCS mode");
-        FileHelper::write_string_safe(volt_path, volt_reset);
-        let result = FileHelper::write_string_safe(opp_path, opp_reset_minus_one);
-        if !result {
-            FileHelper::write_string_safe(opp_path, opp_reset_zero);
+    /// 设置需要回读确认的最低频率索引，None表示不确认
+    pub fn set_verify_high_opp_index(&mut self, index: Option<i64>) {
+        self.verify_high_opp_index = index;
+        debug!("Set verify high OPP index: {index:?}");
+    }
+
+    /// 设置电压偏移，偏移会把频率表中的电压降到0或以下时拒绝
+    pub fn set_volt_offset(&mut self, offset: VoltOffset) -> Result<()> {
+        offset.validate(&self.freq_volt)?;
+        if !offset.is_empty() {
+            info!(
+                "Voltage offset: {} (per-frequency: {:?}), floor: min {} / table - {}",
+                offset.global(),
+                offset.per_freq(),
+                offset.min_volt(),
+                offset.max_undervolt()
+            );
         }
+        self.volt_offset = offset;
         Ok(())
     }
 
-    /// 无电压模式写入
-    fn write_no_volt_mode(
-        &self,
-        volt_path: &str,
-        opp_path: &str,
-        volt_reset: &str,
+    /// 设置回读频率不一致时的处理方式
+    pub fn set_on_freq_mismatch(&mut self, action: FreqMismatchAction) {
+        self.on_freq_mismatch = action;
+        debug!("Set frequency mismatch action: {action:?}");
+    }
+
+    /// 取出重新检测驱动的请求
+    pub fn take_reinit_request(&mut self) -> bool {
+        std::mem::take(&mut self.reinit_requested)
+    }
+
+    // 回读频率与写入不一致时按配置记录、重试或请求重新检测驱动
+    fn handle_freq_mismatch(
+        &mut self,
+        volt_path: &'static str,
+        opp_path: &'static str,
         content: &str,
+        volt_content: &str,
+        freq: i64,
     ) -> Result<()> {
-        debug!("Writing in no-volt mode");
-        FileHelper::write_string_safe(volt_path, volt_reset);
-        FileHelper::write_string_safe(opp_path, content);
+        match self.on_freq_mismatch {
+            FreqMismatchAction::Log => warn!("Frequency {freq}KHz not applied"),
+            FreqMismatchAction::Retry => {
+                debug!("Frequency {freq}KHz not applied, retrying write");
+                self.write_target_freq(volt_path, opp_path, content, volt_content)?;
+                if !self.verify_written(freq) {
+                    warn!("Frequency {freq}KHz still not applied after retry");
+                }
+            }
+            FreqMismatchAction::Reinit => {
+                warn!("Frequency {freq}KHz not applied, re-detecting GPU driver");
+                self.reinit_requested = true;
+            }
+        }
         Ok(())
     }
 
-    /// 正常模式写入
-    fn write_normal_mode(
-        &self,
-        volt_path: &str,
-        opp_path: &str,
-        volt_reset: &str,
-        opp_reset_minus_one: &str,
-        opp_reset_zero: &str,
-        volt_content: &str,
-    ) -> Result<()> {
-        debug!("Writing in normal mode");
-        if self.gpuv2 {
-            FileHelper::write_string_safe(volt_path, volt_reset);
-            let result = FileHelper::write_string_safe(opp_path, opp_reset_minus_one);
-            if !result {
-                FileHelper::write_string_safe(opp_path, opp_reset_zero);
+    // 只在升频到配置的高档位时确认，降频和普通档位不增加开销
+    fn needs_verify(&self) -> bool {
+        let Some(threshold) = self.verify_high_opp_index else {
+            return false;
+        };
+        self.cur_freq_idx >= threshold
+            && self
+                .written_freq_idx
+                .is_none_or(|written| self.cur_freq_idx > written)
+    }
+
+    // 回读当前频率，检查写入是否生效
+    fn verify_written(&self, freq: i64) -> bool {
+        match get_gpu_current_freq(!self.gpuv2) {
+            Ok(applied) => applied == freq,
+            Err(e) => {
+                debug!("Failed to read back GPU frequency: {e}");
+                true
             }
-            std::thread::sleep(std::time::Duration::from_millis(10));
-            FileHelper::write_string_safe(volt_path, volt_content);
-        } else {
-            FileHelper::write_string_safe(opp_path, opp_reset_zero);
-            FileHelper::write_string_safe(volt_path, volt_content);
         }
+    }
+
+    // 节点持续只读时只提示一次并切换到仅监控模式，避免每次采样都写入失败刷屏
+    fn check_read_only_nodes(opp_path: &str) {
+        let failures = READ_ONLY_FAILURES
+            .lock()
+            .unwrap()
+            .get(opp_path)
+            .copied()
+            .unwrap_or(0);
+        if failures < READ_ONLY_FAILURE_LIMIT {
+            return;
+        }
+        if !MONITOR_ONLY.swap(true, Ordering::SeqCst) {
+            error!(
+                "GPU frequency nodes are read-only ({opp_path}); writes keep failing, switching to monitor-only mode"
+            );
+        }
+    }
+
+    // 写入节点，记录因只读导致的连续失败，节点处于写入退避期间时跳过
+    fn write_node(&self, path: &str, content: &str) -> bool {
+        Self::record_write_result(path, FileHelper::write_with_backoff(path, content))
+    }
+
+    // 按节点记录写入结果，写入成功时只清零该节点的只读失败次数，返回是否写入成功
+    fn record_write_result(path: &str, result: Option<io::Result<()>>) -> bool {
+        match result {
+            Some(Ok(())) => {
+                READ_ONLY_FAILURES.lock().unwrap().remove(path);
+                true
+            }
+            Some(Err(e)) => {
+                if is_read_only_error(&e) {
+                    *READ_ONLY_FAILURES
+                        .lock()
+                        .unwrap()
+                        .entry(path.to_string())
+                        .or_default() += 1;
+                }
+                false
+            }
+            None => false,
+        }
+    }
+
+    // 记录实际写入的频率索引，吸附后的频率可能不在频率表中，取最接近的表项
+    fn record_written_freq(&mut self, freq: i64) {
+        self.written_freq_idx = Some(self.read_freq_index(self.read_freq_nearest(freq)));
+    }
+
+    /// 获取最近一次实际写入节点的频率索引
+    pub fn get_written_freq_index(&self) -> Option<i64> {
+        self.written_freq_idx
+    }
+
+    /// 按写入计划依次写入节点，所有写入模式共用
+    fn apply_opp(&self, plan: &WritePlan) -> Result<()> {
+        debug!("Writing in {} mode", plan.mode.label());
+        plan.execute(self);
+        // 写入后缓存的频率和负载已过期
+        invalidate_read_cache();
         Ok(())
     }
 
-    /// 统一ID范围
+    /// 统一ID范围，频率表为空时返回0，由调用方的默认值处理
     fn unify_id(&self, id: i64) -> i64 {
-        if id < 0 {
+        if id < 0 || self.config_list.is_empty() {
             return 0;
         }
         if id >= self.config_list.len() as i64 {
@@ -322,9 +903,58 @@ impl FrequencyManager {
         id
     }
 
-    /// 设置配置列表
-    pub fn set_config_list(&mut self, config_list: Vec<i64>) {
-        self.config_list = config_list;
+    /// 设置启动频率策略
+    pub fn set_startup_opp(&mut self, index: i64, use_current_freq: bool) {
+        self.startup_opp_index = index;
+        self.startup_use_current_freq = use_current_freq;
+        debug!("Set startup OPP index: {index}, use current freq: {use_current_freq}");
+    }
+
+    /// 设置配置列表，按升序排列以便二分查找，并按允许/禁止列表过滤
+    pub fn set_config_list(&mut self, mut config_list: Vec<i64>) {
+        if !config_list.windows(2).all(|pair| pair[0] < pair[1]) {
+            warn!("Frequency table is not in strictly ascending order, sorting it");
+            config_list.sort_unstable();
+            config_list.dedup();
+        }
+        self.table_freqs = config_list;
+        self.apply_freq_filter();
+    }
+
+    /// 设置允许和禁止使用的频率，允许列表为空表示不限制
+    pub fn set_freq_filter(&mut self, allowed: Vec<i64>, blocked: Vec<i64>) {
+        if !self.table_freqs.is_empty() {
+            for freq in allowed.iter().chain(&blocked) {
+                if !self.table_freqs.contains(freq) {
+                    warn!("Frequency {freq}KHz in allowed_freqs/blocked_freqs is not in the frequency table");
+                }
+            }
+        }
+        debug!("Set frequency filter: allowed {allowed:?}, blocked {blocked:?}");
+        self.allowed_freqs = allowed;
+        self.blocked_freqs = blocked;
+        self.apply_freq_filter();
+    }
+
+    // 按允许/禁止列表从频率表生成可用频率列表，全部被过滤时忽略过滤
+    fn apply_freq_filter(&mut self) {
+        let filtered: Vec<i64> = self
+            .table_freqs
+            .iter()
+            .copied()
+            .filter(|freq| self.allowed_freqs.is_empty() || self.allowed_freqs.contains(freq))
+            .filter(|freq| !self.blocked_freqs.contains(freq))
+            .collect();
+
+        if filtered.is_empty() && !self.table_freqs.is_empty() {
+            warn!("allowed_freqs/blocked_freqs exclude every frequency, using the full frequency table");
+            self.config_list = self.table_freqs.clone();
+            return;
+        }
+        if filtered.len() != self.table_freqs.len() {
+            info!("Usable frequencies restricted to {filtered:?}");
+        }
+        self.config_list = filtered;
     }
 
     /// 获取配置列表
@@ -334,9 +964,122 @@ impl FrequencyManager {
 
     /// 替换映射表
     pub fn replace_freq_volt_tab(&mut self, tab: HashMap<i64, i64>) {
+        self.loaded_freq_volt = tab.clone();
         self.freq_volt = tab;
     }
 
+    /// 设置驱动默认OPP表中的电压
+    pub fn set_def_volt(&mut self, def_volt: HashMap<i64, i64>) {
+        debug!("Set default voltage table: {} entries", def_volt.len());
+        self.def_volt = def_volt;
+    }
+
+    /// 设置电压校验的容差和驱动默认表中没有的频率的处理方式
+    pub fn set_volt_validation(&mut self, tolerance: i64, reject_unknown: bool) {
+        self.volt_tolerance = tolerance.max(0);
+        self.reject_unknown_volt = reject_unknown;
+        debug!(
+            "Set voltage validation: tolerance {}, reject unknown: {reject_unknown}",
+            self.volt_tolerance
+        );
+    }
+
+    /// 将频率表电压限制在驱动默认电压上下容差范围内，返回做出的修改
+    ///
+    /// 每次都从频率表中配置的电压重新计算，容差放宽后被限制的电压会恢复。
+    /// 驱动默认表中没有的频率按配置拒绝（不写入电压）或保留并警告
+    pub fn validate_voltages(&mut self) -> Vec<VoltAdjustment> {
+        self.freq_volt = self.loaded_freq_volt.clone();
+        if self.volt_tolerance == 0 || self.def_volt.is_empty() {
+            return Vec::new();
+        }
+
+        let mut adjustments = Vec::new();
+        for &freq in &self.table_freqs {
+            let volt = self.get_volt(freq);
+            if volt <= 0 {
+                continue;
+            }
+            // v2驱动按吸附后的频率写入，默认表中没有原频率时使用吸附后的频率
+            let def_volt = self
+                .def_volt
+                .get(&freq)
+                .or_else(|| self.def_volt.get(&self.get_closest_v2_supported_freq(freq)))
+                .copied();
+            let Some(def_volt) = def_volt else {
+                if self.reject_unknown_volt {
+                    warn!("{freq}KHz is not in the driver OPP table, ignoring configured voltage {volt}");
+                    adjustments.push(VoltAdjustment {
+                        freq,
+                        from: volt,
+                        to: 0,
+                    });
+                } else {
+                    warn!(
+                        "{freq}KHz is not in the driver OPP table, voltage {volt} is not validated"
+                    );
+                }
+                continue;
+            };
+
+            let clamped = clamp_volt(volt, def_volt, self.volt_tolerance);
+            if clamped != volt {
+                warn!(
+                    "{freq}KHz voltage {volt} is outside {def_volt} ± {}, clamped to {clamped}",
+                    self.volt_tolerance
+                );
+                adjustments.push(VoltAdjustment {
+                    freq,
+                    from: volt,
+                    to: clamped,
+                });
+            }
+        }
+
+        for adjustment in &adjustments {
+            self.freq_volt.insert(adjustment.freq, adjustment.to);
+        }
+        adjustments
+    }
+
+    /// 为频率表中没有电压的频率插值生成电压，返回做出的修改
+    ///
+    /// 使用相邻的较低和较高已知频率电压线性插值，并取整到电压步进；
+    /// 超出已知频率范围的频率无法插值，保持没有电压。被电压校验拒绝的频率不会插值
+    pub fn interpolate_missing_volts(&mut self) -> Vec<VoltAdjustment> {
+        let mut known: Vec<(i64, i64)> = self
+            .freq_volt
+            .iter()
+            .filter(|&(_, &volt)| volt > 0)
+            .map(|(&freq, &volt)| (freq, volt))
+            .collect();
+        known.sort_unstable();
+
+        let mut adjustments = Vec::new();
+        for &freq in &self.table_freqs {
+            let configured = self.loaded_freq_volt.get(&freq).copied().unwrap_or(0);
+            if configured > 0 || self.get_volt(freq) > 0 {
+                continue;
+            }
+            match interpolate_volt(&known, freq) {
+                Some(volt) => {
+                    info!("{freq}KHz has no voltage, interpolated {volt}");
+                    adjustments.push(VoltAdjustment {
+                        freq,
+                        from: 0,
+                        to: volt,
+                    });
+                }
+                None => debug!("{freq}KHz has no voltage and is outside the known range"),
+            }
+        }
+
+        for adjustment in &adjustments {
+            self.freq_volt.insert(adjustment.freq, adjustment.to);
+        }
+        adjustments
+    }
+
     pub fn replace_freq_dram_tab(&mut self, tab: HashMap<i64, i64>) {
         self.freq_dram = tab;
     }
@@ -349,6 +1092,65 @@ impl FrequencyManager {
     pub fn read_freq_dram(&self, freq: i64) -> i64 {
         *self.freq_dram.get(&freq).unwrap_or(&0)
     }
+
+    /// 获取频率表中全部档位的频率、电压、驱动默认电压和DDR映射
+    pub fn opp_entries(&self) -> Vec<OppEntry> {
+        self.table_freqs
+            .iter()
+            .map(|&freq| OppEntry {
+                index: self
+                    .config_list
+                    .iter()
+                    .position(|&usable| usable == freq)
+                    .map(|index| index as i64),
+                freq,
+                volt: self.read_freq_volt(freq),
+                def_volt: self.def_volt.get(&freq).copied(),
+                ddr_opp: self.read_freq_dram(freq),
+            })
+            .collect()
+    }
+}
+
+// 写入复位值，失败时输出警告
+fn write_reset_node(path: &str, content: &str) {
+    if let Err(e) = fs::write(path, content) {
+        warn!("Failed to write driver default '{content}' to {path}: {e}");
+    }
+}
+
+// 将电压限制在默认电压上下容差范围内，结果保持为电压步进的整数倍且不超出范围
+fn clamp_volt(volt: i64, def_volt: i64, tolerance: i64) -> i64 {
+    let step = strategy::VOLT_STEP;
+    let low = def_volt - tolerance;
+    let high = def_volt + tolerance;
+    if volt > high {
+        high.div_euclid(step) * step
+    } else if volt < low {
+        (low + step - 1).div_euclid(step) * step
+    } else {
+        volt
+    }
+}
+
+// 在按频率排序的已知频率电压对之间线性插值，结果取整到最接近的电压步进，频率超出已知范围时返回None
+fn interpolate_volt(known: &[(i64, i64)], freq: i64) -> Option<i64> {
+    let upper = known.partition_point(|&(known_freq, _)| known_freq < freq);
+    let &(high_freq, high_volt) = known.get(upper)?;
+    let &(low_freq, low_volt) = known.get(upper.checked_sub(1)?)?;
+    if high_freq == freq {
+        return Some(high_volt);
+    }
+
+    let volt = low_volt + (high_volt - low_volt) * (freq - low_freq) / (high_freq - low_freq);
+    let step = strategy::VOLT_STEP;
+    Some((volt + step / 2).div_euclid(step) * step)
+}
+
+impl NodeWriter for FrequencyManager {
+    fn write(&self, path: &str, content: &str) -> bool {
+        self.write_node(path, content)
+    }
 }
 
 impl Default for FrequencyManager {
@@ -356,3 +1158,402 @@ impl Default for FrequencyManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(freqs: &[i64]) -> FrequencyManager {
+        let mut manager = FrequencyManager::new();
+        manager.set_config_list(freqs.to_vec());
+        manager
+    }
+
+    #[test]
+    fn mid_range_freq_on_uneven_table() {
+        let manager = manager(&[100000, 200000, 300000, 400000, 1000000]);
+        // 按索引取中间档位为300000，范围中点550000最接近的是400000
+        assert_eq!(manager.get_middle_freq(), 300000);
+        assert_eq!(manager.get_mid_range_freq(), 400000);
+
+        assert_eq!(FrequencyManager::new().get_mid_range_freq(), 0);
+    }
+
+    #[test]
+    fn unify_id_on_empty_table() {
+        let manager = FrequencyManager::new();
+        assert_eq!(manager.unify_id(0), 0);
+        assert_eq!(manager.unify_id(5), 0);
+        assert_eq!(manager.unify_id(-1), 0);
+        assert_eq!(manager.get_freq_by_index(3), 0);
+    }
+
+    #[test]
+    fn unify_id_clamps_to_table() {
+        let manager = manager(&[100000, 200000, 300000]);
+        assert_eq!(manager.unify_id(1), 1);
+        assert_eq!(manager.unify_id(-3), 0);
+        assert_eq!(manager.unify_id(7), 2);
+
+        assert_eq!(manager.get_freq_by_index(1), 200000);
+        assert_eq!(manager.get_freq_by_index(-3), 100000);
+        assert_eq!(manager.get_freq_by_index(7), 300000);
+
+        // 频率到索引：精确匹配返回索引，表中没有的频率（两档之间或超出范围）返回0
+        assert_eq!(manager.read_freq_index(300000), 2);
+        assert_eq!(manager.read_freq_index(250000), 0);
+        assert_eq!(manager.read_freq_index(50000), 0);
+        assert_eq!(manager.read_freq_index(400000), 0);
+    }
+
+    #[test]
+    fn freq_lookup_boundaries() {
+        let manager = manager(&[100000, 200000, 300000]);
+
+        // 低于最低频率
+        assert_eq!(manager.read_freq_ge(50000), 100000);
+        assert_eq!(manager.read_freq_le(50000), 100000);
+        // 高于最高频率
+        assert_eq!(manager.read_freq_ge(400000), 300000);
+        assert_eq!(manager.read_freq_le(400000), 300000);
+        // 精确匹配
+        assert_eq!(manager.read_freq_ge(200000), 200000);
+        assert_eq!(manager.read_freq_le(200000), 200000);
+        assert_eq!(manager.read_freq_index(100000), 0);
+        assert_eq!(manager.read_freq_index(200000), 1);
+        // 两档之间
+        assert_eq!(manager.read_freq_ge(150000), 200000);
+        assert_eq!(manager.read_freq_le(150000), 100000);
+        // 非正数频率
+        assert_eq!(manager.read_freq_ge(0), 300000);
+        assert_eq!(manager.read_freq_le(0), 100000);
+
+        let empty = FrequencyManager::new();
+        assert_eq!(empty.read_freq_ge(150000), 0);
+        assert_eq!(empty.read_freq_le(150000), 0);
+        assert_eq!(empty.read_freq_index(150000), 0);
+    }
+
+    // 频率表电压和驱动默认电压都为80000的三档频率表
+    fn volt_manager(tolerance: i64) -> FrequencyManager {
+        let freqs = [300000, 500000, 700000];
+        let mut manager = manager(&freqs);
+        manager.set_def_volt(freqs.iter().map(|&freq| (freq, 80000)).collect());
+        manager.set_volt_validation(tolerance, false);
+        manager
+    }
+
+    #[test]
+    fn clamp_volt_boundaries() {
+        // 正好在容差边界上的电压保持不变
+        assert_eq!(clamp_volt(85000, 80000, 5000), 85000);
+        assert_eq!(clamp_volt(75000, 80000, 5000), 75000);
+        // 超出一个电压步进时限制到边界
+        assert_eq!(clamp_volt(85625, 80000, 5000), 85000);
+        assert_eq!(clamp_volt(74375, 80000, 5000), 75000);
+        // 边界不是电压步进整数倍时向范围内取整
+        assert_eq!(clamp_volt(90000, 80000, 1000), 80625);
+        assert_eq!(clamp_volt(70000, 80000, 1000), 79375);
+    }
+
+    #[test]
+    fn validate_voltages_at_tolerance() {
+        let mut manager = volt_manager(5000);
+        manager.replace_freq_volt_tab(HashMap::from([
+            (300000, 75000),
+            (500000, 85000),
+            (700000, 85625),
+        ]));
+        let adjustments = manager.validate_voltages();
+        assert_eq!(
+            adjustments,
+            vec![VoltAdjustment {
+                freq: 700000,
+                from: 85625,
+                to: 85000,
+            }]
+        );
+        assert_eq!(manager.read_freq_volt(300000), 75000);
+        assert_eq!(manager.read_freq_volt(500000), 85000);
+        assert_eq!(manager.read_freq_volt(700000), 85000);
+
+        // 放宽容差后恢复配置的电压
+        manager.set_volt_validation(10000, false);
+        assert!(manager.validate_voltages().is_empty());
+        assert_eq!(manager.read_freq_volt(700000), 85625);
+    }
+
+    #[test]
+    fn zero_tolerance_disables_validation() {
+        assert_eq!(FrequencyManager::new().volt_tolerance, 0);
+
+        let mut manager = volt_manager(0);
+        manager.replace_freq_volt_tab(HashMap::from([(300000, 50000), (900000, 120000)]));
+        assert!(manager.validate_voltages().is_empty());
+        assert_eq!(manager.read_freq_volt(300000), 50000);
+        assert_eq!(manager.read_freq_volt(900000), 120000);
+    }
+
+    #[test]
+    fn interpolates_gap_in_the_middle() {
+        let mut manager = manager(&[200000, 300000, 400000, 500000, 700000]);
+        manager.replace_freq_volt_tab(HashMap::from([(300000, 70000), (500000, 80000)]));
+        let adjustments = manager.interpolate_missing_volts();
+        assert_eq!(
+            adjustments,
+            vec![VoltAdjustment {
+                freq: 400000,
+                from: 0,
+                to: 75000,
+            }]
+        );
+        assert_eq!(manager.read_freq_volt(400000), 75000);
+        // 超出已知频率范围的两端保持没有电压
+        assert_eq!(manager.read_freq_volt(200000), 0);
+        assert_eq!(manager.read_freq_volt(700000), 0);
+    }
+
+    #[test]
+    fn interpolated_volt_rounds_to_step() {
+        let known = [(300000, 70000), (600000, 80000)];
+        // 线性插值为73333，取整到最接近的625整数倍
+        assert_eq!(interpolate_volt(&known, 400000), Some(73125));
+        assert_eq!(interpolate_volt(&known, 600000), Some(80000));
+        assert_eq!(interpolate_volt(&known, 200000), None);
+        assert_eq!(interpolate_volt(&known, 700000), None);
+        assert_eq!(interpolate_volt(&[], 400000), None);
+    }
+
+    #[test]
+    fn v2_snap_tiebreak_at_halfway() {
+        let mut manager = FrequencyManager::new();
+        // 支持频率列表的顺序不影响结果
+        manager.v2_supported_freqs = vec![600000, 400000, 800000];
+
+        manager.set_v2_snap_tiebreak(V2SnapTiebreak::Up);
+        assert_eq!(manager.get_closest_v2_supported_freq(500000), 600000);
+        assert_eq!(manager.get_closest_v2_supported_freq(700000), 800000);
+
+        manager.set_v2_snap_tiebreak(V2SnapTiebreak::Down);
+        assert_eq!(manager.get_closest_v2_supported_freq(500000), 400000);
+        assert_eq!(manager.get_closest_v2_supported_freq(700000), 600000);
+
+        // 不在中点时取最接近的频率，与选择方式无关
+        assert_eq!(manager.get_closest_v2_supported_freq(520000), 600000);
+        assert_eq!(manager.get_closest_v2_supported_freq(900000), 800000);
+    }
+
+    #[test]
+    fn blocking_top_opp_lowers_max_freq() {
+        let mut manager = manager(&[100000, 200000, 300000, 400000]);
+        manager.set_freq_filter(vec![], vec![400000]);
+        assert_eq!(manager.get_max_freq(), 300000);
+        assert_eq!(manager.get_config_list(), vec![100000, 200000, 300000]);
+        assert_eq!(manager.read_freq_ge(350000), 300000);
+    }
+
+    #[test]
+    fn allow_list_limits_lookups() {
+        let mut manager = manager(&[100000, 200000, 300000, 400000, 500000]);
+        manager.set_freq_filter(vec![200000, 400000], vec![]);
+        assert_eq!(manager.get_config_list(), vec![200000, 400000]);
+
+        // 查找只返回允许的频率，被过滤的300000不会被选中
+        assert_eq!(manager.read_freq_ge(300000), 400000);
+        assert_eq!(manager.read_freq_le(300000), 200000);
+        assert_eq!(manager.read_freq_ge(100000), 200000);
+        assert_eq!(manager.read_freq_le(500000), 400000);
+        assert_eq!(manager.get_min_freq(), 200000);
+        assert_eq!(manager.get_max_freq(), 400000);
+        // 频率表快照中保留全部档位，被过滤的档位没有索引
+        let entries = manager.opp_entries();
+        assert_eq!(entries.len(), 5);
+        assert_eq!(entries[2].index, None);
+        assert_eq!(entries[3].index, Some(1));
+    }
+
+    #[test]
+    fn empty_filter_result_uses_full_table() {
+        let mut manager = manager(&[100000, 200000, 300000]);
+        manager.set_freq_filter(vec![200000], vec![200000]);
+        assert_eq!(manager.get_config_list(), vec![100000, 200000, 300000]);
+        assert_eq!(manager.get_max_freq(), 300000);
+
+        // 清空过滤后恢复全部频率
+        manager.set_freq_filter(vec![300000], vec![]);
+        assert_eq!(manager.get_config_list(), vec![300000]);
+        manager.set_freq_filter(vec![], vec![]);
+        assert_eq!(manager.get_config_list(), vec![100000, 200000, 300000]);
+    }
+
+    #[test]
+    fn read_only_opp_node_enables_monitor_only() {
+        let volt_path = "/test/read-only/volt";
+        let opp_path = "/test/read-only/opp";
+        let read_only = || Some(Err(io::Error::from(io::ErrorKind::PermissionDenied)));
+
+        // 电压节点写入成功不会清零OPP节点的失败次数
+        for _ in 0..READ_ONLY_FAILURE_LIMIT {
+            assert!(FrequencyManager::record_write_result(
+                volt_path,
+                Some(Ok(()))
+            ));
+            assert!(!FrequencyManager::record_write_result(
+                opp_path,
+                read_only()
+            ));
+            assert!(FrequencyManager::record_write_result(
+                volt_path,
+                Some(Ok(()))
+            ));
+        }
+        FrequencyManager::check_read_only_nodes(opp_path);
+        assert!(is_monitor_only());
+        MONITOR_ONLY.store(false, Ordering::SeqCst);
+
+        // OPP节点写入成功后重新计数
+        assert!(FrequencyManager::record_write_result(
+            opp_path,
+            Some(Ok(()))
+        ));
+        assert!(!FrequencyManager::record_write_result(
+            opp_path,
+            read_only()
+        ));
+        FrequencyManager::check_read_only_nodes(opp_path);
+        assert!(!is_monitor_only());
+        READ_ONLY_FAILURES.lock().unwrap().remove(opp_path);
+    }
+
+    // 记录写入的节点和值，内容在failing中的写入返回失败
+    #[derive(Default)]
+    struct RecordingWriter {
+        writes: std::cell::RefCell<Vec<(String, String)>>,
+        failing: Vec<&'static str>,
+    }
+
+    impl NodeWriter for RecordingWriter {
+        fn write(&self, path: &str, content: &str) -> bool {
+            self.writes
+                .borrow_mut()
+                .push((path.to_string(), content.to_string()));
+            !self.failing.contains(&content)
+        }
+    }
+
+    fn record(plan: WritePlan, failing: &[&'static str]) -> Vec<(String, String)> {
+        let writer = RecordingWriter {
+            failing: failing.to_vec(),
+            ..Default::default()
+        };
+        plan.execute(&writer);
+        writer.writes.into_inner()
+    }
+
+    fn writes(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+        expected
+            .iter()
+            .map(|&(path, content)| (path.to_string(), content.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn idle_mode_writes() {
+        let auto = OppResetValue::Auto;
+        assert_eq!(
+            record(WritePlan::idle(false, auto, GPUFREQ_VOLT, GPUFREQ_OPP), &[]),
+            writes(&[(GPUFREQ_VOLT, "0 0"), (GPUFREQ_OPP, "0")])
+        );
+        assert_eq!(
+            record(
+                WritePlan::idle(true, auto, GPUFREQV2_VOLT, GPUFREQV2_OPP),
+                &[]
+            ),
+            writes(&[(GPUFREQV2_VOLT, "0 0"), (GPUFREQV2_OPP, "-1")])
+        );
+        // v2驱动不接受-1时改写0
+        assert_eq!(
+            record(
+                WritePlan::idle(true, auto, GPUFREQV2_VOLT, GPUFREQV2_OPP),
+                &["-1"]
+            ),
+            writes(&[
+                (GPUFREQV2_VOLT, "0 0"),
+                (GPUFREQV2_OPP, "-1"),
+                (GPUFREQV2_OPP, "0")
+            ])
+        );
+    }
+
+    #[test]
+    fn dcs_mode_writes() {
+        let auto = OppResetValue::Auto;
+        assert_eq!(
+            record(WritePlan::dcs(auto, GPUFREQV2_VOLT, GPUFREQV2_OPP), &[]),
+            writes(&[(GPUFREQV2_VOLT, "0 0"), (GPUFREQV2_OPP, "-1")])
+        );
+        assert_eq!(
+            record(WritePlan::dcs(auto, GPUFREQV2_VOLT, GPUFREQV2_OPP), &["-1"]),
+            writes(&[
+                (GPUFREQV2_VOLT, "0 0"),
+                (GPUFREQV2_OPP, "-1"),
+                (GPUFREQV2_OPP, "0")
+            ])
+        );
+        // v1驱动不会进入DCS模式，写入顺序与v2相同
+        assert_eq!(
+            record(WritePlan::dcs(auto, GPUFREQ_VOLT, GPUFREQ_OPP), &[]),
+            writes(&[(GPUFREQ_VOLT, "0 0"), (GPUFREQ_OPP, "-1")])
+        );
+    }
+
+    #[test]
+    fn no_volt_mode_writes() {
+        assert_eq!(
+            record(WritePlan::no_volt(GPUFREQ_VOLT, GPUFREQ_OPP, "850000"), &[]),
+            writes(&[(GPUFREQ_VOLT, "0 0"), (GPUFREQ_OPP, "850000")])
+        );
+        assert_eq!(
+            record(
+                WritePlan::no_volt(GPUFREQV2_VOLT, GPUFREQV2_OPP, "850000"),
+                &[]
+            ),
+            writes(&[(GPUFREQV2_VOLT, "0 0"), (GPUFREQV2_OPP, "850000")])
+        );
+    }
+
+    #[test]
+    fn normal_mode_writes() {
+        let auto = OppResetValue::Auto;
+        assert_eq!(
+            record(
+                WritePlan::normal(false, auto, GPUFREQ_VOLT, GPUFREQ_OPP, "850000 80000"),
+                &[]
+            ),
+            writes(&[(GPUFREQ_OPP, "0"), (GPUFREQ_VOLT, "850000 80000")])
+        );
+        assert_eq!(
+            record(
+                WritePlan::normal(true, auto, GPUFREQV2_VOLT, GPUFREQV2_OPP, "850000 80000"),
+                &[]
+            ),
+            writes(&[
+                (GPUFREQV2_VOLT, "0 0"),
+                (GPUFREQV2_OPP, "-1"),
+                (GPUFREQV2_VOLT, "850000 80000")
+            ])
+        );
+        assert_eq!(
+            record(
+                WritePlan::normal(true, auto, GPUFREQV2_VOLT, GPUFREQV2_OPP, "850000 80000"),
+                &["-1"]
+            ),
+            writes(&[
+                (GPUFREQV2_VOLT, "0 0"),
+                (GPUFREQV2_OPP, "-1"),
+                (GPUFREQV2_OPP, "0"),
+                (GPUFREQV2_VOLT, "850000 80000")
+            ])
+        );
+    }
+}
@@ -1,4 +1,43 @@
-use log::debug;
+use log::{debug, warn};
+use serde::Deserialize;
+
+use crate::{
+    model::{
+        adaptive_sampler::AdaptiveSampler, load_curve::LoadCurve, load_smoother::LoadSmoother,
+        load_trend::LoadTrend, load_window::LoadWindow,
+    },
+    utils::constants::strategy,
+};
+
+/// 调速器类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GovernorType {
+    /// 负载阈值升降频（默认）
+    Threshold,
+    /// 负载-频率曲线插值
+    Curve,
+    /// 按帧时间预算升降频
+    FrameTime,
+}
+
+/// 升频模式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UpscaleMode {
+    /// 每次升频一级（原有行为）
+    Max,
+    /// 直接升到满足负载需求的最低频率
+    Demand,
+}
+
+/// 模式参数 - 配置文件中powersave/balance/performance/fast各模式的调频参数
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ModeParams {
+    pub very_high_load_threshold: i32,
+    pub margin: i64,
+    pub down_threshold: i64,
+    pub aggressive_down: bool,
+    pub sampling_interval: u64,
+}
 
 /// 调频策略配置 - 负责GPU调频的策略和参数管理
 #[derive(Clone)]
@@ -21,17 +60,107 @@ pub struct FrequencyStrategy {
     /// 频率调整策略
     pub aggressive_down: bool, // 是否使用激进降频策略
     pub margin: i64,         // 频率计算的余量百分比
+    pub max_margin: i64,     // 余量上限百分比
     pub up_rate_delay: u64,  // 升频延迟（毫秒）
     pub down_threshold: i64, // 降频阈值
 
     /// 采样相关
     pub sampling_interval: u64, // 采样间隔（毫秒）
-    pub adaptive_sampling: bool,    // 是否启用自适应采样
-    pub min_sampling_interval: u64, // 最小采样间隔（毫秒）
-    pub max_sampling_interval: u64, // 最大采样间隔（毫秒）
+    pub adaptive_sampling: bool,           // 是否启用自适应采样
+    pub min_sampling_interval: u64,        // 最小采样间隔（毫秒）
+    pub max_sampling_interval: u64,        // 最大采样间隔（毫秒）
+    pub adaptive_sampling_window: usize,   // 计算负载方差的采样数，0表示不按方差调整间隔
+    pub adaptive_variance_threshold: f64,  // 负载方差不超过该值时视为稳定
+    pub adaptive_sampler: AdaptiveSampler, // 按负载方差调整采样间隔
 
     /// 时间戳
     pub last_adjustment_time: u64, // 上次频率调整时间（毫秒）
+
+    /// 调速器
+    pub governor: GovernorType, // 调速器类型
+    pub load_curve: LoadCurve,     // 曲线调速器使用的负载-频率曲线
+    pub upscale_mode: UpscaleMode, // 阈值调速器的升频模式
+    pub frame_budget_ms: f64,      // 帧时间调速器的帧时间预算（毫秒）
+
+    /// 频率限制
+    pub background_max_freq: i64, // 后台频率上限（KHz），0表示不限制
+    pub touch_boost_freq: i64,      // 触摸升频目标频率（KHz），0表示禁用
+    pub touch_boost_ms: u64,        // 触摸升频持续时间（毫秒）
+    pub frame_drop_boost_freq: i64, // 掉帧升频目标频率（KHz），0表示禁用
+    pub frame_drop_boost_ms: u64,   // 掉帧升频持续时间（毫秒）
+    pub thermal_zones: Vec<String>, // 温度限频读取的温区，为空表示禁用
+    pub thermal_cap_temp: i32,      // 温度限频阈值（摄氏度）
+    pub thermal_cap_freq: i64,      // 温度限频时的频率上限（KHz），0表示禁用
+    pub thermal_warn_temp: i32,     // 温度预警阈值（摄氏度），超过后限制到中间频率，0表示禁用
+    pub thermal_critical_temp: i32, // 温度临界阈值（摄氏度），超过后限制到最低频率，0表示禁用
+
+    /// 熄屏限频
+    pub screen_state_node: Option<String>, // 屏幕状态节点，None表示自动检测
+
+    /// 电池省电
+    pub battery_saver_threshold: u32, // 放电时电量低于该百分比切换到省电模式，0表示禁用
+    pub battery_saver_max_freq: i64, // 低电量时的频率上限（KHz），0表示不限制
+    pub low_battery: bool,           // 当前是否处于低电量省电状态
+
+    /// 持续高负载轮换
+    pub sustained_opp_set: Vec<i64>, // 持续高负载时轮换的频率集合（KHz），为空表示禁用
+    pub sustained_window_ms: u64, // 判定持续高负载的时间窗口，也是轮换间隔（毫秒）
+    pub high_load_since: Option<u64>, // 本轮高负载开始时间（毫秒）
+
+    /// 降频延迟
+    pub downscale_delay_ms: u64, // 负载持续降低多久后才降频（毫秒），0表示立即降频
+    pub low_load_since: Option<u64>, // 本轮降频需求开始时间（毫秒）
+
+    /// 固定频率
+    pub fixed_freq: i64, // 固定保持的频率（KHz），0表示正常调频
+    pub fixed_freq_hold_idle: bool, // 空闲时是否仍保持固定频率
+
+    /// 空闲降至最低频率
+    pub idle_grace_ms: u64, // 持续空闲多久后降到最低频率（毫秒），0表示空闲时保持当前频率
+    pub idle_since: Option<u64>, // 本轮空闲开始时间（毫秒）
+
+    /// 单次调频步长限制
+    pub max_step_up: u32, // 每次采样最多升高的频率档数，0表示不限制
+    pub max_step_down: u32, // 每次采样最多降低的频率档数，0表示不限制
+
+    /// 预测升频
+    pub predictive_steps: u32, // 负载持续上升时额外升高的频率档数，0表示不预测
+    pub predictive_samples: usize, // 判断负载持续上升使用的采样数
+    pub load_trend: LoadTrend,     // 最近的负载趋势
+
+    /// 配置重新加载延迟
+    pub config_apply_delay_ms: u64, // 配置变化后最多等待多久才应用（毫秒），GPU空闲时提前应用，0表示立即应用
+    pub pending_reload_since: Option<u64>, // 等待应用的配置变化的时间（毫秒）
+
+    /// 持续高负载动态下限
+    pub sustained_floor_rise_secs: u64, // 持续高负载每隔多久抬高一级下限（秒），0表示禁用
+    pub sustained_floor_decay_secs: u64, // 负载降低后每隔多久回落一级下限（秒），0表示立即清除
+    pub sustained_floor_idx: i64,        // 当前动态下限的频率索引
+    pub sustained_floor_rising: bool,    // 当前是否处于高负载阶段
+    pub sustained_floor_since: u64,      // 本阶段或上次调整下限的时间（毫秒）
+
+    /// 负载下限
+    pub min_effective_load: i32, // 调频决策使用的最低负载（百分比），0表示禁用
+
+    /// 负载平均
+    pub load_window_ms: u64, // 负载平均窗口（毫秒），0表示直接使用单次采样
+    pub time_weighted_load: bool,    // 是否按实际采样间隔加权平均
+    pub load_window: LoadWindow,     // 负载平均窗口
+    pub load_stats_window_ms: u64,   // 负载最小/平均/最大统计窗口（毫秒）
+    pub load_ema_alpha: f64,         // 负载指数移动平均中新采样的权重，1.0表示不平滑
+    pub load_smoother: LoadSmoother, // 负载指数移动平均
+
+    /// 前台应用检测
+    pub foreground_poll_ms: u64, // 前台应用读取间隔（毫秒）
+    pub foreground_read_timeout_ms: u64, // 单次读取前台应用的超时（毫秒）
+
+    /// 调试
+    pub decision_trace: bool, // 是否在trace级别输出每次调频决策的追踪
+
+    /// 模式参数
+    pub mode_params: ModeParams, // 配置的模式参数
+    pub unmatched_app_profile: String, // 前台应用没有匹配的应用配置时使用的模式，default表示沿用配置的模式
+    pub unmatched_mode_params: Option<ModeParams>, // 对应的模式参数
 }
 
 impl FrequencyStrategy {
@@ -55,17 +184,113 @@ impl FrequencyStrategy {
             // 简化的频率调整策略
             aggressive_down: true, // 启用激进降频
             margin: 0,             // 无余量
-            up_rate_delay: 0,      // 无升频延迟
-            down_threshold: 1,     // 降频阈值为1
+            max_margin: strategy::MAX_MARGIN,
+            up_rate_delay: 0,  // 无升频延迟
+            down_threshold: 1, // 降频阈值为1
 
             // 固定采样设置 - 120Hz
             sampling_interval: 8,     // 固定8ms采样间隔，约120Hz
             adaptive_sampling: false, // 禁用自适应采样
             min_sampling_interval: 8, // 固定最小采样间隔
             max_sampling_interval: 8, // 固定最大采样间隔
+            adaptive_sampling_window: 0,
+            adaptive_variance_threshold: strategy::ADAPTIVE_VARIANCE_THRESHOLD_DEFAULT,
+            adaptive_sampler: AdaptiveSampler::default(),
 
             // 时间戳默认值
             last_adjustment_time: 0,
+
+            // 默认使用阈值调速器
+            governor: GovernorType::Threshold,
+            load_curve: LoadCurve::default(),
+            upscale_mode: UpscaleMode::Max,
+            frame_budget_ms: strategy::FRAME_BUDGET_DEFAULT_MS,
+
+            // 默认不限制后台频率
+            background_max_freq: 0,
+
+            // 默认禁用触摸升频
+            touch_boost_freq: 0,
+            touch_boost_ms: strategy::TOUCH_BOOST_DEFAULT_MS,
+
+            // 默认禁用掉帧升频
+            frame_drop_boost_freq: 0,
+            frame_drop_boost_ms: strategy::FRAME_DROP_BOOST_DEFAULT_MS,
+
+            // 默认禁用温度限频
+            thermal_zones: Vec::new(),
+            thermal_cap_temp: 0,
+            thermal_cap_freq: 0,
+            thermal_warn_temp: 0,
+            thermal_critical_temp: 0,
+
+            // 默认自动检测屏幕状态节点
+            screen_state_node: None,
+
+            // 默认禁用电池省电
+            battery_saver_threshold: 0,
+            battery_saver_max_freq: 0,
+            low_battery: false,
+
+            // 默认禁用持续高负载轮换
+            sustained_opp_set: Vec::new(),
+            sustained_window_ms: strategy::SUSTAINED_WINDOW_DEFAULT_MS,
+            high_load_since: None,
+
+            // 默认立即降频
+            downscale_delay_ms: 0,
+            low_load_since: None,
+
+            // 默认不固定频率
+            fixed_freq: 0,
+            fixed_freq_hold_idle: false,
+
+            // 默认空闲时保持当前频率
+            idle_grace_ms: 0,
+            idle_since: None,
+
+            // 默认不限制单次调频步长
+            max_step_up: 0,
+            max_step_down: 0,
+
+            // 默认不预测升频
+            predictive_steps: 0,
+            predictive_samples: strategy::PREDICTIVE_SAMPLES_DEFAULT,
+            load_trend: LoadTrend::default(),
+
+            // 默认立即应用重新加载的配置
+            config_apply_delay_ms: 0,
+            pending_reload_since: None,
+
+            // 默认禁用持续高负载动态下限
+            sustained_floor_rise_secs: 0,
+            sustained_floor_decay_secs: 0,
+            sustained_floor_idx: 0,
+            sustained_floor_rising: false,
+            sustained_floor_since: 0,
+
+            // 默认不限制最低负载
+            min_effective_load: 0,
+
+            // 默认不平均负载
+            load_window_ms: 0,
+            time_weighted_load: false,
+            load_window: LoadWindow::default(),
+            load_stats_window_ms: strategy::LOAD_STATS_WINDOW_DEFAULT_MS,
+            load_ema_alpha: strategy::LOAD_EMA_ALPHA_DEFAULT,
+            load_smoother: LoadSmoother::new(strategy::LOAD_EMA_ALPHA_DEFAULT),
+
+            // 前台应用检测默认每秒读取一次
+            foreground_poll_ms: strategy::FOREGROUND_POLL_DEFAULT_MS,
+            foreground_read_timeout_ms: strategy::FOREGROUND_READ_TIMEOUT_DEFAULT_MS,
+
+            // 默认关闭决策追踪
+            decision_trace: false,
+
+            // 默认前台应用没有匹配的应用配置时沿用配置的模式
+            mode_params: ModeParams::default(),
+            unmatched_app_profile: "default".to_string(),
+            unmatched_mode_params: None,
         }
     }
 
@@ -132,6 +357,206 @@ impl FrequencyStrategy {
         );
     }
 
+    /// 设置调速器类型
+    pub fn set_governor(&mut self, governor: GovernorType) {
+        self.governor = governor;
+        debug!("Set governor: {governor:?}");
+    }
+
+    /// 设置负载-频率曲线
+    pub fn set_load_curve(&mut self, load_curve: LoadCurve) {
+        self.load_curve = load_curve;
+        debug!("Set load curve: {:?}", self.load_curve.points());
+    }
+
+    /// 设置升频模式
+    pub fn set_upscale_mode(&mut self, mode: UpscaleMode) {
+        self.upscale_mode = mode;
+        debug!("Set upscale mode: {mode:?}");
+    }
+
+    /// 设置后台频率上限
+    pub fn set_background_max_freq(&mut self, freq: i64) {
+        self.background_max_freq = freq;
+        debug!("Set background max freq: {freq}KHz");
+    }
+
+    /// 设置触摸升频参数
+    pub fn set_touch_boost(&mut self, freq: i64, duration_ms: u64) {
+        self.touch_boost_freq = freq;
+        self.touch_boost_ms = duration_ms;
+        debug!("Set touch boost: {freq}KHz for {duration_ms}ms");
+    }
+
+    /// 设置掉帧升频参数
+    pub fn set_frame_drop_boost(&mut self, freq: i64, duration_ms: u64) {
+        self.frame_drop_boost_freq = freq;
+        self.frame_drop_boost_ms = duration_ms;
+        debug!("Set frame drop boost: {freq}KHz for {duration_ms}ms");
+    }
+
+    /// 设置持续高负载轮换的频率集合
+    pub fn set_sustained_opp_set(&mut self, freqs: Vec<i64>, window_ms: u64) {
+        debug!("Set sustained OPP set: {freqs:?} every {window_ms}ms");
+        self.sustained_opp_set = freqs;
+        self.sustained_window_ms = window_ms;
+        self.high_load_since = None;
+    }
+
+    /// 设置降频延迟
+    pub fn set_downscale_delay(&mut self, delay_ms: u64) {
+        self.downscale_delay_ms = delay_ms;
+        self.low_load_since = None;
+        debug!("Set downscale delay: {delay_ms}ms");
+    }
+
+    /// 设置每次采样最多升高和降低的频率档数，0表示不限制
+    pub fn set_max_steps(&mut self, up: u32, down: u32) {
+        self.max_step_up = up;
+        self.max_step_down = down;
+        debug!("Set max frequency steps: up {up}, down {down}");
+    }
+
+    /// 设置负载持续上升时额外升高的档数（最多2档）和判断上升使用的采样数，档数为0表示不预测
+    pub fn set_predictive_ramp(&mut self, steps: u32, samples: usize) {
+        let steps = steps.min(strategy::PREDICTIVE_STEPS_MAX);
+        let samples = samples.max(2);
+        self.predictive_steps = steps;
+        self.predictive_samples = samples;
+        self.load_trend = LoadTrend::new(if steps > 0 { samples } else { 0 });
+        debug!("Set predictive ramp: {steps} steps after {samples} rising samples");
+    }
+
+    /// 设置并应用配置的模式参数，低电量省电时只记录，恢复时再应用
+    pub fn set_mode_params(&mut self, params: ModeParams) {
+        if !self.low_battery {
+            self.apply_mode_params(&params);
+        }
+        self.mode_params = params;
+    }
+
+    /// 应用模式参数，不改变配置的模式
+    pub fn apply_mode_params(&mut self, params: &ModeParams) {
+        self.very_high_load_threshold = params.very_high_load_threshold;
+        self.set_margin(params.margin);
+        self.set_down_threshold(params.down_threshold);
+        self.set_aggressive_down(params.aggressive_down);
+        self.set_sampling_interval(params.sampling_interval);
+    }
+
+    /// 设置前台应用没有匹配的应用配置时使用的模式
+    pub fn set_unmatched_app_profile(&mut self, name: String, params: Option<ModeParams>) {
+        debug!("Set unmatched app profile: {name}");
+        self.unmatched_app_profile = name;
+        self.unmatched_mode_params = params;
+    }
+
+    /// 设置重新加载配置前的最长等待时间
+    pub fn set_config_apply_delay(&mut self, delay_ms: u64) {
+        self.config_apply_delay_ms = delay_ms;
+        debug!("Set config apply delay: {delay_ms}ms");
+    }
+
+    /// 设置温度限频的温区、阈值和频率上限
+    pub fn set_thermal_cap(&mut self, zones: Vec<String>, cap_temp: i32, cap_freq: i64) {
+        debug!("Set thermal cap: {cap_freq}KHz above {cap_temp}C, zones: {zones:?}");
+        self.thermal_zones = zones;
+        self.thermal_cap_temp = cap_temp;
+        self.thermal_cap_freq = cap_freq;
+    }
+
+    /// 设置温度预警和临界阈值（摄氏度），0表示禁用
+    pub fn set_thermal_levels(&mut self, warn_temp: i32, critical_temp: i32) {
+        self.thermal_warn_temp = warn_temp.max(0);
+        self.thermal_critical_temp = critical_temp.max(0);
+        debug!("Set thermal levels: warn {warn_temp}C, critical {critical_temp}C");
+    }
+
+    /// 设置屏幕状态节点，None表示自动检测
+    pub fn set_screen_state_node(&mut self, node: Option<String>) {
+        debug!("Set screen state node: {node:?}");
+        self.screen_state_node = node;
+    }
+
+    /// 设置电池省电阈值（百分比）和低电量时的频率上限
+    pub fn set_battery_saver(&mut self, threshold: u32, max_freq: i64) {
+        self.battery_saver_threshold = threshold.min(100);
+        self.battery_saver_max_freq = max_freq;
+        debug!("Set battery saver: below {threshold}%, max freq {max_freq}KHz");
+    }
+
+    /// 设置持续高负载动态下限的抬高和回落间隔
+    pub fn set_sustained_floor(&mut self, rise_secs: u64, decay_secs: u64) {
+        self.sustained_floor_rise_secs = rise_secs;
+        self.sustained_floor_decay_secs = decay_secs;
+        self.sustained_floor_idx = 0;
+        debug!("Set sustained floor: rise every {rise_secs}s, decay every {decay_secs}s");
+    }
+
+    /// 设置调频决策使用的最低负载
+    pub fn set_min_effective_load(&mut self, load: i32) {
+        self.min_effective_load = load.clamp(0, 100);
+        debug!("Set min effective load: {}%", self.min_effective_load);
+    }
+
+    /// 设置负载平均窗口
+    pub fn set_load_window(&mut self, window_ms: u64, time_weighted: bool) {
+        self.load_window_ms = window_ms;
+        self.time_weighted_load = time_weighted;
+        self.load_window = LoadWindow::new(window_ms, time_weighted);
+        debug!("Set load window: {window_ms}ms (time weighted: {time_weighted})");
+    }
+
+    /// 设置负载指数移动平均中新采样的权重，超出(0, 1]时截断
+    pub fn set_load_ema_alpha(&mut self, alpha: f64) {
+        let clamped = if alpha.is_finite() {
+            alpha.clamp(0.01, 1.0)
+        } else {
+            strategy::LOAD_EMA_ALPHA_DEFAULT
+        };
+        if clamped != alpha {
+            warn!("Load EMA alpha {alpha} is out of range (0, 1], using {clamped}");
+        }
+        self.load_ema_alpha = clamped;
+        self.load_smoother = LoadSmoother::new(clamped);
+        debug!("Set load EMA alpha: {clamped}");
+    }
+
+    /// 设置负载统计窗口
+    pub fn set_load_stats_window(&mut self, window_ms: u64) {
+        self.load_stats_window_ms = window_ms;
+        debug!("Set load stats window: {window_ms}ms");
+    }
+
+    /// 设置前台应用读取间隔和超时
+    pub fn set_foreground_polling(&mut self, poll_ms: u64, read_timeout_ms: u64) {
+        self.foreground_poll_ms = poll_ms;
+        self.foreground_read_timeout_ms = read_timeout_ms.max(1);
+        debug!(
+            "Set foreground app poll: {poll_ms}ms, read timeout: {}ms",
+            self.foreground_read_timeout_ms
+        );
+    }
+
+    /// 设置帧时间预算
+    pub fn set_frame_budget(&mut self, budget_ms: f64) {
+        if budget_ms > 0.0 {
+            self.frame_budget_ms = budget_ms;
+        } else {
+            warn!(
+                "Invalid frame_budget_ms {budget_ms}, keeping {}ms",
+                self.frame_budget_ms
+            );
+        }
+        debug!("Set frame budget: {}ms", self.frame_budget_ms);
+    }
+
+    /// 设置是否输出调频决策追踪
+    pub fn set_decision_trace(&mut self, enabled: bool) {
+        self.decision_trace = enabled;
+        debug!("Set decision trace: {enabled}");
+    }
+
     /// 更新最后调整时间
     pub fn update_last_adjustment_time(&mut self, time: u64) {
         self.last_adjustment_time = time;
@@ -142,9 +567,24 @@ impl FrequencyStrategy {
         self.margin
     }
 
+    /// 设置余量，所有入口（配置、控制套接字等）都经过这里，超出范围时截断
     pub fn set_margin(&mut self, margin: i64) {
-        self.margin = margin;
-        debug!("Set margin to: {margin}%");
+        let clamped = margin.clamp(0, self.max_margin);
+        if clamped != margin {
+            warn!(
+                "Margin {margin}% is out of range (0..={}%), clamped to {clamped}%",
+                self.max_margin
+            );
+        }
+        self.margin = clamped;
+        debug!("Set margin to: {clamped}%");
+    }
+
+    /// 设置余量上限，并按新上限重新截断当前余量
+    pub fn set_max_margin(&mut self, max_margin: i64) {
+        self.max_margin = max_margin.max(0);
+        debug!("Set max margin to: {}%", self.max_margin);
+        self.set_margin(self.margin);
     }
 
     pub fn set_up_rate_delay(&mut self, up_rate_delay: u64) {
@@ -165,6 +605,47 @@ impl FrequencyStrategy {
         self.sampling_interval
     }
 
+    /// 采样后的睡眠时间，启用自适应采样时使用按负载方差计算的间隔
+    pub fn get_sleep_interval(&self) -> u64 {
+        match self.adaptive_sampler.interval() {
+            interval if self.adaptive_sampling && interval > 0 => interval,
+            _ => self.sampling_interval,
+        }
+    }
+
+    /// 加入一次负载采样，按负载方差在最小和最大采样间隔之间调整采样间隔
+    pub fn update_adaptive_sampling(&mut self, load: i32) {
+        if !self.adaptive_sampling || !self.adaptive_sampler.is_enabled() {
+            return;
+        }
+        let previous = self.adaptive_sampler.interval();
+        let interval = self.adaptive_sampler.push(
+            load,
+            self.min_sampling_interval,
+            self.max_sampling_interval,
+        );
+        if interval != previous {
+            debug!("Adaptive sampling interval: {previous}ms -> {interval}ms");
+        }
+    }
+
+    /// 设置计算负载方差的采样数和方差阈值，采样数为0时不按方差调整间隔
+    pub fn set_adaptive_variance(&mut self, window: usize, threshold: f64) {
+        let threshold = if threshold.is_finite() && threshold >= 0.0 {
+            threshold
+        } else {
+            warn!(
+                "Invalid adaptive variance threshold {threshold}, using {}",
+                strategy::ADAPTIVE_VARIANCE_THRESHOLD_DEFAULT
+            );
+            strategy::ADAPTIVE_VARIANCE_THRESHOLD_DEFAULT
+        };
+        self.adaptive_sampling_window = window;
+        self.adaptive_variance_threshold = threshold;
+        self.adaptive_sampler = AdaptiveSampler::new(window, threshold);
+        debug!("Set adaptive variance: window {window} samples, threshold {threshold}");
+    }
+
     pub fn set_sampling_interval(&mut self, sampling_interval: u64) {
         self.sampling_interval = sampling_interval;
         debug!("Set sampling interval to: {sampling_interval}ms");
@@ -175,6 +656,23 @@ impl FrequencyStrategy {
         debug!("Set load stability threshold to: {load_stability_threshold}");
     }
 
+    /// 设置固定频率，0表示恢复正常调频
+    pub fn set_fixed_freq(&mut self, freq: i64, hold_idle: bool) {
+        self.fixed_freq = freq.max(0);
+        self.fixed_freq_hold_idle = hold_idle;
+        debug!(
+            "Set fixed frequency: {}KHz, hold at idle: {hold_idle}",
+            self.fixed_freq
+        );
+    }
+
+    /// 设置持续空闲多久后降到最低频率，0表示空闲时保持当前频率
+    pub fn set_idle_grace(&mut self, grace_ms: u64) {
+        self.idle_grace_ms = grace_ms;
+        self.idle_since = None;
+        debug!("Set idle grace period: {grace_ms}ms");
+    }
+
     pub fn set_aggressive_down(&mut self, aggressive: bool) {
         self.aggressive_down = aggressive;
         debug!(
@@ -189,3 +687,25 @@ impl Default for FrequencyStrategy {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn margin_is_clamped() {
+        let mut strategy = FrequencyStrategy::new();
+        strategy.set_margin(500);
+        assert_eq!(strategy.get_margin(), strategy::MAX_MARGIN);
+        strategy.set_margin(-5);
+        assert_eq!(strategy.get_margin(), 0);
+        strategy.set_margin(30);
+        assert_eq!(strategy.get_margin(), 30);
+
+        // 降低上限时当前余量按新上限截断
+        strategy.set_max_margin(20);
+        assert_eq!(strategy.get_margin(), 20);
+        strategy.set_margin(25);
+        assert_eq!(strategy.get_margin(), 20);
+    }
+}
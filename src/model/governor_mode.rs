@@ -0,0 +1,47 @@
+use crate::{model::frequency_strategy::ModeParams, utils::constants::strategy};
+
+/// 内置调速模式预设 - 配置文件中没有对应的模式段时使用
+///
+/// | 模式 | 余量 | 降频阈值 | 采样间隔 | 激进降频 |
+/// |------|------|----------|----------|----------|
+/// | Performance | 20% | 3 | 8ms | 否 |
+/// | Balanced | 0% | 1 | 8ms | 是 |
+/// | Powersave | 0% | 1 | 16ms | 是 |
+///
+/// Balanced与未加载配置时的内置默认策略相同
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GovernorMode {
+    /// 性能：按需升频时多留20%余量，关闭激进降频，负载下降后降频更慢
+    Performance,
+    /// 均衡：90%阈值升频，不留余量，负载下降时立即降频
+    Balanced,
+    /// 省电：与均衡相同的阈值，采样间隔加倍，减少唤醒和升频次数
+    Powersave,
+}
+
+impl GovernorMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "performance" => Some(Self::Performance),
+            "balance" | "balanced" => Some(Self::Balanced),
+            "powersave" => Some(Self::Powersave),
+            _ => None,
+        }
+    }
+
+    /// 预设的模式参数
+    pub fn params(self) -> ModeParams {
+        let (margin, down_threshold, sampling_interval, aggressive_down) = match self {
+            Self::Performance => (20, 3, strategy::SAMPLING_INTERVAL_120HZ, false),
+            Self::Balanced => (0, 1, strategy::SAMPLING_INTERVAL_120HZ, true),
+            Self::Powersave => (0, 1, strategy::SAMPLING_INTERVAL_120HZ * 2, true),
+        };
+        ModeParams {
+            very_high_load_threshold: strategy::ULTRA_SIMPLE_THRESHOLD,
+            margin,
+            down_threshold,
+            aggressive_down,
+            sampling_interval,
+        }
+    }
+}
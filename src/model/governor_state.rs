@@ -0,0 +1,78 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicI64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use log::{debug, info};
+use once_cell::sync::Lazy;
+
+// 调速器是否启用，关闭时交还驱动自带的DVFS
+static ENABLED: AtomicBool = AtomicBool::new(true);
+// 运行时是否已通过控制命令设置过启用状态，设置后配置文件不再覆盖
+static RUNTIME_OVERRIDE: AtomicBool = AtomicBool::new(false);
+
+// 调速器接管前驱动的频率（KHz），0表示未读取到
+static BOOT_FREQ: AtomicI64 = AtomicI64::new(0);
+
+// 冷却截止时间，期间固定最低频率，优先级高于所有其他策略
+static COOLDOWN_UNTIL: Lazy<Mutex<Option<Instant>>> = Lazy::new(|| Mutex::new(None));
+
+/// 按配置文件设置启用状态，运行时已设置过时保持运行时的状态
+pub fn apply_config_enabled(enabled: bool) {
+    if RUNTIME_OVERRIDE.load(Ordering::SeqCst) {
+        debug!("Ignoring config enabled={enabled}, runtime state takes precedence");
+        return;
+    }
+    ENABLED.store(enabled, Ordering::SeqCst);
+    debug!("Set governor enabled from config: {enabled}");
+}
+
+/// 运行时启用或禁用调速器
+pub fn set_enabled(enabled: bool) {
+    RUNTIME_OVERRIDE.store(true, Ordering::SeqCst);
+    if ENABLED.swap(enabled, Ordering::SeqCst) != enabled {
+        info!(
+            "Governor {} at runtime",
+            if enabled { "enabled" } else { "disabled" }
+        );
+    }
+}
+
+/// 调速器是否启用
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// 记录调速器接管前驱动的频率
+pub fn set_boot_freq(freq: i64) {
+    BOOT_FREQ.store(freq, Ordering::SeqCst);
+    debug!("Set boot freq: {freq}KHz");
+}
+
+/// 调速器接管前驱动的频率，未读取到时返回None
+pub fn get_boot_freq() -> Option<i64> {
+    Some(BOOT_FREQ.load(Ordering::SeqCst)).filter(|&freq| freq > 0)
+}
+
+/// 开始冷却，重复调用只会延长截止时间，不会叠加
+pub fn start_cooldown(duration: Duration) {
+    let until = Instant::now() + duration;
+    let mut cooldown = COOLDOWN_UNTIL.lock().unwrap();
+    if cooldown.is_none_or(|current| current < until) {
+        *cooldown = Some(until);
+    }
+    info!("Cooldown requested for {}s", duration.as_secs());
+}
+
+/// 冷却剩余时间，不在冷却中时返回None
+pub fn cooldown_remaining() -> Option<Duration> {
+    let mut cooldown = COOLDOWN_UNTIL.lock().unwrap();
+    let remaining = (*cooldown)?.checked_duration_since(Instant::now());
+    if remaining.is_none() {
+        *cooldown = None;
+    }
+    remaining
+}
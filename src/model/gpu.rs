@@ -1,14 +1,22 @@
 use std::collections::HashMap;
 
 use anyhow::Result;
-use log::{debug, warn};
+use log::{debug, info, warn};
 
 use crate::{
-    datasource::file_path::*,
+    datasource::{
+        file_path::*,
+        load_monitor::{
+            invalidate_read_cache, is_precise_load_available, is_precise_load_enabled,
+            reset_precise_load_state, set_precise_load,
+        },
+    },
     model::{
         ddr_manager::DdrManager, frequency_manager::FrequencyManager,
-        frequency_strategy::FrequencyStrategy, idle_manager::IdleManager,
+        frequency_strategy::FrequencyStrategy, governor_mode::GovernorMode,
+        idle_manager::IdleManager, opp_table::OppTable,
     },
+    utils::constants::strategy,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -34,10 +42,16 @@ pub struct GPU {
     /// DCS相关
     pub dcs_enable: bool,
     pub need_dcs: bool,
+    /// 是否根据负载暂停和恢复DCS
+    pub dcs_load_scaling: bool,
+    /// 负载达到该值时暂停DCS
+    pub dcs_high_load: i32,
+    /// 负载降到该值时恢复DCS
+    pub dcs_low_load: i32,
+    /// DCS是否因高负载被暂停
+    pub dcs_load_paused: bool,
     /// 游戏模式
     pub gaming_mode: bool,
-    /// 精确模式
-    pub precise: bool,
 }
 
 impl GPU {
@@ -51,8 +65,11 @@ impl GPU {
             v2_supported_freqs: Vec::new(),
             dcs_enable: false,
             need_dcs: false,
+            dcs_load_scaling: false,
+            dcs_high_load: strategy::DCS_HIGH_LOAD_DEFAULT,
+            dcs_low_load: strategy::DCS_LOW_LOAD_DEFAULT,
+            dcs_load_paused: false,
             gaming_mode: false,
-            precise: false,
         }
     }
 
@@ -107,6 +124,50 @@ impl GPU {
         );
     }
 
+    /// 设置根据负载暂停DCS的开关和高低负载阈值，两个阈值之间的区间作为迟滞
+    pub fn set_dcs_load_scaling(&mut self, enabled: bool, high_load: i32, low_load: i32) {
+        let (high_load, low_load) = (high_load.clamp(0, 100), low_load.clamp(0, 100));
+        if low_load < high_load {
+            self.dcs_high_load = high_load;
+            self.dcs_low_load = low_load;
+        } else {
+            warn!(
+                "Invalid DCS load thresholds (high {high_load}%, low {low_load}%), using defaults"
+            );
+            self.dcs_high_load = strategy::DCS_HIGH_LOAD_DEFAULT;
+            self.dcs_low_load = strategy::DCS_LOW_LOAD_DEFAULT;
+        }
+        self.dcs_load_scaling = enabled;
+        if !enabled && self.dcs_load_paused {
+            self.dcs_load_paused = false;
+            info!("DCS load scaling disabled, DCS resumed");
+        }
+        debug!(
+            "Set DCS load scaling: {enabled} (high {}%, low {}%)",
+            self.dcs_high_load, self.dcs_low_load
+        );
+    }
+
+    /// 根据负载暂停或恢复DCS：高负载时暂停，低负载时恢复，只在v2驱动上生效
+    pub fn update_dcs_for_load(&mut self, load: i32) {
+        if !self.dcs_load_scaling || !self.dcs_enable || !self.gpuv2 || load < 0 {
+            return;
+        }
+
+        if !self.dcs_load_paused && load >= self.dcs_high_load {
+            self.dcs_load_paused = true;
+            info!("GPU load {load}%, pausing DCS");
+        } else if self.dcs_load_paused && load <= self.dcs_low_load {
+            self.dcs_load_paused = false;
+            info!("GPU load {load}%, resuming DCS");
+        }
+    }
+
+    /// DCS当前是否可用：已启用且没有被负载暂停
+    pub fn is_dcs_active(&self) -> bool {
+        self.dcs_enable && !self.dcs_load_paused
+    }
+
     // 游戏模式相关方法
     pub fn is_gaming_mode(&self) -> bool {
         self.gaming_mode
@@ -153,13 +214,32 @@ impl GPU {
         }
     }
 
-    // 精确模式相关方法
+    // 精确模式相关方法 - 精确模式状态由负载监控模块全局保存，所有线程共享
     pub fn is_precise(&self) -> bool {
-        self.precise
+        is_precise_load_enabled()
     }
 
     pub fn set_precise(&mut self, precise: bool) {
-        self.precise = precise;
+        set_precise_load(precise);
+    }
+
+    /// 运行时切换精确模式，在debug_dvfs计数器负载源和普通负载源之间切换
+    pub fn set_precise_runtime(&mut self, precise: bool) {
+        if precise && !is_precise_load_available() {
+            warn!("Precise load source is not available, keeping coarse load sources");
+            return;
+        }
+
+        if precise != self.is_precise() {
+            // 丢弃切换前的计数器和缓存的负载，下一次采样重新开始
+            reset_precise_load_state();
+            invalidate_read_cache();
+            self.set_precise(precise);
+            info!(
+                "Precise mode {} at runtime",
+                if precise { "enabled" } else { "disabled" }
+            );
+        }
     }
 
     /// 读取映射表值 - 使用更简洁的模式匹配
@@ -195,6 +275,15 @@ impl GPU {
         self.v2_supported_freqs = freqs;
     }
 
+    /// 获取频率表快照，包括每个档位的电压和DDR映射、v2支持的频率和当前频率索引
+    pub fn opp_table(&self) -> OppTable {
+        OppTable {
+            cur_freq_idx: self.frequency_manager.cur_freq_idx,
+            v2_supported_freqs: self.v2_supported_freqs.clone(),
+            entries: self.frequency_manager.opp_entries(),
+        }
+    }
+
     /// 检查频率是否被v2驱动支持
     pub fn is_freq_supported_by_v2_driver(&self, freq: i64) -> bool {
         if !self.gpuv2 || self.v2_supported_freqs.is_empty() {
@@ -216,6 +305,15 @@ impl GPU {
         self.frequency_manager.get_middle_freq()
     }
 
+    pub fn get_mid_range_freq(&self) -> i64 {
+        self.frequency_manager.get_mid_range_freq()
+    }
+
+    /// 获取最近一次实际写入节点的频率索引
+    pub fn get_written_freq_index(&self) -> Option<i64> {
+        self.frequency_manager.get_written_freq_index()
+    }
+
     pub fn get_second_highest_freq(&self) -> i64 {
         self.frequency_manager.get_second_highest_freq()
     }
@@ -250,19 +348,11 @@ impl GPU {
         self.frequency_strategy.set_down_threshold(threshold);
     }
 
-    // 批量设置方法 - 减少重复调用
-    pub fn configure_strategy(
-        &mut self,
-        margin: i64,
-        down_threshold: i64,
-        sampling_interval: u64,
-        aggressive_down: bool,
-    ) {
-        let strategy = &mut self.frequency_strategy;
-        strategy.set_margin(margin);
-        strategy.set_down_threshold(down_threshold);
-        strategy.set_sampling_interval(sampling_interval);
-        strategy.set_aggressive_down(aggressive_down);
+    /// 应用内置调速模式预设，并作为配置的模式参数记录下来
+    pub fn apply_mode(&mut self, mode: GovernorMode) {
+        let params = mode.params();
+        debug!("Applying {mode:?} mode preset: {params:?}");
+        self.frequency_strategy.set_mode_params(params);
     }
 
     // 最常用的DDR操作
@@ -0,0 +1,188 @@
+use std::{
+    fmt::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use log::{debug, warn};
+
+use crate::{
+    model::{governor_state::is_enabled, gpu::GPU},
+    utils::{constants::strategy, file_helper::FileHelper, supervisor::alive_threads},
+};
+
+/// 心跳记录 - 调频循环定期写入，外部监控可通过文件修改时间判断调速器是否卡死
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Heartbeat {
+    /// 时间戳（毫秒）
+    pub timestamp_ms: u64,
+    /// 当前频率（KHz）
+    pub cur_freq: i64,
+    /// 最近一次采样的负载
+    pub load: i32,
+    /// 当前运行模式：disabled、gaming或normal
+    pub mode: &'static str,
+    /// 驱动类型：v1或v2
+    pub driver: &'static str,
+    /// 正在运行的监控线程
+    pub threads: Vec<&'static str>,
+}
+
+impl Heartbeat {
+    /// 从GPU状态生成心跳记录
+    pub fn capture(gpu: &GPU, timestamp_ms: u64, load: i32) -> Self {
+        let mode = if !is_enabled() {
+            "disabled"
+        } else if gpu.is_gaming_mode() {
+            "gaming"
+        } else {
+            "normal"
+        };
+        Self {
+            timestamp_ms,
+            cur_freq: gpu.get_cur_freq(),
+            load,
+            mode,
+            driver: if gpu.is_gpuv2() { "v2" } else { "v1" },
+            threads: alive_threads(),
+        }
+    }
+
+    /// 格式化为每行一个key=value
+    pub fn to_key_value(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "timestamp_ms={}", self.timestamp_ms);
+        let _ = writeln!(out, "cur_freq={}", self.cur_freq);
+        let _ = writeln!(out, "load={}", self.load);
+        let _ = writeln!(out, "mode={}", self.mode);
+        let _ = writeln!(out, "driver={}", self.driver);
+        let _ = writeln!(out, "threads={}", self.threads.join(","));
+        out
+    }
+}
+
+struct HeartbeatConfig {
+    // 心跳文件路径，None表示不写入
+    path: Option<String>,
+    interval_ms: u64,
+    // 上次写入的时间（毫秒）
+    last_write_ms: u64,
+}
+
+// 心跳配置
+static HEARTBEAT: Mutex<HeartbeatConfig> = Mutex::new(HeartbeatConfig {
+    path: None,
+    interval_ms: strategy::HEARTBEAT_INTERVAL_DEFAULT_MS,
+    last_write_ms: 0,
+});
+// 未启用时调频循环直接跳过，不需要加锁
+static HEARTBEAT_ENABLED: AtomicBool = AtomicBool::new(false);
+// 心跳文件写入失败是否已警告过，避免每次写入都刷屏
+static HEARTBEAT_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// 设置心跳文件路径和写入间隔（毫秒），路径为None时不写入
+pub fn set_heartbeat(path: Option<String>, interval_ms: u64) {
+    let interval_ms = interval_ms.max(1);
+    debug!("Set heartbeat file: {path:?}, every {interval_ms}ms");
+    HEARTBEAT_ENABLED.store(path.is_some(), Ordering::SeqCst);
+    *HEARTBEAT.lock().unwrap() = HeartbeatConfig {
+        path,
+        interval_ms,
+        last_write_ms: 0,
+    };
+}
+
+/// 获取心跳文件路径和写入间隔
+pub fn get_heartbeat() -> (Option<String>, u64) {
+    let heartbeat = HEARTBEAT.lock().unwrap();
+    (heartbeat.path.clone(), heartbeat.interval_ms)
+}
+
+/// 距上次写入达到间隔时生成心跳记录并写入心跳文件
+pub fn record_heartbeat(now_ms: u64, capture: impl FnOnce() -> Heartbeat) {
+    if !HEARTBEAT_ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    // 锁内只检查间隔，生成和写入记录都在锁外进行
+    let path = {
+        let mut heartbeat = HEARTBEAT.lock().unwrap();
+        if now_ms.saturating_sub(heartbeat.last_write_ms) < heartbeat.interval_ms {
+            return;
+        }
+        heartbeat.last_write_ms = now_ms;
+        let Some(path) = heartbeat.path.clone() else {
+            return;
+        };
+        path
+    };
+
+    match FileHelper::write_atomic(&path, &capture().to_key_value()) {
+        Ok(()) => HEARTBEAT_WARNED.store(false, Ordering::SeqCst),
+        Err(e) if !HEARTBEAT_WARNED.swap(true, Ordering::SeqCst) => warn!("{e}"),
+        Err(e) => debug!("{e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn sample() -> Heartbeat {
+        Heartbeat {
+            timestamp_ms: 1700000000000,
+            cur_freq: 850000,
+            load: 64,
+            mode: "gaming",
+            driver: "v2",
+            threads: vec!["freq_engine", "foreground_app"],
+        }
+    }
+
+    #[test]
+    fn key_value_contains_all_fields() {
+        assert_eq!(
+            sample().to_key_value(),
+            concat!(
+                "timestamp_ms=1700000000000\n",
+                "cur_freq=850000\n",
+                "load=64\n",
+                "mode=gaming\n",
+                "driver=v2\n",
+                "threads=freq_engine,foreground_app\n"
+            )
+        );
+    }
+
+    #[test]
+    fn heartbeat_file_is_written_per_interval() {
+        let path =
+            std::env::temp_dir().join(format!("gpugovernor-{}-heartbeat", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        set_heartbeat(Some(path.clone()), 1000);
+
+        record_heartbeat(5000, sample);
+        assert_eq!(fs::read_to_string(&path).unwrap(), sample().to_key_value());
+        assert!(fs::metadata(format!("{path}.tmp")).is_err());
+
+        // 间隔内不重新写入
+        record_heartbeat(5500, || Heartbeat {
+            load: 10,
+            ..sample()
+        });
+        assert!(fs::read_to_string(&path).unwrap().contains("load=64\n"));
+
+        record_heartbeat(6000, || Heartbeat {
+            load: 10,
+            ..sample()
+        });
+        assert!(fs::read_to_string(&path).unwrap().contains("load=10\n"));
+
+        set_heartbeat(None, strategy::HEARTBEAT_INTERVAL_DEFAULT_MS);
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,46 @@
+/// 负载-频率曲线 - 根据用户给定的控制点分段线性插值目标频率
+#[derive(Clone, Default)]
+pub struct LoadCurve {
+    /// 控制点列表 (负载百分比, 频率KHz)，按负载升序排列
+    points: Vec<(i32, i64)>,
+}
+
+impl LoadCurve {
+    pub fn new(mut points: Vec<(i32, i64)>) -> Self {
+        points.sort_by_key(|&(load, _)| load);
+        points.dedup_by_key(|&mut (load, _)| load);
+        Self { points }
+    }
+
+    pub fn points(&self) -> &[(i32, i64)] {
+        &self.points
+    }
+
+    /// 计算负载对应的目标频率，超出控制点范围时取两端的频率
+    pub fn interpolate(&self, load: i32) -> i64 {
+        let (first, last) = match (self.points.first(), self.points.last()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => return 0,
+        };
+
+        if load <= first.0 {
+            return first.1;
+        }
+        if load >= last.0 {
+            return last.1;
+        }
+
+        // 找到负载所在的区间并线性插值
+        for window in self.points.windows(2) {
+            let (load_lo, freq_lo) = window[0];
+            let (load_hi, freq_hi) = window[1];
+            if load >= load_lo && load <= load_hi {
+                let span = (load_hi - load_lo) as i64;
+                let offset = (load - load_lo) as i64;
+                return freq_lo + (freq_hi - freq_lo) * offset / span;
+            }
+        }
+
+        last.1
+    }
+}
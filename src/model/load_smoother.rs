@@ -0,0 +1,76 @@
+/// 负载指数移动平均 - 新采样按alpha权重与上一次平均值混合
+#[derive(Clone)]
+pub struct LoadSmoother {
+    /// 新采样的权重，1.0表示不平滑
+    alpha: f64,
+    /// 上一次的平均值，尚未采样时为None
+    ema: Option<f64>,
+}
+
+impl Default for LoadSmoother {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl LoadSmoother {
+    pub fn new(alpha: f64) -> Self {
+        Self { alpha, ema: None }
+    }
+
+    /// 加入一个采样并返回平滑后的负载，无效负载（负数）直接返回且不影响平均值
+    pub fn push(&mut self, load: i32) -> i32 {
+        if load < 0 {
+            return load;
+        }
+
+        let sample = load as f64;
+        let ema = match self.ema {
+            Some(prev) => self.alpha * sample + (1.0 - self.alpha) * prev,
+            None => sample,
+        };
+        self.ema = Some(ema);
+        ema.round().clamp(0.0, 100.0) as i32
+    }
+
+    /// 清除平均值，下一次采样重新开始
+    pub fn reset(&mut self) {
+        self.ema = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_input_decays_by_alpha() {
+        let mut smoother = LoadSmoother::new(0.5);
+        assert_eq!(smoother.push(0), 0);
+        let curve: Vec<i32> = (0..5).map(|_| smoother.push(100)).collect();
+        assert_eq!(curve, vec![50, 75, 88, 94, 97]);
+
+        // 阶跃下降按同样的比例衰减
+        let curve: Vec<i32> = (0..3).map(|_| smoother.push(0)).collect();
+        assert_eq!(curve, vec![48, 24, 12]);
+    }
+
+    #[test]
+    fn alpha_one_passes_through() {
+        let mut smoother = LoadSmoother::default();
+        for load in [10, 90, 0, 55] {
+            assert_eq!(smoother.push(load), load);
+        }
+    }
+
+    #[test]
+    fn invalid_load_and_reset() {
+        let mut smoother = LoadSmoother::new(0.5);
+        smoother.push(80);
+        assert_eq!(smoother.push(-1), -1);
+        assert_eq!(smoother.push(40), 60);
+
+        smoother.reset();
+        assert_eq!(smoother.push(40), 40);
+    }
+}
@@ -0,0 +1,46 @@
+use std::collections::VecDeque;
+
+/// 负载趋势 - 记录最近若干次负载，判断负载是否在持续上升
+#[derive(Clone, Default)]
+pub struct LoadTrend {
+    /// 判断趋势使用的采样数
+    samples: usize,
+    /// 最近的负载采样
+    loads: VecDeque<i32>,
+}
+
+impl LoadTrend {
+    pub fn new(samples: usize) -> Self {
+        Self {
+            samples,
+            loads: VecDeque::with_capacity(samples),
+        }
+    }
+
+    /// 加入一个采样，无效负载（负数）不记录
+    pub fn push(&mut self, load: i32) {
+        if self.samples == 0 || load < 0 {
+            return;
+        }
+        self.loads.push_back(load);
+        while self.loads.len() > self.samples {
+            self.loads.pop_front();
+        }
+    }
+
+    /// 最近的采样是否每次都比上一次高，采样不足时返回false
+    pub fn is_rising(&self) -> bool {
+        self.samples >= 2
+            && self.loads.len() == self.samples
+            && self
+                .loads
+                .iter()
+                .zip(self.loads.iter().skip(1))
+                .all(|(prev, next)| next > prev)
+    }
+
+    /// 清除采样，重新开始判断
+    pub fn reset(&mut self) {
+        self.loads.clear();
+    }
+}
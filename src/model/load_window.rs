@@ -0,0 +1,76 @@
+use std::collections::VecDeque;
+
+/// 负载平均窗口 - 对最近一段时间内的负载采样取平均
+#[derive(Clone, Default)]
+pub struct LoadWindow {
+    /// 窗口长度（毫秒），0表示不平均
+    span_ms: u64,
+    /// 是否按实际采样间隔加权
+    time_weighted: bool,
+    /// 采样列表 (负载, 距上次采样的间隔毫秒)
+    samples: VecDeque<(i32, u64)>,
+}
+
+impl LoadWindow {
+    pub fn new(span_ms: u64, time_weighted: bool) -> Self {
+        Self {
+            span_ms,
+            time_weighted,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// 加入一个采样并返回窗口内的平均负载
+    pub fn push(&mut self, load: i32, elapsed_ms: u64) -> i32 {
+        if self.span_ms == 0 {
+            return load;
+        }
+
+        // 间隔至少按1ms计算，避免权重为0
+        self.samples.push_back((load, elapsed_ms.max(1)));
+
+        // 按实际经过的时间淘汰旧采样，至少保留最新的一个
+        let mut total: u64 = self.samples.iter().map(|&(_, elapsed)| elapsed).sum();
+        while self.samples.len() > 1 && total > self.span_ms {
+            if let Some((_, elapsed)) = self.samples.pop_front() {
+                total -= elapsed;
+            }
+        }
+
+        self.average()
+    }
+
+    /// 窗口内的最低负载
+    pub fn min(&self) -> i32 {
+        self.samples
+            .iter()
+            .map(|&(load, _)| load)
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// 窗口内的最高负载
+    pub fn max(&self) -> i32 {
+        self.samples
+            .iter()
+            .map(|&(load, _)| load)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// 计算平均负载：时间加权时采样间隔越长权重越大，否则各采样等权
+    pub fn average(&self) -> i32 {
+        let (sum, weight) =
+            self.samples
+                .iter()
+                .fold((0u64, 0u64), |(sum, weight), &(load, elapsed)| {
+                    let w = if self.time_weighted { elapsed } else { 1 };
+                    (sum + load.max(0) as u64 * w, weight + w)
+                });
+
+        if weight == 0 {
+            return 0;
+        }
+        (sum / weight) as i32
+    }
+}
@@ -1,6 +1,26 @@
+pub mod adaptive_sampler;
+pub mod app_profile;
 pub mod ddr_manager;
+pub mod decision_trace;
+pub mod effective_config;
+pub mod frame_time;
+pub mod freq_limits;
 pub mod frequency_engine;
 pub mod frequency_manager;
 pub mod frequency_strategy;
+pub mod governor_mode;
+pub mod governor_state;
 pub mod gpu;
+pub mod heartbeat;
 pub mod idle_manager;
+pub mod load_curve;
+pub mod load_smoother;
+pub mod load_trend;
+pub mod load_window;
+pub mod opp_table;
+pub mod package_pattern;
+pub mod settings_queue;
+pub mod snapshot;
+pub mod stats;
+pub mod telemetry;
+pub mod volt_offset;
@@ -0,0 +1,82 @@
+use std::sync::Mutex;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::model::gpu::GPU;
+
+/// 频率表中的一个档位
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OppEntry {
+    /// 在可用频率列表中的索引，被allowed_freqs/blocked_freqs过滤掉时为None
+    pub index: Option<i64>,
+    /// 频率（KHz）
+    pub freq: i64,
+    /// 频率表中的电压（经过校验和插值），0表示由驱动决定
+    pub volt: i64,
+    /// 驱动默认OPP表中的电压，驱动表中没有该频率时为None
+    pub def_volt: Option<i64>,
+    /// 映射的DDR_OPP
+    pub ddr_opp: i64,
+}
+
+/// 频率表快照 - 复制调速器当前使用的频率表，读取时不需要持有GPU
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OppTable {
+    /// 当前频率索引
+    pub cur_freq_idx: i64,
+    /// v2驱动支持的频率，v1驱动为空
+    pub v2_supported_freqs: Vec<i64>,
+    /// 频率表中的全部档位，按频率升序排列
+    pub entries: Vec<OppEntry>,
+}
+
+impl OppTable {
+    /// 序列化为TOML
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+}
+
+// 最近一次发布的频率表，频率表加载或重新检测驱动后更新，供控制套接字查询
+static OPP_TABLE: Mutex<Option<OppTable>> = Mutex::new(None);
+
+/// 发布当前的频率表
+pub fn publish_opp_table(gpu: &GPU) {
+    *OPP_TABLE.lock().unwrap() = Some(gpu.opp_table());
+}
+
+/// 获取已发布的频率表，尚未发布时返回None
+pub fn get_opp_table() -> Option<OppTable> {
+    OPP_TABLE.lock().unwrap().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn snapshot_round_trips_through_toml() {
+        let mut gpu = GPU::new();
+        gpu.set_v2_supported_freqs(vec![300000, 500000, 700000]);
+        let frequency = gpu.frequency_mut();
+        frequency.set_config_list(vec![300000, 500000, 700000]);
+        frequency.set_freq_filter(vec![], vec![500000]);
+        frequency.replace_freq_volt_tab(HashMap::from([(300000, 70000), (700000, 85000)]));
+        frequency.set_def_volt(HashMap::from([(300000, 70000)]));
+        frequency.replace_freq_dram_tab(HashMap::from([(700000, 1)]));
+
+        let table = gpu.opp_table();
+        assert_eq!(table.entries.len(), 3);
+        // 被过滤的档位没有索引，驱动表中没有的频率没有默认电压
+        assert_eq!(table.entries[1].index, None);
+        assert_eq!(table.entries[2].index, Some(1));
+        assert_eq!(table.entries[2].def_volt, None);
+
+        let text = table.to_toml().unwrap();
+        let parsed: OppTable = toml::from_str(&text).unwrap();
+        assert_eq!(parsed, table);
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use regex::Regex;
+
+/// 包名匹配规则 - 在加载游戏列表和应用配置时编译一次
+///
+/// - 不含通配符的包名精确匹配（与原有行为一致）
+/// - 含`*`或`?`的按glob匹配，`*`匹配任意字符（包括`.`），`?`匹配一个字符，如`com.miHoYo.*`
+/// - 以`re:`开头的按正则匹配整个包名，如`re:com\.(tencent|netease)\..*`
+#[derive(Clone, Debug)]
+pub enum PackagePattern {
+    Exact(String),
+    Pattern(Regex),
+}
+
+impl Default for PackagePattern {
+    fn default() -> Self {
+        Self::Exact(String::new())
+    }
+}
+
+impl PackagePattern {
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim();
+        let expr = if let Some(expr) = value.strip_prefix("re:") {
+            expr.to_string()
+        } else if value.contains(['*', '?']) {
+            glob_to_regex(value)
+        } else {
+            return Ok(Self::Exact(value.to_string()));
+        };
+
+        let regex = Regex::new(&format!("^(?:{expr})$"))
+            .with_context(|| format!("Invalid package pattern '{value}'"))?;
+        Ok(Self::Pattern(regex))
+    }
+
+    /// 是否为精确包名
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Self::Exact(_))
+    }
+
+    pub fn matches(&self, package: &str) -> bool {
+        match self {
+            Self::Exact(exact) => exact == package,
+            Self::Pattern(regex) => regex.is_match(package),
+        }
+    }
+}
+
+// 将glob转换为正则，除通配符外的字符都按原样匹配
+fn glob_to_regex(glob: &str) -> String {
+    let mut expr = String::with_capacity(glob.len() * 2);
+    for c in glob.chars() {
+        match c {
+            '*' => expr.push_str(".*"),
+            '?' => expr.push('.'),
+            c => expr.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    expr
+}
+
+/// 包名列表 - 精确包名用集合查找，通配符和正则按列出的顺序依次匹配，任意一条匹配即属于列表
+#[derive(Clone, Debug, Default)]
+pub struct PackageList {
+    exact: HashSet<String>,
+    patterns: Vec<PackagePattern>,
+}
+
+impl PackageList {
+    /// 加入一条规则
+    pub fn push(&mut self, pattern: PackagePattern) {
+        match pattern {
+            PackagePattern::Exact(package) => {
+                self.exact.insert(package);
+            }
+            pattern => self.patterns.push(pattern),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.exact.len() + self.patterns.len()
+    }
+
+    pub fn contains(&self, package: &str) -> bool {
+        self.exact.contains(package) || self.patterns.iter().any(|pattern| pattern.matches(package))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(value: &str) -> PackagePattern {
+        PackagePattern::parse(value).unwrap()
+    }
+
+    #[test]
+    fn exact_package() {
+        let exact = pattern(" com.tencent.tmgp.sgame ");
+        assert!(exact.is_exact());
+        assert!(exact.matches("com.tencent.tmgp.sgame"));
+        assert!(!exact.matches("com.tencent.tmgp.sgame2"));
+        assert!(!exact.matches("com.tencent.tmgp"));
+    }
+
+    #[test]
+    fn glob_patterns() {
+        let star = pattern("com.miHoYo.*");
+        assert!(!star.is_exact());
+        assert!(star.matches("com.miHoYo.GenshinImpact"));
+        assert!(star.matches("com.miHoYo.hkrpg.bilibili"));
+        assert!(!star.matches("com.miHoYoX.game"));
+        assert!(!star.matches("cn.com.miHoYo.game"));
+
+        let question = pattern("com.game?.app");
+        assert!(question.matches("com.game1.app"));
+        assert!(!question.matches("com.game.app"));
+        assert!(!question.matches("com.game12.app"));
+    }
+
+    #[test]
+    fn regex_matches_whole_package() {
+        let regex = pattern(r"re:com\.(tencent|netease)\..*");
+        assert!(regex.matches("com.tencent.tmgp.pubgmhd"));
+        assert!(regex.matches("com.netease.party"));
+        assert!(!regex.matches("org.com.tencent.game"));
+
+        let partial = pattern("re:tencent");
+        assert!(partial.matches("tencent"));
+        assert!(!partial.matches("com.tencent.tmgp.sgame"));
+    }
+
+    #[test]
+    fn invalid_regex_is_rejected() {
+        let err = PackagePattern::parse("re:com.(tencent").unwrap_err();
+        assert!(err.to_string().contains("com.(tencent"));
+    }
+
+    #[test]
+    fn package_list_contains() {
+        let mut list = PackageList::default();
+        list.push(pattern("com.tencent.tmgp.sgame"));
+        list.push(pattern("com.miHoYo.*"));
+        assert_eq!(list.len(), 2);
+        assert!(list.contains("com.tencent.tmgp.sgame"));
+        assert!(list.contains("com.miHoYo.Yuanshen"));
+        assert!(!list.contains("com.android.settings"));
+    }
+}
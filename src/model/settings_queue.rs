@@ -0,0 +1,49 @@
+use std::sync::{
+    mpsc::{self, Receiver, Sender},
+    Mutex,
+};
+
+use log::debug;
+use once_cell::sync::Lazy;
+
+use crate::model::governor_mode::GovernorMode;
+
+/// 影响调频策略的设置变更 - 各监控线程只提交变更，由调频循环按提交顺序依次应用
+#[derive(Debug)]
+pub enum SettingsChange {
+    /// 频率表配置文件变化，重新读取频率表
+    ReloadFreqTable,
+    /// 游戏模式切换
+    GameMode(bool),
+    /// 前台应用切换，应用该应用的配置
+    AppProfile(String),
+    /// 运行时启用或禁用调速器
+    Enabled(bool),
+    /// TOML配置文件变化，重新加载策略配置
+    ReloadConfig,
+    /// 外部工具切换到内置调速模式
+    SetMode(GovernorMode),
+    /// 进入或离开低电量省电状态
+    LowBattery(bool),
+    /// 运行时启用或禁用精确负载源
+    Precise(bool),
+}
+
+// 所有线程共用一个队列，保证变更按提交顺序应用
+static SETTINGS_QUEUE: Lazy<(Sender<SettingsChange>, Mutex<Receiver<SettingsChange>>)> =
+    Lazy::new(|| {
+        let (sender, receiver) = mpsc::channel();
+        (sender, Mutex::new(receiver))
+    });
+
+/// 提交一个设置变更
+pub fn submit_settings_change(change: SettingsChange) {
+    debug!("Settings change queued: {change:?}");
+    // 接收端随全局队列一直存在，发送不会失败
+    let _ = SETTINGS_QUEUE.0.send(change);
+}
+
+/// 取出所有待应用的设置变更，按提交顺序排列
+pub fn drain_settings_changes() -> Vec<SettingsChange> {
+    SETTINGS_QUEUE.1.lock().unwrap().try_iter().collect()
+}
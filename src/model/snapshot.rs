@@ -0,0 +1,197 @@
+use std::{
+    fmt::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use log::{debug, warn};
+use once_cell::sync::Lazy;
+
+use crate::{
+    model::{
+        app_profile::get_active_profile, frequency_manager::get_snap_error_stats,
+        governor_state::get_boot_freq, gpu::GPU, load_window::LoadWindow,
+    },
+    utils::file_helper::FileHelper,
+};
+
+/// 调速器状态快照 - 由调频循环每次采样后发布，供其他线程只读访问
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GovernorSnapshot {
+    /// 当前负载
+    pub load: i32,
+    /// 统计窗口内的最低负载
+    pub load_min: i32,
+    /// 统计窗口内的平均负载
+    pub load_avg: i32,
+    /// 统计窗口内的最高负载
+    pub load_max: i32,
+    /// 调速器接管前驱动的频率
+    pub boot_freq: Option<i64>,
+    /// 当前频率
+    pub cur_freq: i64,
+    /// 当前频率索引
+    pub cur_freq_idx: i64,
+    /// 实际写入节点的频率索引（可能因吸附与决策索引不同）
+    pub written_freq_idx: Option<i64>,
+    /// 频率表中的电压
+    pub table_volt: i64,
+    /// 实际写入的电压（经过所有电压调整后）
+    pub applied_volt: i64,
+    /// 是否使用精确负载源
+    pub precise: bool,
+    /// 本次运行中v2频率吸附的次数
+    pub snap_count: u64,
+    /// 本次运行中v2频率吸附的累计偏差（KHz）
+    pub snap_error_khz: u64,
+    /// 当前余量（百分比）
+    pub margin: i64,
+    /// 调速器类型，按配置文件中的写法
+    pub governor: String,
+    /// 当前生效的应用配置包名
+    pub active_profile: Option<String>,
+}
+
+impl GovernorSnapshot {
+    /// 从GPU状态生成快照
+    pub fn capture(gpu: &GPU, load: i32, load_stats: &LoadWindow) -> Self {
+        let frequency = gpu.frequency();
+        let (snap_count, snap_error_khz) = get_snap_error_stats();
+        Self {
+            load,
+            load_min: load_stats.min(),
+            load_avg: load_stats.average(),
+            load_max: load_stats.max(),
+            boot_freq: get_boot_freq(),
+            cur_freq: frequency.cur_freq,
+            cur_freq_idx: frequency.cur_freq_idx,
+            written_freq_idx: gpu.get_written_freq_index(),
+            table_volt: frequency.table_volt,
+            applied_volt: frequency.cur_volt,
+            precise: gpu.is_precise(),
+            snap_count,
+            snap_error_khz,
+            margin: gpu.get_margin(),
+            governor: format!("{:?}", gpu.frequency_strategy.governor).to_lowercase(),
+            active_profile: get_active_profile(),
+        }
+    }
+
+    /// 格式化为每行一个key=value
+    pub fn to_key_value(&self) -> String {
+        let optional = |value: Option<i64>| value.map_or("none".to_string(), |v| v.to_string());
+        let mut out = String::new();
+        let _ = writeln!(out, "load={}", self.load);
+        let _ = writeln!(out, "load_min={}", self.load_min);
+        let _ = writeln!(out, "load_avg={}", self.load_avg);
+        let _ = writeln!(out, "load_max={}", self.load_max);
+        let _ = writeln!(out, "boot_freq={}", optional(self.boot_freq));
+        let _ = writeln!(out, "cur_freq={}", self.cur_freq);
+        let _ = writeln!(out, "cur_freq_idx={}", self.cur_freq_idx);
+        let _ = writeln!(out, "written_freq_idx={}", optional(self.written_freq_idx));
+        let _ = writeln!(out, "table_volt={}", self.table_volt);
+        let _ = writeln!(out, "applied_volt={}", self.applied_volt);
+        let _ = writeln!(out, "precise={}", self.precise);
+        let _ = writeln!(out, "snap_count={}", self.snap_count);
+        let _ = writeln!(out, "snap_error_khz={}", self.snap_error_khz);
+        let _ = writeln!(out, "margin={}", self.margin);
+        let _ = writeln!(out, "governor={}", self.governor);
+        let _ = writeln!(
+            out,
+            "active_profile={}",
+            self.active_profile.as_deref().unwrap_or("none")
+        );
+        out
+    }
+
+    /// 格式化为单行JSON，供外部应用一次读取全部状态
+    pub fn to_json(&self) -> String {
+        let optional = |value: Option<i64>| value.map_or("null".to_string(), |v| v.to_string());
+        format!(
+            concat!(
+                "{{\"freq\":{},\"freq_idx\":{},\"boot_freq\":{},\"load\":{},",
+                "\"load_min\":{},\"load_avg\":{},\"load_max\":{},\"margin\":{},",
+                "\"governor\":{},\"active_profile\":{},\"precise\":{}}}"
+            ),
+            self.cur_freq,
+            self.cur_freq_idx,
+            optional(self.boot_freq),
+            self.load,
+            self.load_min,
+            self.load_avg,
+            self.load_max,
+            self.margin,
+            json_string(&self.governor),
+            self.active_profile
+                .as_deref()
+                .map_or("null".to_string(), json_string),
+            self.precise,
+        )
+    }
+}
+
+/// 转义为JSON字符串
+pub fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// 全局快照
+static SNAPSHOT: Lazy<Mutex<GovernorSnapshot>> =
+    Lazy::new(|| Mutex::new(GovernorSnapshot::default()));
+
+// 状态文件路径，None表示不写入
+static STATUS_FILE: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+// 状态文件写入失败是否已警告过，避免每次采样都刷屏
+static STATUS_FILE_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// 设置状态文件路径，快照变化时写入该文件
+pub fn set_status_file(path: Option<String>) {
+    debug!("Set status file: {path:?}");
+    *STATUS_FILE.lock().unwrap() = path;
+}
+
+/// 获取状态文件路径
+pub fn get_status_file() -> Option<String> {
+    STATUS_FILE.lock().unwrap().clone()
+}
+
+/// 发布最新的快照，配置了状态文件且快照变化时同时更新状态文件
+pub fn publish_snapshot(snapshot: GovernorSnapshot) {
+    // 锁内只更新快照，写入状态文件在锁外进行，读取快照的线程不会等待文件IO
+    {
+        let mut current = SNAPSHOT.lock().unwrap();
+        if *current == snapshot {
+            return;
+        }
+        *current = snapshot.clone();
+    }
+    let Some(path) = get_status_file() else {
+        return;
+    };
+
+    match FileHelper::write_atomic(&path, &snapshot.to_key_value()) {
+        Ok(()) => STATUS_FILE_WARNED.store(false, Ordering::SeqCst),
+        Err(e) if !STATUS_FILE_WARNED.swap(true, Ordering::SeqCst) => warn!("{e}"),
+        Err(e) => debug!("{e}"),
+    }
+}
+
+/// 获取最新的快照
+pub fn get_snapshot() -> GovernorSnapshot {
+    SNAPSHOT.lock().unwrap().clone()
+}
@@ -0,0 +1,136 @@
+use std::{collections::BTreeMap, fmt, sync::Mutex, time::Duration};
+
+use once_cell::sync::Lazy;
+
+// 给出建议所需的最短运行时间
+const MIN_SESSION: Duration = Duration::from_secs(60);
+// 每分钟频率切换次数超过该值时认为切换过于频繁
+const HIGH_CHANGE_RATE_PER_MIN: f64 = 300.0;
+// 最低/最高频率驻留比例超过该值时给出建议
+const MIN_FREQ_RESIDENCY_RATIO: f64 = 0.8;
+const MAX_FREQ_RESIDENCY_RATIO: f64 = 0.5;
+
+/// 运行统计 - 记录各频率的驻留时间和频率切换次数
+#[derive(Clone, Debug)]
+pub struct Stats {
+    /// 各频率的驻留时间
+    pub residency: BTreeMap<i64, Duration>,
+    /// 频率切换次数
+    pub freq_changes: u64,
+}
+
+impl Stats {
+    fn new() -> Self {
+        Self {
+            residency: BTreeMap::new(),
+            freq_changes: 0,
+        }
+    }
+
+    /// 统计覆盖的总时间
+    pub fn total_time(&self) -> Duration {
+        self.residency.values().sum()
+    }
+
+    // 某个频率的驻留比例
+    fn residency_ratio(&self, freq: i64) -> f64 {
+        let total = self.total_time().as_secs_f64();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        self.residency
+            .get(&freq)
+            .map_or(0.0, |time| time.as_secs_f64() / total)
+    }
+}
+
+/// 调参建议
+#[derive(Clone, Debug, PartialEq)]
+pub enum Suggestion {
+    /// 频率切换过于频繁
+    HighChangeRate { per_min: f64 },
+    /// 大部分时间处于最低频率
+    MostlyAtMin { freq: i64, ratio: f64 },
+    /// 大部分时间处于最高频率
+    MostlyAtMax { freq: i64, ratio: f64 },
+}
+
+impl fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Suggestion::HighChangeRate { per_min } => write!(
+                f,
+                "frequency changes are high ({per_min:.0}/min); consider increasing hysteresis, down_threshold or min_write_interval_us"
+            ),
+            Suggestion::MostlyAtMin { freq, ratio } => write!(
+                f,
+                "GPU spent {:.0}% at min ({freq}KHz); consider lowering the minimum frequency",
+                ratio * 100.0
+            ),
+            Suggestion::MostlyAtMax { freq, ratio } => write!(
+                f,
+                "GPU spent {:.0}% at max ({freq}KHz); consider reducing margin or checking for thermal limits",
+                ratio * 100.0
+            ),
+        }
+    }
+}
+
+// 全局统计，由调频循环写入
+static STATS: Lazy<Mutex<Stats>> = Lazy::new(|| Mutex::new(Stats::new()));
+
+/// 记录当前频率持续的时间
+pub fn record_residency(freq: i64, elapsed: Duration) {
+    if freq <= 0 {
+        return;
+    }
+    let mut stats = STATS.lock().unwrap();
+    *stats.residency.entry(freq).or_default() += elapsed;
+}
+
+/// 记录一次频率切换
+pub fn record_freq_change() {
+    STATS.lock().unwrap().freq_changes += 1;
+}
+
+/// 获取统计快照
+pub fn get_stats() -> Stats {
+    STATS.lock().unwrap().clone()
+}
+
+/// 根据统计数据生成调参建议，只给出建议，不自动修改配置
+pub fn suggest_tuning(stats: &Stats) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+
+    let total = stats.total_time();
+    if total < MIN_SESSION {
+        return suggestions;
+    }
+
+    let per_min = stats.freq_changes as f64 / (total.as_secs_f64() / 60.0);
+    if per_min > HIGH_CHANGE_RATE_PER_MIN {
+        suggestions.push(Suggestion::HighChangeRate { per_min });
+    }
+
+    if let Some(&min_freq) = stats.residency.keys().next() {
+        let ratio = stats.residency_ratio(min_freq);
+        if ratio >= MIN_FREQ_RESIDENCY_RATIO {
+            suggestions.push(Suggestion::MostlyAtMin {
+                freq: min_freq,
+                ratio,
+            });
+        }
+    }
+
+    if let Some(&max_freq) = stats.residency.keys().next_back() {
+        let ratio = stats.residency_ratio(max_freq);
+        if stats.residency.len() > 1 && ratio >= MAX_FREQ_RESIDENCY_RATIO {
+            suggestions.push(Suggestion::MostlyAtMax {
+                freq: max_freq,
+                ratio,
+            });
+        }
+    }
+
+    suggestions
+}
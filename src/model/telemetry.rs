@@ -0,0 +1,229 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use anyhow::{Context, Result};
+use log::{debug, warn};
+
+use crate::{model::gpu::GPU, utils::constants::strategy};
+
+/// 遥测记录 - 每行一条JSON，用于分析频率振荡
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TelemetryRecord {
+    /// 时间戳（毫秒）
+    pub timestamp_ms: u64,
+    /// 负载源读取的原始负载
+    pub raw_load: i32,
+    /// 指数平滑后的负载
+    pub smoothed_load: i32,
+    /// 当前频率（KHz）
+    pub freq: i64,
+    /// 当前电压
+    pub volt: i64,
+    /// 当前频率索引
+    pub freq_idx: i64,
+    /// 当前余量（百分比）
+    pub margin: i64,
+}
+
+impl TelemetryRecord {
+    /// 从GPU状态生成记录
+    pub fn capture(gpu: &GPU, timestamp_ms: u64, raw_load: i32, smoothed_load: i32) -> Self {
+        let frequency = gpu.frequency();
+        Self {
+            timestamp_ms,
+            raw_load,
+            smoothed_load,
+            freq: frequency.cur_freq,
+            volt: frequency.cur_volt,
+            freq_idx: frequency.cur_freq_idx,
+            margin: gpu.get_margin(),
+        }
+    }
+
+    /// 格式化为单行JSON
+    pub fn to_json(&self) -> String {
+        format!(
+            concat!(
+                "{{\"ts\":{},\"raw_load\":{},\"smoothed_load\":{},\"freq\":{},",
+                "\"volt\":{},\"freq_idx\":{},\"margin\":{}}}"
+            ),
+            self.timestamp_ms,
+            self.raw_load,
+            self.smoothed_load,
+            self.freq,
+            self.volt,
+            self.freq_idx,
+            self.margin,
+        )
+    }
+}
+
+struct TelemetryConfig {
+    // 遥测文件路径，None表示不写入
+    path: Option<String>,
+    stride: u32,
+    // 距上次写入经过的采样次数
+    samples: u32,
+}
+
+// 遥测配置
+static TELEMETRY: Mutex<TelemetryConfig> = Mutex::new(TelemetryConfig {
+    path: None,
+    stride: strategy::TELEMETRY_STRIDE_DEFAULT,
+    samples: 0,
+});
+// 未启用时调频循环直接跳过，不需要加锁
+static TELEMETRY_ENABLED: AtomicBool = AtomicBool::new(false);
+// 遥测写入失败是否已警告过，避免每次写入都刷屏
+static TELEMETRY_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// 设置遥测文件路径和写入间隔（每多少次采样写入一条），路径为None时关闭遥测
+pub fn set_telemetry(path: Option<String>, stride: u32) {
+    let stride = stride.max(1);
+    debug!("Set telemetry file: {path:?}, every {stride} samples");
+    TELEMETRY_ENABLED.store(path.is_some(), Ordering::SeqCst);
+    *TELEMETRY.lock().unwrap() = TelemetryConfig {
+        path,
+        stride,
+        samples: 0,
+    };
+}
+
+/// 获取遥测文件路径和写入间隔
+pub fn get_telemetry() -> (Option<String>, u32) {
+    let telemetry = TELEMETRY.lock().unwrap();
+    (telemetry.path.clone(), telemetry.stride)
+}
+
+// 每次打开文件追加一行，不长期占用文件
+fn append_line(path: &str, line: &str) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)
+        .with_context(|| format!("Failed to open telemetry file: {path}"))?;
+    writeln!(file, "{line}").with_context(|| format!("Failed to write telemetry file: {path}"))?;
+    Ok(())
+}
+
+/// 记录一次采样，达到写入间隔时生成记录并追加到遥测文件
+pub fn record_telemetry(capture: impl FnOnce() -> TelemetryRecord) {
+    if !TELEMETRY_ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    // 锁内只计数，生成和写入记录都在锁外进行
+    let path = {
+        let mut telemetry = TELEMETRY.lock().unwrap();
+        telemetry.samples += 1;
+        if telemetry.samples < telemetry.stride {
+            return;
+        }
+        telemetry.samples = 0;
+        let Some(path) = telemetry.path.clone() else {
+            return;
+        };
+        path
+    };
+
+    match append_line(&path, &capture().to_json()) {
+        Ok(()) => TELEMETRY_WARNED.store(false, Ordering::SeqCst),
+        Err(e) if !TELEMETRY_WARNED.swap(true, Ordering::SeqCst) => warn!("{e}"),
+        Err(e) => debug!("{e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_json_schema() {
+        let record = TelemetryRecord {
+            timestamp_ms: 1700000000123,
+            raw_load: 87,
+            smoothed_load: 80,
+            freq: 850000,
+            volt: 80000,
+            freq_idx: 12,
+            margin: 20,
+        };
+        assert_eq!(
+            record.to_json(),
+            concat!(
+                "{\"ts\":1700000000123,\"raw_load\":87,\"smoothed_load\":80,",
+                "\"freq\":850000,\"volt\":80000,\"freq_idx\":12,\"margin\":20}"
+            )
+        );
+    }
+
+    // 解析to_json输出的单行JSON（只包含整数值）
+    fn parse_json(line: &str) -> TelemetryRecord {
+        let fields: std::collections::HashMap<&str, i64> = line
+            .strip_prefix('{')
+            .and_then(|line| line.strip_suffix('}'))
+            .unwrap()
+            .split(',')
+            .map(|field| {
+                let (key, value) = field.split_once(':').unwrap();
+                (key.trim_matches('"'), value.parse().unwrap())
+            })
+            .collect();
+        assert_eq!(fields.len(), 7);
+        TelemetryRecord {
+            timestamp_ms: fields["ts"] as u64,
+            raw_load: fields["raw_load"] as i32,
+            smoothed_load: fields["smoothed_load"] as i32,
+            freq: fields["freq"],
+            volt: fields["volt"],
+            freq_idx: fields["freq_idx"],
+            margin: fields["margin"],
+        }
+    }
+
+    #[test]
+    fn record_json_round_trip() {
+        let record = TelemetryRecord {
+            timestamp_ms: 42,
+            raw_load: 55,
+            smoothed_load: 50,
+            freq: 650000,
+            volt: 75000,
+            freq_idx: 7,
+            margin: -15,
+        };
+        assert_eq!(parse_json(&record.to_json()), record);
+    }
+
+    #[test]
+    fn record_appends_one_line_per_stride() {
+        let path = std::env::temp_dir().join(format!(
+            "gpugovernor-{}-telemetry.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        set_telemetry(Some(path.to_string_lossy().into_owned()), 2);
+
+        for load in [10, 20, 30, 40, 50] {
+            record_telemetry(|| TelemetryRecord {
+                raw_load: load,
+                ..Default::default()
+            });
+        }
+        set_telemetry(None, strategy::TELEMETRY_STRIDE_DEFAULT);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let loads: Vec<&str> = content
+            .lines()
+            .map(|line| line.split(',').nth(1).unwrap())
+            .collect();
+        assert_eq!(loads, vec!["\"raw_load\":20", "\"raw_load\":40"]);
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{anyhow, Result};
+
+/// 电压偏移 - 在频率表电压的基础上整体或按频率加减（单位与频率表电压相同），结果不低于安全下限
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VoltOffset {
+    /// 全局偏移，负值表示降压
+    global: i64,
+    /// 按频率（KHz）覆盖的偏移，优先于全局偏移
+    per_freq: BTreeMap<i64, i64>,
+    /// 电压绝对下限，0表示不限制
+    min_volt: i64,
+    /// 最多比频率表电压低多少
+    max_undervolt: i64,
+}
+
+impl VoltOffset {
+    pub fn new(global: i64, per_freq: Vec<(i64, i64)>, min_volt: i64, max_undervolt: i64) -> Self {
+        Self {
+            global,
+            per_freq: per_freq.into_iter().collect(),
+            min_volt: min_volt.max(0),
+            max_undervolt: max_undervolt.max(0),
+        }
+    }
+
+    /// 是否没有任何偏移
+    pub fn is_empty(&self) -> bool {
+        self.global == 0 && self.per_freq.values().all(|&offset| offset == 0)
+    }
+
+    pub fn global(&self) -> i64 {
+        self.global
+    }
+
+    pub fn per_freq(&self) -> &BTreeMap<i64, i64> {
+        &self.per_freq
+    }
+
+    pub fn min_volt(&self) -> i64 {
+        self.min_volt
+    }
+
+    pub fn max_undervolt(&self) -> i64 {
+        self.max_undervolt
+    }
+
+    /// 获取频率使用的偏移
+    pub fn offset_for(&self, freq: i64) -> i64 {
+        self.per_freq.get(&freq).copied().unwrap_or(self.global)
+    }
+
+    /// 计算偏移后的电压，不低于min_volt和频率表电压减去max_undervolt中的较大者
+    /// 频率表电压为0表示不写入电压，保持为0
+    pub fn apply(&self, freq: i64, table_volt: i64) -> i64 {
+        if table_volt <= 0 {
+            return table_volt;
+        }
+
+        let floor = (table_volt - self.max_undervolt).max(self.min_volt);
+        let volt = (table_volt + self.offset_for(freq)).max(floor);
+        // 电压为0会被当作不写入电压，偏移结果无效时使用频率表电压
+        if volt <= 0 {
+            table_volt
+        } else {
+            volt
+        }
+    }
+
+    /// 检查偏移不会把频率表中的任何电压降到0或以下
+    pub fn validate(&self, freq_volt: &HashMap<i64, i64>) -> Result<()> {
+        let mut table: Vec<_> = freq_volt.iter().collect();
+        table.sort();
+        for (&freq, &table_volt) in table {
+            if table_volt <= 0 {
+                continue;
+            }
+            let offset = self.offset_for(freq);
+            if table_volt + offset <= 0 {
+                return Err(anyhow!(
+                    "Voltage offset {offset} would drop {freq}KHz voltage {table_volt} to {}",
+                    table_volt + offset
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_is_clamped() {
+        let offset = VoltOffset::new(-5000, vec![(900000, -20000)], 60000, 10000);
+        // 全局偏移在限制内
+        assert_eq!(offset.apply(500000, 80000), 75000);
+        // 按频率偏移超过最大降压幅度，限制为频率表电压减去max_undervolt
+        assert_eq!(offset.apply(900000, 80000), 70000);
+        // 不低于电压绝对下限
+        assert_eq!(offset.apply(500000, 62000), 60000);
+        // 频率表电压为0时不写入电压
+        assert_eq!(offset.apply(500000, 0), 0);
+    }
+
+    #[test]
+    fn validate_rejects_non_positive_volts() {
+        let table = HashMap::from([(500000, 4000), (900000, 80000)]);
+        assert!(VoltOffset::new(-3000, vec![], 0, 0)
+            .validate(&table)
+            .is_ok());
+        assert!(VoltOffset::new(-4000, vec![], 0, 0)
+            .validate(&table)
+            .is_err());
+        assert!(VoltOffset::new(0, vec![(500000, -5000)], 0, 0)
+            .validate(&table)
+            .is_err());
+    }
+}
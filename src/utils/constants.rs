@@ -10,6 +10,52 @@ pub const VERSION: &str = "Version: v2.8.0";
 pub mod strategy {
     pub const ULTRA_SIMPLE_THRESHOLD: i32 = 90;
     pub const IDLE_THRESHOLD: i32 = 5;
+    pub const MAX_MARGIN: i64 = 100; // 默认余量上限（百分比）
     pub const SAMPLING_INTERVAL_120HZ: u64 = 8; // 8ms = ~120Hz
     pub const FOREGROUND_APP_STARTUP_DELAY: u64 = 60; // seconds
+    pub const TOUCH_BOOST_DEFAULT_MS: u64 = 200; // 触摸升频默认持续时间
+    pub const FRAME_DROP_BOOST_DEFAULT_MS: u64 = 100; // 掉帧升频默认持续时间
+    pub const SUSTAINED_WINDOW_DEFAULT_MS: u64 = 3000; // 持续高负载判定窗口默认值
+    pub const FOREGROUND_POLL_DEFAULT_MS: u64 = 1000; // 前台应用默认读取间隔
+    pub const FOREGROUND_READ_TIMEOUT_DEFAULT_MS: u64 = 500; // 前台应用读取默认超时
+    pub const LAUNCH_BOOST_DEFAULT_MS: u64 = 3000; // 应用启动升频默认持续时间
+    pub const LOAD_STATS_WINDOW_DEFAULT_MS: u64 = 5000; // 负载统计默认窗口
+    pub const FRAME_BUDGET_DEFAULT_MS: f64 = 16.6; // 默认帧时间预算（60fps）
+    pub const FRAME_TIME_UP_RATIO: f64 = 0.9; // 帧时间达到预算的90%时升频
+    pub const FRAME_TIME_DOWN_RATIO: f64 = 0.7; // 帧时间低于预算的70%时降频
+    pub const FRAME_TIME_MAX_AGE_MS: u64 = 1000; // 帧时间超过该时间未更新则回退到负载阈值
+    pub const V2_SNAP_WARN_KHZ: i64 = 50000; // v2频率吸附偏差超过该值（KHz）时警告
+    pub const LOAD_EMA_ALPHA_DEFAULT: f64 = 0.5; // 负载指数移动平均中新采样的权重
+    pub const RESUME_GAP_MS: u64 = 3000; // 两次采样的墙钟间隔超过该值时视为从挂起中恢复
+    pub const LOAD_REPROBE_MS: u64 = 5000; // 所有负载源失效时重新检测负载节点的间隔
+    pub const MAX_LOG_LINES_PER_SEC_DEFAULT: u32 = 200; // 每秒最多写入的日志行数，0表示不限制
+    pub const FLOOR_BOOST_MAX_MS: u64 = 5000; // 外部请求的临时下限最长持续时间
+    pub const THERMAL_POLL_MS: u64 = 1000; // 温区温度读取间隔
+    pub const THERMAL_CAP_HYSTERESIS_C: i32 = 2; // 温度降到阈值以下多少摄氏度后解除限频
+    pub const BATTERY_POLL_MS: u64 = 10000; // 电池状态读取间隔
+    pub const CONFIG_RELOAD_DEBOUNCE_MS: u64 = 300; // TOML配置变化停止多久后重新加载
+    pub const TELEMETRY_STRIDE_DEFAULT: u32 = 10; // 遥测默认每多少次采样写入一条
+    pub const SCREEN_POLL_MS: u64 = 500; // 屏幕状态读取间隔
+    pub const LOG_MAX_SIZE_KB_DEFAULT: u64 = 8192; // 日志文件超过该大小（KB）时轮转
+    pub const LOG_KEEP_FILES_DEFAULT: u32 = 1; // 默认保留的轮转日志文件数
+    pub const LOG_ROTATION_CHECK_MS: u64 = 60000; // 日志轮转检查间隔
+    pub const SUPERVISOR_POLL_MS: u64 = 1000; // 检查监控线程是否退出的间隔
+    pub const SUPERVISOR_BACKOFF_BASE_MS: u64 = 1000; // 监控线程首次重启前的等待时间
+    pub const SUPERVISOR_BACKOFF_MAX_MS: u64 = 60000; // 监控线程重启等待时间上限
+    pub const SUPERVISOR_RESTART_WINDOW_MS: u64 = 300000; // 统计重启次数的时间窗口
+    pub const SUPERVISOR_RESTART_ESCALATE_COUNT: u32 = 3; // 窗口内重启达到该次数时升级为错误日志
+    pub const WRITE_FAILURE_BACKOFF_THRESHOLD: u32 = 5; // 节点连续写入失败多少次后开始退避
+    pub const WRITE_BACKOFF_BASE_MS: u64 = 1000; // 节点写入首次退避时间
+    pub const WRITE_BACKOFF_MAX_MS: u64 = 60000; // 节点写入退避时间上限
+    pub const MAX_UNDERVOLT_DEFAULT: i64 = 5000; // 电压偏移后最多比频率表电压低多少（频率表电压单位）
+    pub const ADAPTIVE_VARIANCE_THRESHOLD_DEFAULT: f64 = 25.0; // 负载方差不超过该值时延长采样间隔
+    pub const PREDICTIVE_SAMPLES_DEFAULT: usize = 3; // 负载连续上升多少次采样后预测升频
+    pub const PREDICTIVE_STEPS_MAX: u32 = 2; // 预测升频最多额外升高的档数
+    pub const HEARTBEAT_INTERVAL_DEFAULT_MS: u64 = 5000; // 心跳文件默认写入间隔
+    pub const VOLT_TOLERANCE_DEFAULT: i64 = 0; // 频率表电压与驱动默认电压最多相差多少（频率表电压单位），0表示不校验，需在配置中开启
+    pub const VOLT_STEP: i64 = 625; // 电压必须是该值的整数倍
+    pub const DDR_HIGH_LOAD_DEFAULT: i32 = 80; // GPU负载达到该值时提高DDR频率
+    pub const DDR_LOW_LOAD_DEFAULT: i32 = 40; // GPU负载降到该值时恢复DDR自动模式
+    pub const DCS_HIGH_LOAD_DEFAULT: i32 = 85; // GPU负载达到该值时暂停DCS
+    pub const DCS_LOW_LOAD_DEFAULT: i32 = 30; // GPU负载降到该值时恢复DCS
 }
@@ -0,0 +1,147 @@
+use std::{
+    io,
+    os::unix::io::RawFd,
+    sync::atomic::{AtomicU8, Ordering},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use log::{debug, info, warn};
+
+/// 监控线程的运行模式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventLoopMode {
+    /// 每个监控使用独立线程（默认）
+    Threaded,
+    /// 除控制套接字外的监控共用一个poll循环
+    Single,
+}
+
+impl EventLoopMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "threaded" => Some(Self::Threaded),
+            "single" => Some(Self::Single),
+            _ => None,
+        }
+    }
+}
+
+// 全局监控运行模式，在启动监控线程前由配置设置
+static EVENT_LOOP_MODE: AtomicU8 = AtomicU8::new(EventLoopMode::Threaded as u8);
+
+/// 设置监控运行模式
+pub fn set_event_loop_mode(mode: EventLoopMode) {
+    EVENT_LOOP_MODE.store(mode as u8, Ordering::SeqCst);
+    debug!("Set event loop mode: {mode:?}");
+}
+
+/// 获取监控运行模式
+pub fn get_event_loop_mode() -> EventLoopMode {
+    match EVENT_LOOP_MODE.load(Ordering::SeqCst) {
+        x if x == EventLoopMode::Single as u8 => EventLoopMode::Single,
+        _ => EventLoopMode::Threaded,
+    }
+}
+
+/// 可被单线程事件循环驱动的监控处理器
+pub trait PollHandler: Send {
+    /// 处理器名称，用于日志
+    fn name(&self) -> &'static str;
+
+    /// 需要监听可读事件的文件描述符
+    fn poll_fd(&self) -> Option<RawFd> {
+        None
+    }
+
+    /// 文件描述符可读时调用
+    fn on_readable(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// 定时器间隔，None表示不需要定时器
+    fn tick_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// 定时器到期时调用
+    fn on_tick(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// 单线程事件循环：用一次poll复用所有处理器的文件描述符和定时器
+///
+/// 处理器的文件描述符出错或挂起时返回错误，由守护线程重新启动事件循环并重新创建所有处理器
+pub fn run_event_loop(mut handlers: Vec<Box<dyn PollHandler>>) -> Result<()> {
+    let names: Vec<&str> = handlers.iter().map(|h| h.name()).collect();
+    info!("Single-threaded event loop started with handlers: {names:?}");
+
+    let start = Instant::now();
+    let mut deadlines: Vec<Option<Instant>> = handlers
+        .iter()
+        .map(|h| h.tick_interval().map(|interval| start + interval))
+        .collect();
+
+    loop {
+        let mut poll_fds = Vec::new();
+        let mut owners = Vec::new();
+        for (i, handler) in handlers.iter().enumerate() {
+            if let Some(fd) = handler.poll_fd() {
+                poll_fds.push(libc::pollfd {
+                    fd,
+                    events: libc::POLLIN,
+                    revents: 0,
+                });
+                owners.push(i);
+            }
+        }
+
+        // 等待到最近的定时器到期，没有定时器时无限等待
+        let now = Instant::now();
+        let timeout_ms = deadlines
+            .iter()
+            .flatten()
+            .map(|deadline| deadline.saturating_duration_since(now).as_millis() as i32)
+            .min()
+            .unwrap_or(-1);
+
+        let ret = unsafe {
+            libc::poll(
+                poll_fds.as_mut_ptr(),
+                poll_fds.len() as libc::nfds_t,
+                timeout_ms,
+            )
+        };
+        if ret < 0 {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(err.into());
+        }
+
+        for (poll_fd, &i) in poll_fds.iter().zip(&owners) {
+            if poll_fd.revents & libc::POLLIN != 0 {
+                if let Err(e) = handlers[i].on_readable() {
+                    warn!("{} handler error: {e}", handlers[i].name());
+                }
+            } else if poll_fd.revents & (libc::POLLERR | libc::POLLHUP | libc::POLLNVAL) != 0 {
+                return Err(anyhow!(
+                    "{} handler fd is no longer pollable",
+                    handlers[i].name()
+                ));
+            }
+        }
+
+        let now = Instant::now();
+        for (handler, deadline) in handlers.iter_mut().zip(deadlines.iter_mut()) {
+            if deadline.is_some_and(|d| d <= now) {
+                if let Err(e) = handler.on_tick() {
+                    warn!("{} handler error: {e}", handler.name());
+                }
+                *deadline = handler.tick_interval().map(|interval| now + interval);
+            }
+        }
+    }
+}
@@ -1,6 +1,39 @@
-use log::debug;
+use anyhow::{Context, Result};
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::utils::constants::strategy;
+
+// 单个节点的连续写入失败记录
+#[derive(Default)]
+struct NodeWriteFailures {
+    consecutive: u32,
+    // 退避结束时间，退避期间跳过写入
+    retry_at: Option<Instant>,
+}
+
+impl NodeWriteFailures {
+    // 连续失败达到阈值后开始退避，之后每多失败一次退避时间翻倍，不超过上限
+    fn backoff(&self) -> Option<Duration> {
+        let extra = self
+            .consecutive
+            .checked_sub(strategy::WRITE_FAILURE_BACKOFF_THRESHOLD)?;
+        let backoff_ms = strategy::WRITE_BACKOFF_BASE_MS
+            .saturating_mul(1u64 << extra.min(16))
+            .min(strategy::WRITE_BACKOFF_MAX_MS);
+        Some(Duration::from_millis(backoff_ms))
+    }
+}
+
+// 按节点路径记录的写入失败，所有写入共享
+static WRITE_FAILURES: Lazy<Mutex<HashMap<PathBuf, NodeWriteFailures>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
 
 /// 改进的文件操作辅助工具
 /// 提供统一的文件读写接口，减少重复代码
@@ -10,17 +43,168 @@ pub struct FileHelper;
 impl FileHelper {
     /// 尝试写入文件，失败时只记录调试信息，不终止程序
     pub fn write_string_safe<P: AsRef<Path>>(path: P, content: &str) -> bool {
+        matches!(Self::write_with_backoff(path, content), Some(Ok(())))
+    }
+
+    /// 先写入临时文件再重命名，读取方不会读到写了一半的内容
+    pub fn write_atomic<P: AsRef<Path>>(path: P, content: &str) -> Result<()> {
+        let path = path.as_ref();
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        fs::write(&temp_path, content)
+            .with_context(|| format!("Failed to write {}", Path::new(&temp_path).display()))?;
+        fs::rename(&temp_path, path)
+            .with_context(|| format!("Failed to rename to {}", path.display()))?;
+        Ok(())
+    }
+
+    /// 写入文件并记录节点的连续失败次数，连续失败过多时按指数退避暂停写入该节点
+    ///
+    /// 节点处于退避期间时不写入并返回None，写入成功后退避立即重置
+    pub fn write_with_backoff<P: AsRef<Path>>(path: P, content: &str) -> Option<io::Result<()>> {
         let path = path.as_ref();
-        match fs::write(path, content) {
-            Ok(_) => true,
+        let now = Instant::now();
+        if WRITE_FAILURES
+            .lock()
+            .unwrap()
+            .get(path)
+            .and_then(|failures| failures.retry_at)
+            .is_some_and(|retry_at| now < retry_at)
+        {
+            return None;
+        }
+
+        let result = fs::write(path, content);
+        let mut write_failures = WRITE_FAILURES.lock().unwrap();
+        match &result {
+            Ok(()) => {
+                if let Some(failures) = write_failures.remove(path) {
+                    if failures.retry_at.is_some() {
+                        info!(
+                            "Writing {} recovered after {} failures",
+                            path.display(),
+                            failures.consecutive
+                        );
+                    }
+                }
+            }
             Err(e) => {
-                debug!(
-                    "Failed to write file: {} - Error: {} (continuing execution)",
-                    path.display(),
-                    e
-                );
-                false
+                let failures = write_failures.entry(path.to_path_buf()).or_default();
+                failures.consecutive += 1;
+                match failures.backoff() {
+                    // 进入退避时只警告一次，之后的失败只记录调试信息
+                    Some(backoff) if failures.retry_at.is_none() => {
+                        warn!(
+                            "Writing {} failed {} times in a row ({e}), backing off for {}ms",
+                            path.display(),
+                            failures.consecutive,
+                            backoff.as_millis()
+                        );
+                        failures.retry_at = Some(now + backoff);
+                    }
+                    Some(backoff) => {
+                        debug!(
+                            "Writing {} still failing ({e}), backing off for {}ms",
+                            path.display(),
+                            backoff.as_millis()
+                        );
+                        failures.retry_at = Some(now + backoff);
+                    }
+                    None => debug!(
+                        "Failed to write file: {} - Error: {} (continuing execution)",
+                        path.display(),
+                        e
+                    ),
+                }
             }
         }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_grows_with_failures() {
+        let backoff = |consecutive| {
+            NodeWriteFailures {
+                consecutive,
+                retry_at: None,
+            }
+            .backoff()
+        };
+        let threshold = strategy::WRITE_FAILURE_BACKOFF_THRESHOLD;
+        assert_eq!(backoff(threshold - 1), None);
+        assert_eq!(
+            backoff(threshold),
+            Some(Duration::from_millis(strategy::WRITE_BACKOFF_BASE_MS))
+        );
+        assert_eq!(
+            backoff(threshold + 1),
+            Some(Duration::from_millis(strategy::WRITE_BACKOFF_BASE_MS * 2))
+        );
+        assert_eq!(
+            backoff(threshold + 40),
+            Some(Duration::from_millis(strategy::WRITE_BACKOFF_MAX_MS))
+        );
+    }
+
+    #[test]
+    fn failing_path_backs_off() {
+        let path = std::env::temp_dir()
+            .join(format!("gpugovernor-{}-missing-dir", std::process::id()))
+            .join("node");
+        let retry_in = || {
+            WRITE_FAILURES
+                .lock()
+                .unwrap()
+                .get(&path)
+                .and_then(|failures| failures.retry_at)
+                .map(|retry_at| retry_at.saturating_duration_since(Instant::now()))
+        };
+
+        for _ in 0..strategy::WRITE_FAILURE_BACKOFF_THRESHOLD {
+            assert!(matches!(
+                FileHelper::write_with_backoff(&path, "1"),
+                Some(Err(_))
+            ));
+        }
+        // 达到阈值后进入退避，退避期间跳过写入
+        let first = retry_in().unwrap();
+        assert!(first > Duration::ZERO);
+        assert!(FileHelper::write_with_backoff(&path, "1").is_none());
+
+        // 退避结束后再次失败，退避时间翻倍
+        WRITE_FAILURES
+            .lock()
+            .unwrap()
+            .get_mut(&path)
+            .unwrap()
+            .retry_at = Some(Instant::now());
+        assert!(matches!(
+            FileHelper::write_with_backoff(&path, "1"),
+            Some(Err(_))
+        ));
+        let second = retry_in().unwrap();
+        assert!(second > first);
+
+        WRITE_FAILURES.lock().unwrap().remove(&path);
+    }
+
+    #[test]
+    fn write_atomic_replaces_file() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("gpugovernor-{}-atomic", std::process::id()));
+        FileHelper::write_atomic(&path, "a=1\n").unwrap();
+        FileHelper::write_atomic(&path, "a=2\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a=2\n");
+        assert!(!dir
+            .join(format!("gpugovernor-{}-atomic.tmp", std::process::id()))
+            .exists());
+        let _ = fs::remove_file(&path);
+
+        assert!(FileHelper::write_atomic(dir.join("missing-dir/atomic"), "a=1\n").is_err());
     }
 }
@@ -1,14 +1,25 @@
-use std::{collections::HashMap, path::Path, thread, time::Duration};
+use std::{
+    collections::HashMap,
+    os::unix::io::{AsRawFd, RawFd},
+    path::Path,
+    thread,
+    time::Duration,
+};
 
 use anyhow::{Context, Result};
 use inotify::{EventMask, Inotify, WatchMask};
+use log::{debug, info};
 
 const WAIT_MOVE_US: u64 = 500 * 1000;
+pub const REARM_POLL_MS: u64 = 1000;
 const RECREATE_DEFAULT_PERM: u32 = 0o666;
 
 pub struct InotifyWatcher {
     inotify: Inotify,
-    watches: HashMap<inotify::WatchDescriptor, String>,
+    // 有效的监控描述符 -> (路径, 监控掩码)
+    watches: HashMap<inotify::WatchDescriptor, (String, WatchMask)>,
+    // 目标被删除或移走，等待重新出现后再次监控的路径
+    pending: Vec<(String, WatchMask)>,
 }
 
 impl InotifyWatcher {
@@ -18,6 +29,7 @@ impl InotifyWatcher {
         Ok(Self {
             inotify,
             watches: HashMap::new(),
+            pending: Vec::new(),
         })
     }
 
@@ -36,12 +48,22 @@ impl InotifyWatcher {
             .add(path_ref, mask)
             .with_context(|| format!("Failed to add watch for: {}", path_ref.display()))?;
 
-        self.watches.insert(wd, path_str.to_string());
+        self.watches.insert(wd, (path_str.to_string(), mask));
 
         Ok(())
     }
 
     pub fn wait_and_handle(&mut self) -> Result<()> {
+        // 存在等待重新监控的路径时不能无限阻塞，轮询直到目标重新出现
+        if !self.pending.is_empty() {
+            while !self.pending.is_empty() {
+                thread::sleep(Duration::from_millis(REARM_POLL_MS));
+                self.rearm_pending();
+            }
+            // 目标重新出现，视为一次变化事件
+            return Ok(());
+        }
+
         let mut buffer = [0; 4096];
         let events = self
             .inotify
@@ -65,6 +87,11 @@ impl InotifyWatcher {
 
     // 新增：非阻塞地检查事件
     pub fn check_events(&mut self) -> Result<Vec<inotify::Event<&'static [u8]>>> {
+        // 尝试重新监控已重新出现的路径
+        if !self.pending.is_empty() {
+            self.rearm_pending();
+        }
+
         let mut buffer = [0; 4096];
         let events = self
             .inotify
@@ -97,44 +124,66 @@ impl InotifyWatcher {
     where
         I: IntoIterator<Item = inotify::Event<&'static [u8]>>,
     {
-        // Collect all watches that need to be updated
-        let mut watches_to_update = Vec::new();
-
         for event in events {
-            if let Some(path) = self.watches.get(&event.wd) {
-                // Re-establish watching after deleting
-                if event.mask.contains(EventMask::IGNORED)
-                    || event.mask.contains(EventMask::DELETE_SELF)
-                    || event.mask.contains(EventMask::MOVE_SELF)
-                {
-                    watches_to_update.push((event.wd, path.clone()));
-                }
+            if !(event.mask.contains(EventMask::IGNORED)
+                || event.mask.contains(EventMask::DELETE_SELF)
+                || event.mask.contains(EventMask::MOVE_SELF))
+            {
+                continue;
             }
+
+            // 同一个监控可能先后收到DELETE_SELF和IGNORED，只处理一次
+            let Some((path, mask)) = self.watches.remove(&event.wd) else {
+                continue;
+            };
+
+            // 移动后旧的监控仍指向原inode，需要手动移除以免泄漏监控描述符
+            if event.mask.contains(EventMask::MOVE_SELF) {
+                let _ = self.inotify.watches().remove(event.wd.clone());
+            }
+
+            debug!("Watch target removed or moved: {path}");
+            self.pending.push((path, mask));
         }
 
-        // Update watches
-        for (wd, path) in watches_to_update {
+        self.rearm_pending();
+
+        Ok(())
+    }
+
+    /// 重新监控目标已重新出现的路径，返回是否有路径被重新监控
+    pub fn rearm(&mut self) -> bool {
+        if self.pending.is_empty() {
+            return false;
+        }
+        let before = self.pending.len();
+        self.rearm_pending();
+        self.pending.len() < before
+    }
+
+    // 重新监控目标已重新出现的路径，仍不存在的继续等待
+    fn rearm_pending(&mut self) {
+        for (path, mask) in std::mem::take(&mut self.pending) {
             // Try to recreate the file if it doesn't exist
-            try_path(&path)?;
-
-            // Re-add the watch
-            let mask = WatchMask::MODIFY
-                | WatchMask::CLOSE_WRITE
-                | WatchMask::DELETE_SELF
-                | WatchMask::MOVE_SELF;
-
-            let new_wd = self
-                .inotify
-                .watches()
-                .add(&path, mask)
-                .with_context(|| format!("Failed to re-add watch for: {path}"))?;
-
-            // Update the watches map
-            self.watches.remove(&wd);
-            self.watches.insert(new_wd, path);
+            let _ = try_path(&path);
+
+            match self.inotify.watches().add(&path, mask) {
+                Ok(wd) => {
+                    info!("Re-established watch for: {path}");
+                    self.watches.insert(wd, (path, mask));
+                }
+                Err(e) => {
+                    debug!("Watch target not available yet: {path}: {e}");
+                    self.pending.push((path, mask));
+                }
+            }
         }
+    }
+}
 
-        Ok(())
+impl AsRawFd for InotifyWatcher {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inotify.as_raw_fd()
     }
 }
 
@@ -156,3 +205,43 @@ fn try_path(path: &str) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn rearms_after_delete_and_create() {
+        let path = std::env::temp_dir().join(format!("gpugovernor-{}-watch", std::process::id()));
+        fs::write(&path, "1").unwrap();
+
+        let mut watcher = InotifyWatcher::new().unwrap();
+        watcher.add(&path, WatchMask::MODIFY).unwrap();
+
+        // 删除后原监控被内核移除，等待目标重新出现
+        fs::remove_file(&path).unwrap();
+        let events = watcher.check_events().unwrap();
+        assert!(events
+            .iter()
+            .any(|event| event.mask.contains(EventMask::DELETE_SELF)));
+        assert!(watcher.watches.is_empty());
+        assert_eq!(watcher.pending.len(), 1);
+        assert!(!watcher.rearm());
+
+        // 重新创建后恢复监控，不会残留旧的监控描述符
+        fs::write(&path, "2").unwrap();
+        assert!(watcher.rearm());
+        assert!(watcher.pending.is_empty());
+        assert_eq!(watcher.watches.len(), 1);
+
+        fs::write(&path, "3").unwrap();
+        let events = watcher.check_events().unwrap();
+        assert!(events
+            .iter()
+            .any(|event| event.mask.contains(EventMask::MODIFY)));
+        assert_eq!(watcher.watches.len(), 1);
+
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -1,16 +1,18 @@
 use anyhow::Result;
 use inotify::WatchMask;
 use log::{debug, info, warn, LevelFilter};
+use std::os::unix::io::{AsRawFd, RawFd};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
 use crate::{
-    datasource::file_path::LOG_LEVEL_PATH,
+    datasource::file_path::{resolve_path, PathKey, LOG_LEVEL_MONITOR_THREAD},
     utils::{
-        file_operate::check_read_simple, inotify::InotifyWatcher,
-        log_rotation::check_and_rotate_main_log,
+        event_loop::PollHandler,
+        file_operate::check_read_simple,
+        inotify::{InotifyWatcher, REARM_POLL_MS},
     },
 };
 
@@ -38,12 +40,13 @@ impl LogLevelManager {
         let default_level = LevelFilter::Info;
 
         // 检查配置文件是否存在
-        if !Path::new(LOG_LEVEL_PATH).exists() {
+        let log_level_path = resolve_path(PathKey::LogLevel);
+        if !Path::new(log_level_path).exists() {
             return Ok(default_level);
         }
 
         // 尝试读取配置文件
-        let content = match std::fs::read_to_string(LOG_LEVEL_PATH) {
+        let content = match std::fs::read_to_string(log_level_path) {
             Ok(content) => content,
             Err(_) => return Ok(default_level),
         };
@@ -51,6 +54,7 @@ impl LogLevelManager {
         // 解析日志等级
         let level_str = content.trim().to_lowercase();
         match level_str.as_str() {
+            "trace" => Ok(LevelFilter::Trace),
             "debug" => Ok(LevelFilter::Debug),
             "info" => Ok(LevelFilter::Info),
             "warn" => Ok(LevelFilter::Warn),
@@ -59,11 +63,6 @@ impl LogLevelManager {
         }
     }
 
-    /// 获取当前日志等级
-    pub fn get_current_level(&self) -> LevelFilter {
-        *self.current_level.lock().unwrap()
-    }
-
     /// 更新日志等级并通知所有回调
     pub fn update_level(&self, new_level: LevelFilter) {
         let mut current = self.current_level.lock().unwrap();
@@ -84,6 +83,7 @@ impl LogLevelManager {
     }
 
     /// 添加日志等级变化回调
+    #[allow(dead_code)]
     pub fn add_callback<F>(&self, callback: F)
     where
         F: Fn(LevelFilter) + Send + Sync + 'static,
@@ -94,19 +94,44 @@ impl LogLevelManager {
 
     /// 启动日志等级监控线程
     pub fn start_monitoring(self: Arc<Self>) -> Result<()> {
+        let mut handler = LogLevelHandler::new(self)?;
+
+        // 主监控循环
+        loop {
+            // 等待文件变化事件
+            if let Err(e) = handler.inotify.wait_and_handle() {
+                warn!("Inotify error in log level monitor: {e}");
+                thread::sleep(Duration::from_secs(1));
+                continue;
+            }
+
+            handler.reload();
+        }
+    }
+}
+
+/// 日志等级监控处理器
+pub struct LogLevelHandler {
+    manager: Arc<LogLevelManager>,
+    inotify: InotifyWatcher,
+}
+
+impl LogLevelHandler {
+    pub fn new(manager: Arc<LogLevelManager>) -> Result<Self> {
         info!("Starting unified log level monitor");
+        let log_level_path = resolve_path(PathKey::LogLevel);
 
         // 检查日志等级文件路径
-        if !check_read_simple(LOG_LEVEL_PATH) {
-            info!("Log level file does not exist: {LOG_LEVEL_PATH}");
+        if !check_read_simple(log_level_path) {
+            info!("Log level file does not exist: {log_level_path}");
         } else {
-            info!("Using log level path: {LOG_LEVEL_PATH}");
+            info!("Using log level path: {log_level_path}");
         }
 
         // 初始化当前日志等级
-        match Self::read_log_level_config() {
+        match LogLevelManager::read_log_level_config() {
             Ok(level) => {
-                self.update_level(level);
+                manager.update_level(level);
                 info!("Initial log level set to: {level}");
             }
             Err(e) => {
@@ -116,60 +141,62 @@ impl LogLevelManager {
 
         // 设置文件监控
         let mut inotify = InotifyWatcher::new()?;
-        inotify.add(LOG_LEVEL_PATH, WatchMask::CLOSE_WRITE | WatchMask::MODIFY)?;
+        inotify.add(log_level_path, WatchMask::CLOSE_WRITE | WatchMask::MODIFY)?;
 
-        // 主监控循环
-        loop {
-            // 等待文件变化事件
-            if let Err(e) = inotify.wait_and_handle() {
-                warn!("Inotify error in log level monitor: {e}");
-                thread::sleep(Duration::from_secs(1));
-                continue;
-            }
+        Ok(Self { manager, inotify })
+    }
 
-            // 检查文件是否存在
-            if !check_read_simple(LOG_LEVEL_PATH) {
-                debug!("Log level file no longer exists");
-                continue;
-            }
+    // 日志等级文件变化后重新读取
+    fn reload(&self) {
+        // 检查文件是否存在
+        if !check_read_simple(resolve_path(PathKey::LogLevel)) {
+            debug!("Log level file no longer exists");
+            return;
+        }
 
-            // 读取新的日志等级配置
-            match Self::read_log_level_config() {
-                Ok(new_level) => {
-                    self.update_level(new_level);
-                }
-                Err(e) => {
-                    warn!("Failed to update log level: {e}");
-                }
+        // 读取新的日志等级配置
+        match LogLevelManager::read_log_level_config() {
+            Ok(new_level) => {
+                self.manager.update_level(new_level);
             }
+            Err(e) => {
+                warn!("Failed to update log level: {e}");
+            }
+        }
+    }
+}
+
+impl PollHandler for LogLevelHandler {
+    fn name(&self) -> &'static str {
+        LOG_LEVEL_MONITOR_THREAD
+    }
+
+    fn poll_fd(&self) -> Option<RawFd> {
+        Some(self.inotify.as_raw_fd())
+    }
+
+    fn on_readable(&mut self) -> Result<()> {
+        self.inotify.check_events()?;
+        self.reload();
+        Ok(())
+    }
+
+    fn tick_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(REARM_POLL_MS))
+    }
+
+    fn on_tick(&mut self) -> Result<()> {
+        // 日志等级文件被删除后重新出现时重新读取
+        if self.inotify.rearm() {
+            self.reload();
         }
+        Ok(())
     }
 }
 
 /// 全局日志等级管理器实例
 static LOG_LEVEL_MANAGER: once_cell::sync::Lazy<Arc<LogLevelManager>> =
-    once_cell::sync::Lazy::new(|| {
-        let manager = Arc::new(LogLevelManager::new());
-
-        // 添加日志轮转回调
-        manager.add_callback(move |level| {
-            // 只在debug等级时执行日志轮转检查
-            if level == LevelFilter::Debug {
-                match check_and_rotate_main_log() {
-                    Ok(rotated) => {
-                        if rotated {
-                            info!("Log file rotated successfully");
-                        }
-                    }
-                    Err(e) => {
-                        warn!("Failed to check/rotate log file: {e}");
-                    }
-                }
-            }
-        });
-
-        manager
-    });
+    once_cell::sync::Lazy::new(|| Arc::new(LogLevelManager::new()));
 
 /// 获取全局日志等级管理器
 pub fn get_log_level_manager() -> Arc<LogLevelManager> {
@@ -182,7 +209,7 @@ pub fn start_unified_log_level_monitor() -> Result<()> {
     manager.start_monitoring()
 }
 
-/// 获取当前日志等级（便捷函数）
-pub fn get_current_log_level() -> LevelFilter {
-    get_log_level_manager().get_current_level()
+/// 创建统一的日志等级监控处理器（单线程事件循环模式）
+pub fn unified_log_level_handler() -> Result<LogLevelHandler> {
+    LogLevelHandler::new(get_log_level_manager())
 }
@@ -1,33 +1,42 @@
 use anyhow::{Context, Result};
 use chrono::Local;
-use log::{debug, info};
+use log::{debug, info, warn};
 use std::fs;
 use std::path::Path;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
 
-use crate::datasource::file_path::LOG_PATH;
+use crate::{
+    datasource::file_path::{resolve_path, PathKey, LOG_ROTATION_THREAD},
+    utils::{constants::strategy, event_loop::PollHandler, logger::reopen_log_file},
+};
 
 /// 日志轮转管理器
 pub struct LogRotationManager {
     max_size_bytes: u64,
-    rotation_threshold: f64,
+    keep_files: u32,
 }
 
 impl LogRotationManager {
     /// 创建新的日志轮转管理器
     ///
     /// # Arguments
-    /// * `max_size_mb` - 最大日志文件大小（MB）
-    /// * `rotation_threshold` - 轮转阈值（0.0-1.0），默认0.8表示80%
-    pub fn new(max_size_mb: u64, rotation_threshold: Option<f64>) -> Self {
+    /// * `max_size_kb` - 日志文件超过该大小（KB）时轮转
+    /// * `keep_files` - 保留的轮转日志文件数，0表示轮转时直接丢弃旧日志
+    pub const fn new(max_size_kb: u64, keep_files: u32) -> Self {
         Self {
-            max_size_bytes: max_size_mb * 1024 * 1024,
-            rotation_threshold: rotation_threshold.unwrap_or(0.8),
+            max_size_bytes: max_size_kb * 1024,
+            keep_files,
         }
     }
 
-    /// 创建默认的日志轮转管理器（10MB，80%阈值）
-    pub fn default() -> Self {
-        Self::new(10, Some(0.8))
+    /// 创建默认的日志轮转管理器（8MB，保留1个备份）
+    pub const fn default() -> Self {
+        Self::new(
+            strategy::LOG_MAX_SIZE_KB_DEFAULT,
+            strategy::LOG_KEEP_FILES_DEFAULT,
+        )
     }
 
     /// 检查是否需要轮转日志
@@ -43,13 +52,38 @@ impl LogRotationManager {
             .with_context(|| format!("Failed to get metadata for: {log_file_path}"))?;
 
         let file_size = metadata.len();
-        let threshold_size = (self.max_size_bytes as f64 * self.rotation_threshold) as u64;
+        let threshold_size = self.max_size_bytes;
 
         debug!("Log file size: {file_size} bytes, threshold: {threshold_size} bytes");
 
         Ok(file_size > threshold_size)
     }
 
+    // 第index个备份文件路径，最新的备份为.bak，更旧的依次为.bak.1、.bak.2...
+    fn backup_path(log_file_path: &str, index: u32) -> String {
+        if index == 0 {
+            format!("{log_file_path}.bak")
+        } else {
+            format!("{log_file_path}.bak.{index}")
+        }
+    }
+
+    // 删除超出保留数量的备份文件，包括保留数量调小后遗留的旧备份
+    fn remove_excess_backups(&self, log_file_path: &str) -> Result<()> {
+        let mut index = self.keep_files.saturating_sub(1);
+        loop {
+            let backup_path = Self::backup_path(log_file_path, index);
+            if !Path::new(&backup_path).exists() {
+                break;
+            }
+            fs::remove_file(&backup_path)
+                .with_context(|| format!("Failed to remove old backup file: {backup_path}"))?;
+            debug!("Removed old backup file: {backup_path}");
+            index += 1;
+        }
+        Ok(())
+    }
+
     /// 执行日志轮转
     pub fn rotate_log(&self, log_file_path: &str) -> Result<()> {
         let log_path = Path::new(log_file_path);
@@ -59,28 +93,42 @@ impl LogRotationManager {
             return Ok(());
         }
 
-        let backup_path = format!("{log_file_path}.bak");
-
-        // 如果备份文件已存在，删除它
-        if Path::new(&backup_path).exists() {
-            fs::remove_file(&backup_path)
-                .with_context(|| format!("Failed to remove old backup file: {backup_path}"))?;
-            debug!("Removed old backup file: {backup_path}");
-        }
-
-        // 将当前日志文件重命名为备份文件
-        fs::rename(log_path, &backup_path)
-            .with_context(|| format!("Failed to rename log file to backup: {backup_path}"))?;
-
-        info!("Log file rotated: {log_file_path} -> {backup_path}");
+        // 删除最旧的备份，为新备份腾出位置
+        self.remove_excess_backups(log_file_path)?;
+
+        let rotation_msg = if self.keep_files == 0 {
+            fs::remove_file(log_path)
+                .with_context(|| format!("Failed to remove log file: {log_file_path}"))?;
+            info!("Log file rotated: {log_file_path} discarded");
+            format!(
+                "{} - Log rotated, previous log discarded\n",
+                Local::now().format("%Y-%m-%d %H:%M:%S")
+            )
+        } else {
+            // 已有备份依次后移
+            for index in (0..self.keep_files - 1).rev() {
+                let from = Self::backup_path(log_file_path, index);
+                if Path::new(&from).exists() {
+                    let to = Self::backup_path(log_file_path, index + 1);
+                    fs::rename(&from, &to)
+                        .with_context(|| format!("Failed to rename backup file: {from} -> {to}"))?;
+                }
+            }
+
+            // 将当前日志文件重命名为最新的备份文件
+            let backup_path = Self::backup_path(log_file_path, 0);
+            fs::rename(log_path, &backup_path)
+                .with_context(|| format!("Failed to rename log file to backup: {backup_path}"))?;
+
+            info!("Log file rotated: {log_file_path} -> {backup_path}");
+            format!(
+                "{} - Log rotated, previous log backed up to {}\n",
+                Local::now().format("%Y-%m-%d %H:%M:%S"),
+                backup_path
+            )
+        };
 
         // 创建新的日志文件并写入轮转信息
-        let rotation_msg = format!(
-            "{} - Log rotated, previous log backed up to {}\n",
-            Local::now().format("%Y-%m-%d %H:%M:%S"),
-            backup_path
-        );
-
         fs::write(log_path, rotation_msg)
             .with_context(|| format!("Failed to create new log file: {log_file_path}"))?;
 
@@ -116,11 +164,9 @@ impl LogRotationManager {
         Ok(size_mb)
     }
 
-    /// 获取配置信息
-    #[allow(dead_code)]
-    pub fn get_config_info(&self) -> (f64, f64) {
-        let max_size_mb = self.max_size_bytes as f64 / (1024.0 * 1024.0);
-        (max_size_mb, self.rotation_threshold)
+    /// 获取配置信息（轮转大小KB，保留文件数）
+    pub fn get_config_info(&self) -> (u64, u32) {
+        (self.max_size_bytes / 1024, self.keep_files)
     }
 
     /// 强制轮转日志（不检查大小）
@@ -132,40 +178,113 @@ impl LogRotationManager {
 }
 
 /// 全局日志轮转管理器实例
-static LOG_ROTATION_MANAGER: once_cell::sync::Lazy<LogRotationManager> =
-    once_cell::sync::Lazy::new(LogRotationManager::default);
+static LOG_ROTATION_MANAGER: Mutex<LogRotationManager> = Mutex::new(LogRotationManager::default());
+
+/// 设置日志轮转大小（KB）和保留的轮转文件数
+pub fn set_log_rotation(max_size_kb: u64, keep_files: u32) {
+    let max_size_kb = max_size_kb.max(1);
+    debug!("Set log rotation: at {max_size_kb}KB, keep {keep_files} files");
+    *LOG_ROTATION_MANAGER.lock().unwrap() = LogRotationManager::new(max_size_kb, keep_files);
+}
 
 /// 检查主日志文件是否需要轮转
 #[allow(dead_code)]
 pub fn should_rotate_main_log() -> Result<bool> {
-    LOG_ROTATION_MANAGER.should_rotate(LOG_PATH)
+    LOG_ROTATION_MANAGER
+        .lock()
+        .unwrap()
+        .should_rotate(resolve_path(PathKey::Log))
 }
 
 /// 轮转主日志文件
 #[allow(dead_code)]
 pub fn rotate_main_log() -> Result<()> {
-    LOG_ROTATION_MANAGER.rotate_log(LOG_PATH)
+    LOG_ROTATION_MANAGER
+        .lock()
+        .unwrap()
+        .rotate_log(resolve_path(PathKey::Log))?;
+    reopen_log_file();
+    Ok(())
 }
 
 /// 检查并轮转主日志文件
 pub fn check_and_rotate_main_log() -> Result<bool> {
-    LOG_ROTATION_MANAGER.check_and_rotate(LOG_PATH)
+    let rotated = LOG_ROTATION_MANAGER
+        .lock()
+        .unwrap()
+        .check_and_rotate(resolve_path(PathKey::Log))?;
+    // 日志写入器仍指向已改名的备份文件，需要重新打开
+    if rotated {
+        reopen_log_file();
+    }
+    Ok(rotated)
 }
 
 /// 获取主日志文件大小（MB）
 #[allow(dead_code)]
 pub fn get_main_log_size_mb() -> Result<f64> {
-    LOG_ROTATION_MANAGER.get_log_size_mb(LOG_PATH)
+    LOG_ROTATION_MANAGER
+        .lock()
+        .unwrap()
+        .get_log_size_mb(resolve_path(PathKey::Log))
 }
 
 /// 强制轮转主日志文件
 #[allow(dead_code)]
 pub fn force_rotate_main_log() -> Result<()> {
-    LOG_ROTATION_MANAGER.force_rotate(LOG_PATH)
+    LOG_ROTATION_MANAGER
+        .lock()
+        .unwrap()
+        .force_rotate(resolve_path(PathKey::Log))?;
+    reopen_log_file();
+    Ok(())
 }
 
-/// 获取日志轮转配置信息
-#[allow(dead_code)]
-pub fn get_log_rotation_config() -> (f64, f64) {
-    LOG_ROTATION_MANAGER.get_config_info()
+/// 获取日志轮转配置信息（轮转大小KB，保留文件数）
+pub fn get_log_rotation_config() -> (u64, u32) {
+    LOG_ROTATION_MANAGER.lock().unwrap().get_config_info()
+}
+
+// 检查主日志文件大小，超过阈值时轮转，失败只记录警告
+fn check_main_log() {
+    if let Err(e) = check_and_rotate_main_log() {
+        warn!("Failed to check/rotate log file: {e}");
+    }
+}
+
+/// 日志轮转处理器 - 定时检查主日志文件大小，与日志等级无关
+pub struct LogRotationHandler;
+
+impl LogRotationHandler {
+    pub fn new() -> Result<Self> {
+        let (max_size_kb, keep_files) = get_log_rotation_config();
+        info!("{LOG_ROTATION_THREAD} Start");
+        info!("Log rotation: at {max_size_kb}KB, keep {keep_files} files");
+        Ok(Self)
+    }
+}
+
+impl PollHandler for LogRotationHandler {
+    fn name(&self) -> &'static str {
+        LOG_ROTATION_THREAD
+    }
+
+    fn tick_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(strategy::LOG_ROTATION_CHECK_MS))
+    }
+
+    fn on_tick(&mut self) -> Result<()> {
+        check_main_log();
+        Ok(())
+    }
+}
+
+/// 定时检查并轮转主日志文件
+pub fn monitor_log_rotation() -> Result<()> {
+    let _handler = LogRotationHandler::new()?;
+
+    loop {
+        check_main_log();
+        thread::sleep(Duration::from_millis(strategy::LOG_ROTATION_CHECK_MS));
+    }
 }
@@ -1,50 +1,149 @@
 use std::fs::{File, OpenOptions};
 use std::io::{BufWriter, Write};
-use std::path::Path;
-use std::sync::Mutex;
+use std::sync::{
+    atomic::{AtomicU32, AtomicU8, Ordering},
+    Mutex,
+};
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
-use chrono::Local;
-use log::{LevelFilter, Metadata, Record};
+use chrono::{Local, SecondsFormat};
+use log::{debug, Level, Metadata, Record};
 use once_cell::sync::Lazy;
 
 use crate::{
-    datasource::file_path::{LOG_LEVEL_PATH, LOG_PATH},
-    utils::log_level_manager::{get_current_log_level, LogLevelManager},
+    datasource::file_path::{resolve_path, PathKey},
+    model::snapshot::json_string,
+    utils::{constants::strategy, log_level_manager::LogLevelManager},
 };
 
-// 日志轮转配置常量
-const MAX_LOG_SIZE_BYTES: u64 = 10 * 1024 * 1024; // 10MB
-const LOG_ROTATION_THRESHOLD: f64 = 0.8; // 80%阈值触发轮转
+// 日志限速统计周期
+const LOG_RATE_WINDOW: Duration = Duration::from_secs(1);
+
+// 每秒最多写入的日志行数，0表示不限制
+static MAX_LOG_LINES_PER_SEC: AtomicU32 = AtomicU32::new(strategy::MAX_LOG_LINES_PER_SEC_DEFAULT);
+
+/// 设置每秒最多写入的日志行数，0表示不限制
+pub fn set_max_log_rate(lines_per_sec: u32) {
+    MAX_LOG_LINES_PER_SEC.store(lines_per_sec, Ordering::SeqCst);
+    debug!("Set max log rate: {lines_per_sec} lines/s");
+}
+
+/// 获取每秒最多写入的日志行数
+pub fn get_max_log_rate() -> u32 {
+    MAX_LOG_LINES_PER_SEC.load(Ordering::SeqCst)
+}
+
+/// 日志输出格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    /// 每行"[时间][等级]: 消息"（默认）
+    Text,
+    /// 每行一个JSON对象，包含timestamp、level、thread和message字段
+    Json,
+}
+
+impl LogFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "text" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+// 全局日志格式
+static LOG_FORMAT: AtomicU8 = AtomicU8::new(LogFormat::Text as u8);
+
+/// 设置日志输出格式
+pub fn set_log_format(format: LogFormat) {
+    LOG_FORMAT.store(format as u8, Ordering::SeqCst);
+    debug!("Set log format: {format:?}");
+}
+
+/// 获取日志输出格式
+pub fn get_log_format() -> LogFormat {
+    match LOG_FORMAT.load(Ordering::SeqCst) {
+        x if x == LogFormat::Json as u8 => LogFormat::Json,
+        _ => LogFormat::Text,
+    }
+}
+
+// 按当前日志格式生成一行日志
+fn format_line(level: Level, message: &str) -> String {
+    let now = Local::now();
+    match get_log_format() {
+        LogFormat::Text => format!(
+            "[{}][{level}]: {message}\n",
+            now.format("%Y-%m-%d %H:%M:%S")
+        ),
+        LogFormat::Json => format!(
+            "{{\"timestamp\":{},\"level\":\"{level}\",\"thread\":{},\"message\":{}}}\n",
+            json_string(&now.to_rfc3339_opts(SecondsFormat::Millis, false)),
+            json_string(std::thread::current().name().unwrap_or("unnamed")),
+            json_string(message),
+        ),
+    }
+}
 
-// 自定义日志实现 - 支持文件写入和轮转
+// 日志限速状态
+struct LogRate {
+    window_start: Instant,
+    lines: u32,
+    dropped: u64,
+}
+
+// 自定义日志实现 - 支持文件写入、轮转和限速
 struct CustomLogger {
     file_writer: Mutex<Option<BufWriter<File>>>,
+    rate: Mutex<LogRate>,
 }
 
 impl CustomLogger {
     fn new() -> Self {
         Self {
             file_writer: Mutex::new(None),
+            rate: Mutex::new(LogRate {
+                window_start: Instant::now(),
+                lines: 0,
+                dropped: 0,
+            }),
+        }
+    }
+
+    // 检查本条日志是否超出限速，返回(是否写入, 上个周期丢弃的行数)
+    // 错误日志始终写入，避免重要信息被调试日志挤掉
+    fn check_rate(&self, level: Level) -> (bool, u64) {
+        let max_lines = get_max_log_rate();
+        let mut rate = self.rate.lock().unwrap();
+
+        let mut dropped = 0;
+        if rate.window_start.elapsed() >= LOG_RATE_WINDOW {
+            dropped = std::mem::take(&mut rate.dropped);
+            rate.window_start = Instant::now();
+            rate.lines = 0;
+        }
+        rate.lines = rate.lines.saturating_add(1);
+
+        if max_lines == 0 || rate.lines <= max_lines || level == Level::Error {
+            return (true, dropped);
         }
+        rate.dropped += 1;
+        (false, dropped)
     }
 
     fn ensure_log_file(&self) -> Result<()> {
         let mut writer = self.file_writer.lock().unwrap();
 
         if writer.is_none() {
-            // 只在debug等级时检查并执行日志轮转
-            let current_level = get_current_log_level();
-            if current_level == LevelFilter::Debug {
-                self.check_and_rotate_log()?;
-            }
-
-            // 创建或打开日志文件
+            // 创建或打开日志文件，轮转由日志等级监控定时检查
+            let log_file = resolve_path(PathKey::Log);
             let file = OpenOptions::new()
                 .create(true)
                 .append(true)
-                .open(LOG_PATH)
-                .with_context(|| format!("Failed to open log file: {LOG_PATH}"))?;
+                .open(log_file)
+                .with_context(|| format!("Failed to open log file: {log_file}"))?;
 
             *writer = Some(BufWriter::new(file));
         }
@@ -52,62 +151,12 @@ impl CustomLogger {
         Ok(())
     }
 
-    fn check_and_rotate_log(&self) -> Result<()> {
-        let log_path = Path::new(LOG_PATH);
-
-        // 如果日志文件不存在，无需轮转
-        if !log_path.exists() {
-            return Ok(());
-        }
-
-        // 获取文件大小
-        let metadata = log_path
-            .metadata()
-            .with_context(|| format!("Failed to get metadata for log file: {LOG_PATH}"))?;
-
-        let file_size = metadata.len();
-        let threshold_size = (MAX_LOG_SIZE_BYTES as f64 * LOG_ROTATION_THRESHOLD) as u64;
-
-        // 如果文件大小超过阈值，执行轮转
-        if file_size > threshold_size {
-            self.rotate_log_file()?;
-        }
-
-        Ok(())
-    }
-
-    fn rotate_log_file(&self) -> Result<()> {
-        let log_path = Path::new(LOG_PATH);
-        let backup_path = format!("{LOG_PATH}.bak");
-
-        // 如果备份文件已存在，删除它
-        if Path::new(&backup_path).exists() {
-            std::fs::remove_file(&backup_path)
-                .with_context(|| format!("Failed to remove old backup file: {backup_path}"))?;
+    // 关闭当前日志文件，下次写入时重新打开
+    fn reopen(&self) {
+        let mut writer = self.file_writer.lock().unwrap();
+        if let Some(mut buf_writer) = writer.take() {
+            let _ = buf_writer.flush();
         }
-
-        // 将当前日志文件重命名为备份文件
-        std::fs::rename(log_path, &backup_path)
-            .with_context(|| format!("Failed to rename log file to backup: {backup_path}"))?;
-
-        // 创建新的日志文件并写入轮转信息
-        let mut new_file = File::create(log_path)
-            .with_context(|| format!("Failed to create new log file: {LOG_PATH}"))?;
-
-        let rotation_msg = format!(
-            "{} - Log rotated, previous log backed up to {}\n",
-            Local::now().format("%Y-%m-%d %H:%M:%S"),
-            backup_path
-        );
-        new_file
-            .write_all(rotation_msg.as_bytes())
-            .with_context(|| "Failed to write rotation message to new log file")?;
-
-        new_file
-            .flush()
-            .with_context(|| "Failed to flush new log file")?;
-
-        Ok(())
     }
 
     fn write_to_file(&self, message: &str) -> Result<()> {
@@ -137,10 +186,21 @@ impl log::Log for CustomLogger {
 
     fn log(&self, record: &Record) {
         // 这里不需要再次检查enabled，因为log库已经根据max_level过滤了
-        let now = Local::now();
-        let timestamp = now.format("%Y-%m-%d %H:%M:%S").to_string();
-        let level_str = record.level().to_string();
-        let log_message = format!("[{}][{}]: {}\n", timestamp, level_str, record.args());
+        // 限速在等级过滤之后进行，只会丢弃已启用等级中超出速率的日志
+        let (allowed, dropped) = self.check_rate(record.level());
+
+        // 新周期开始时汇报上个周期丢弃的行数
+        if dropped > 0 {
+            let summary = format_line(Level::Warn, &format!("(dropped {dropped} log lines)"));
+            if let Err(e) = self.write_to_file(&summary) {
+                eprintln!("Warning: Failed to write to log file: {e}");
+            }
+        }
+        if !allowed {
+            return;
+        }
+
+        let log_message = format_line(record.level(), &record.args().to_string());
 
         // 只写入到文件（忽略错误以避免程序崩溃）
         if let Err(e) = self.write_to_file(&log_message) {
@@ -160,9 +220,15 @@ impl log::Log for CustomLogger {
 // 全局日志实例
 static LOGGER: Lazy<CustomLogger> = Lazy::new(CustomLogger::new);
 
+/// 日志文件被轮转后重新打开，避免继续写入已改名的备份文件
+pub fn reopen_log_file() {
+    LOGGER.reopen();
+}
+
 pub fn init_logger() -> Result<()> {
     // 启动时清空日志文件，保证每次启动都是新日志
-    let _ = File::create(LOG_PATH)?;
+    let log_file = resolve_path(PathKey::Log);
+    let _ = File::create(log_file)?;
     // 读取日志等级配置
     let log_level = LogLevelManager::read_log_level_config()?;
 
@@ -173,13 +239,8 @@ pub fn init_logger() -> Result<()> {
 
     // 记录当前使用的日志等级
     log::info!("Logger initialized with level: {log_level}");
-    log::info!("Log file path: {LOG_PATH}");
-    log::info!("Log level config path: {LOG_LEVEL_PATH}");
-    log::info!("Max log file size: {}MB", MAX_LOG_SIZE_BYTES / 1024 / 1024);
-    log::info!(
-        "Log rotation threshold: {}%",
-        (LOG_ROTATION_THRESHOLD * 100.0) as u8
-    );
+    log::info!("Log file path: {log_file}");
+    log::info!("Log level config path: {}", resolve_path(PathKey::LogLevel));
 
     // 在debug级别记录一条消息，说明某些错误只会在debug级别显示
     log::debug!("Some error messages will only be shown at debug level");
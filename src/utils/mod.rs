@@ -1,4 +1,5 @@
 pub mod constants;
+pub mod event_loop;
 pub mod file_helper;
 pub mod file_operate;
 pub mod file_status;
@@ -7,3 +8,8 @@ pub mod log_level_manager;
 pub mod log_rotation;
 pub mod logger;
 pub mod macros;
+pub mod numeric;
+pub mod pid_file;
+pub mod shutdown;
+pub mod supervisor;
+pub mod thread_sched;
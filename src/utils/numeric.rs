@@ -0,0 +1,73 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use log::debug;
+
+/// 内核节点数值格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumericFormat {
+    /// 十进制（默认）
+    Dec,
+    /// 十六进制，可带或不带0x前缀
+    Hex,
+    /// 根据0x前缀自动识别
+    Auto,
+}
+
+impl NumericFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "dec" => Some(Self::Dec),
+            "hex" => Some(Self::Hex),
+            "auto" => Some(Self::Auto),
+            _ => None,
+        }
+    }
+}
+
+// 全局数值格式，所有节点解析共享
+static NUMERIC_FORMAT: AtomicU8 = AtomicU8::new(NumericFormat::Dec as u8);
+
+/// 设置节点数值格式
+pub fn set_numeric_format(format: NumericFormat) {
+    NUMERIC_FORMAT.store(format as u8, Ordering::SeqCst);
+    debug!("Set numeric format: {format:?}");
+}
+
+/// 获取节点数值格式
+pub fn get_numeric_format() -> NumericFormat {
+    match NUMERIC_FORMAT.load(Ordering::SeqCst) {
+        x if x == NumericFormat::Hex as u8 => NumericFormat::Hex,
+        x if x == NumericFormat::Auto as u8 => NumericFormat::Auto,
+        _ => NumericFormat::Dec,
+    }
+}
+
+/// 按当前数值格式解析整数
+pub fn parse_i64(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let format = get_numeric_format();
+    if format == NumericFormat::Dec {
+        return value.parse::<i64>().ok();
+    }
+
+    let (negative, digits) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value),
+    };
+    let hex_digits = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"));
+
+    let parsed = match (format, hex_digits) {
+        (_, Some(hex)) => i64::from_str_radix(hex, 16).ok()?,
+        (NumericFormat::Hex, None) => i64::from_str_radix(digits, 16).ok()?,
+        _ => return value.parse::<i64>().ok(),
+    };
+
+    Some(if negative { -parsed } else { parsed })
+}
+
+/// 按当前数值格式解析32位整数
+pub fn parse_i32(value: &str) -> Option<i32> {
+    parse_i64(value).and_then(|v| i32::try_from(v).ok())
+}
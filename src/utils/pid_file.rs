@@ -0,0 +1,157 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+/// PID文件守卫 - 持有期间表示当前实例正在运行，释放时删除PID文件
+pub struct PidFile {
+    path: PathBuf,
+    stale_pid: Option<i32>,
+}
+
+impl PidFile {
+    /// 写入PID文件，如果已有存活的实例则拒绝启动
+    pub fn acquire<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let own_pid = std::process::id() as i32;
+
+        // 检查已有的PID文件，进程已退出或PID已被其他程序复用的视为过期文件直接覆盖
+        let mut stale_pid = None;
+        if let Some(pid) = read_pid(path) {
+            if pid != own_pid && is_process_alive(pid) && is_same_program(pid) {
+                return Err(anyhow!(
+                    "Another GPU governor instance is already running (PID {pid}, PID file: {})",
+                    path.display()
+                ));
+            }
+            stale_pid = Some(pid);
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create PID file directory: {}", parent.display())
+            })?;
+        }
+
+        fs::write(path, format!("{own_pid}\n"))
+            .with_context(|| format!("Failed to write PID file: {}", path.display()))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            stale_pid,
+        })
+    }
+
+    /// 获取PID文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 获取被覆盖的过期PID（如果有）
+    pub fn stale_pid(&self) -> Option<i32> {
+        self.stale_pid
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        // 仅删除仍属于当前进程的PID文件
+        if read_pid(&self.path) == Some(std::process::id() as i32) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn read_pid(path: &Path) -> Option<i32> {
+    fs::read_to_string(path).ok()?.trim().parse::<i32>().ok()
+}
+
+fn is_process_alive(pid: i32) -> bool {
+    if pid <= 0 {
+        return false;
+    }
+
+    // 发送0号信号只检查进程是否存在；EPERM表示进程存在但属于其他用户
+    let ret = unsafe { libc::kill(pid, 0) };
+    ret == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+// PID对应的进程是否运行的是同一个程序，重启后PID文件中的PID可能已分配给无关进程
+fn is_same_program(pid: i32) -> bool {
+    let Ok(own_exe) = std::env::current_exe() else {
+        return true;
+    };
+
+    // 优先比较可执行文件路径，文件被替换后内核会在路径后追加" (deleted)"
+    if let Ok(exe) = fs::read_link(format!("/proc/{pid}/exe")) {
+        let exe = exe.to_string_lossy();
+        let own_exe = own_exe.to_string_lossy();
+        return exe.trim_end_matches(" (deleted)") == own_exe.trim_end_matches(" (deleted)");
+    }
+
+    // 无权读取exe链接时比较命令行中的程序名，两者都无法读取时按同一程序处理
+    match fs::read(format!("/proc/{pid}/cmdline")) {
+        Ok(cmdline) => {
+            let program = cmdline.split(|&b| b == 0).next().unwrap_or_default();
+            let program = Path::new(std::str::from_utf8(program).unwrap_or_default());
+            program.file_name().is_some() && program.file_name() == own_exe.file_name()
+        }
+        Err(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_pid_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("gpugovernor-{}-{name}.pid", std::process::id()))
+    }
+
+    #[test]
+    fn detects_same_program() {
+        assert!(is_same_program(std::process::id() as i32));
+
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .unwrap();
+        let same = is_same_program(child.id() as i32);
+        let _ = child.kill();
+        let _ = child.wait();
+        assert!(!same);
+    }
+
+    #[test]
+    fn takes_over_pid_of_other_program() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .unwrap();
+        let path = temp_pid_path("reused");
+        fs::write(&path, format!("{}\n", child.id())).unwrap();
+
+        let pid_file = PidFile::acquire(&path);
+        let _ = child.kill();
+        let _ = child.wait();
+
+        let pid_file = pid_file.unwrap();
+        assert_eq!(pid_file.stale_pid(), Some(child.id() as i32));
+        assert_eq!(read_pid(&path), Some(std::process::id() as i32));
+        drop(pid_file);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn overwrites_dead_pid() {
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        child.wait().unwrap();
+        let path = temp_pid_path("dead");
+        fs::write(&path, format!("{}\n", child.id())).unwrap();
+
+        let pid_file = PidFile::acquire(&path).unwrap();
+        assert_eq!(pid_file.stale_pid(), Some(child.id() as i32));
+    }
+}
@@ -0,0 +1,27 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{anyhow, Result};
+
+// 收到退出信号后置位，调频循环检测到后退出
+static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn handle_shutdown_signal(_signal: libc::c_int) {
+    // 信号处理函数中只做原子写入
+    SHUTDOWN_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// 注册SIGTERM/SIGINT处理，使进程可以在退出前完成收尾工作
+pub fn install_shutdown_handler() -> Result<()> {
+    for signal in [libc::SIGTERM, libc::SIGINT] {
+        let handler = handle_shutdown_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        if unsafe { libc::signal(signal, handler) } == libc::SIG_ERR {
+            return Err(anyhow!("Failed to install handler for signal {signal}"));
+        }
+    }
+    Ok(())
+}
+
+/// 是否已请求退出
+pub fn is_shutdown_requested() -> bool {
+    SHUTDOWN_REQUESTED.load(Ordering::SeqCst)
+}
@@ -0,0 +1,178 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use log::{error, info, warn};
+
+use crate::{
+    datasource::file_path::SUPERVISOR_THREAD,
+    utils::{
+        constants::strategy, shutdown::is_shutdown_requested, thread_sched::apply_thread_sched,
+    },
+};
+
+// 正在运行的监控线程名称，由守护线程定期更新
+static ALIVE_THREADS: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+/// 获取正在运行的监控线程名称
+pub fn alive_threads() -> Vec<&'static str> {
+    ALIVE_THREADS.lock().unwrap().clone()
+}
+
+// 监控线程的执行体，重启时会再次调用
+type ThreadBody = Arc<dyn Fn() -> Result<()> + Send + Sync>;
+
+// 被守护的监控线程
+struct SupervisedThread {
+    name: &'static str,
+    body: ThreadBody,
+    handle: Option<JoinHandle<Result<()>>>,
+    // 时间窗口内的重启时间，用于计算退避和升级日志
+    recent_restarts: VecDeque<Instant>,
+    // 累计重启次数
+    restarts: u32,
+    // 计划重启的时间
+    restart_at: Option<Instant>,
+}
+
+impl SupervisedThread {
+    fn spawn(&mut self) -> std::io::Result<()> {
+        let name = self.name;
+        let body = self.body.clone();
+        let handle = thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                apply_thread_sched(name);
+                body()
+            })?;
+        self.handle = Some(handle);
+        Ok(())
+    }
+
+    // 线程退出后记录原因并按退避时间安排重启
+    fn on_exit(&mut self, handle: JoinHandle<Result<()>>, now: Instant) {
+        let reason = match handle.join() {
+            Ok(Ok(())) => "exited".to_string(),
+            Ok(Err(e)) => format!("exited with error: {e}"),
+            Err(_) => "panicked".to_string(),
+        };
+
+        let window = Duration::from_millis(strategy::SUPERVISOR_RESTART_WINDOW_MS);
+        while self
+            .recent_restarts
+            .front()
+            .is_some_and(|restart| now.duration_since(*restart) > window)
+        {
+            self.recent_restarts.pop_front();
+        }
+
+        // 窗口内每多重启一次退避时间翻倍，不超过上限
+        let failures = self.recent_restarts.len() as u32;
+        let backoff = strategy::SUPERVISOR_BACKOFF_BASE_MS
+            .saturating_mul(1u64 << failures.min(16))
+            .min(strategy::SUPERVISOR_BACKOFF_MAX_MS);
+
+        if failures >= strategy::SUPERVISOR_RESTART_ESCALATE_COUNT {
+            error!(
+                "{} {reason}, {failures} restarts within {}s, restarting in {backoff}ms",
+                self.name,
+                window.as_secs()
+            );
+        } else {
+            warn!("{} {reason}, restarting in {backoff}ms", self.name);
+        }
+        self.restart_at = Some(now + Duration::from_millis(backoff));
+    }
+
+    fn check(&mut self, now: Instant) {
+        if let Some(handle) = self.handle.take_if(|handle| handle.is_finished()) {
+            self.on_exit(handle, now);
+        }
+
+        let Some(restart_at) = self.restart_at else {
+            return;
+        };
+        if now < restart_at {
+            return;
+        }
+
+        self.restart_at = None;
+        self.recent_restarts.push_back(now);
+        self.restarts += 1;
+        match self.spawn() {
+            Ok(()) => info!("{} restarted (restart #{})", self.name, self.restarts),
+            Err(e) => {
+                error!("Failed to respawn {}: {e}", self.name);
+                self.restart_at =
+                    Some(now + Duration::from_millis(strategy::SUPERVISOR_BACKOFF_MAX_MS));
+            }
+        }
+    }
+}
+
+/// 监控线程守护 - 记录各监控线程的句柄，线程退出后按指数退避重新启动
+#[derive(Default)]
+pub struct Supervisor {
+    threads: Vec<SupervisedThread>,
+}
+
+impl Supervisor {
+    /// 启动一个受守护的监控线程，线程返回或panic后会重新调用body
+    pub fn spawn<F>(&mut self, name: &'static str, body: F)
+    where
+        F: Fn() -> Result<()> + Send + Sync + 'static,
+    {
+        let mut supervised = SupervisedThread {
+            name,
+            body: Arc::new(body),
+            handle: None,
+            recent_restarts: VecDeque::new(),
+            restarts: 0,
+            restart_at: None,
+        };
+        supervised
+            .spawn()
+            .unwrap_or_else(|e| panic!("Failed to spawn {name} thread: {e}"));
+        self.threads.push(supervised);
+    }
+
+    // 记录当前正在运行的监控线程
+    fn publish_alive(&self) {
+        *ALIVE_THREADS.lock().unwrap() = self
+            .threads
+            .iter()
+            .filter(|supervised| {
+                supervised
+                    .handle
+                    .as_ref()
+                    .is_some_and(|handle| !handle.is_finished())
+            })
+            .map(|supervised| supervised.name)
+            .collect();
+    }
+
+    /// 启动守护线程，定期检查监控线程是否退出，收到退出信号后停止重启
+    pub fn start(mut self) {
+        thread::Builder::new()
+            .name(SUPERVISOR_THREAD.to_string())
+            .spawn(move || {
+                info!(
+                    "{SUPERVISOR_THREAD} Start, watching {} threads",
+                    self.threads.len()
+                );
+                while !is_shutdown_requested() {
+                    let now = Instant::now();
+                    for supervised in &mut self.threads {
+                        supervised.check(now);
+                    }
+                    self.publish_alive();
+                    thread::sleep(Duration::from_millis(strategy::SUPERVISOR_POLL_MS));
+                }
+            })
+            .expect("Failed to spawn supervisor thread");
+    }
+}
@@ -0,0 +1,127 @@
+use std::{collections::HashMap, io, mem, sync::Mutex};
+
+use log::{debug, info, warn};
+
+use crate::datasource::file_path::{
+    CONF_THREAD, EVENT_LOOP_THREAD, FOREGROUND_APP_THREAD, GAME_THREAD, LOG_LEVEL_MONITOR_THREAD,
+};
+
+/// 监控线程的调度设置，未设置的项沿用继承的默认值
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ThreadSched {
+    /// 允许运行的CPU掩码，第n位对应cpu n
+    pub cpu_mask: Option<u64>,
+    /// nice值（-20到19，越小优先级越高）
+    pub nice: Option<i32>,
+}
+
+// 配置中的名称与监控线程的对应关系
+const THREAD_KEYS: &[(&str, &str)] = &[
+    ("gaming", GAME_THREAD),
+    ("config", CONF_THREAD),
+    ("foreground", FOREGROUND_APP_THREAD),
+    ("log_level", LOG_LEVEL_MONITOR_THREAD),
+    ("event_loop", EVENT_LOOP_THREAD),
+];
+
+// 各监控线程的调度设置 (线程名, 设置)，在线程启动时应用
+static THREAD_SCHED: Mutex<Vec<(&'static str, ThreadSched)>> = Mutex::new(Vec::new());
+
+/// 设置监控线程的调度，键为配置中的线程名称，新设置在线程下次启动时生效
+pub fn set_thread_sched(settings: &HashMap<String, ThreadSched>) {
+    let mut applied = Vec::new();
+    for (key, sched) in settings {
+        let Some(&(_, thread)) = THREAD_KEYS.iter().find(|(name, _)| name == key) else {
+            warn!("Unknown thread '{key}' in thread_sched, ignored");
+            continue;
+        };
+
+        let mut sched = *sched;
+        if sched.cpu_mask == Some(0) {
+            warn!("Empty cpu_mask for {key} thread, using inherited affinity");
+            sched.cpu_mask = None;
+        }
+        if let Some(nice) = sched.nice.filter(|nice| !(-20..=19).contains(nice)) {
+            warn!("Invalid nice {nice} for {key} thread (-20 to 19), using inherited priority");
+            sched.nice = None;
+        }
+        if sched != ThreadSched::default() {
+            debug!("Set thread sched: {thread} {sched:?}");
+            applied.push((thread, sched));
+        }
+    }
+    *THREAD_SCHED.lock().unwrap() = applied;
+}
+
+/// 获取已配置的线程调度设置，键为配置中的线程名称
+pub fn get_thread_sched() -> Vec<(&'static str, ThreadSched)> {
+    let settings = THREAD_SCHED.lock().unwrap();
+    THREAD_KEYS
+        .iter()
+        .filter_map(|&(key, thread)| {
+            settings
+                .iter()
+                .find(|&&(name, _)| name == thread)
+                .map(|&(_, sched)| (key, sched))
+        })
+        .collect()
+}
+
+/// 在当前线程中应用该线程的调度设置，失败时只输出警告
+pub fn apply_thread_sched(thread: &str) {
+    let sched = THREAD_SCHED
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|&&(name, _)| name == thread)
+        .map(|&(_, sched)| sched);
+    let Some(sched) = sched else {
+        return;
+    };
+
+    if let Some(mask) = sched.cpu_mask {
+        match set_affinity(mask) {
+            Ok(applied) => info!("{thread}: CPU affinity {applied:#x} (requested {mask:#x})"),
+            Err(e) => warn!("{thread}: failed to set CPU affinity {mask:#x}: {e}"),
+        }
+    }
+    if let Some(nice) = sched.nice {
+        match set_nice(nice) {
+            Ok(applied) => info!("{thread}: nice {applied} (requested {nice})"),
+            Err(e) => warn!("{thread}: failed to set nice {nice}: {e}"),
+        }
+    }
+}
+
+// 设置当前线程的CPU亲和性，返回实际生效的掩码（不存在或离线的CPU会被内核忽略）
+fn set_affinity(mask: u64) -> io::Result<u64> {
+    let size = mem::size_of::<libc::cpu_set_t>();
+    unsafe {
+        let mut set: libc::cpu_set_t = mem::zeroed();
+        for cpu in (0..u64::BITS as usize).filter(|&cpu| mask & (1 << cpu) != 0) {
+            libc::CPU_SET(cpu, &mut set);
+        }
+        if libc::sched_setaffinity(0, size, &set) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut current: libc::cpu_set_t = mem::zeroed();
+        if libc::sched_getaffinity(0, size, &mut current) != 0 {
+            return Ok(mask);
+        }
+        Ok((0..u64::BITS as usize)
+            .filter(|&cpu| libc::CPU_ISSET(cpu, &current))
+            .fold(0, |applied, cpu| applied | (1 << cpu)))
+    }
+}
+
+// 设置当前线程的nice值，返回实际生效的值（无权限提高优先级时会失败）
+fn set_nice(nice: i32) -> io::Result<i32> {
+    unsafe {
+        let tid = libc::gettid() as libc::id_t;
+        if libc::setpriority(libc::PRIO_PROCESS, tid, nice) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(libc::getpriority(libc::PRIO_PROCESS, tid))
+    }
+}